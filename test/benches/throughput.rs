@@ -0,0 +1,198 @@
+//! Execution throughput of the most common transactions and schema reads.
+//!
+//! Criterion is not among the dependencies, so this is a plain timing harness
+//! (`harness = false`). It reports operations per second and heap allocations
+//! per operation, counted by a wrapping global allocator.
+//!
+//! Run with `cargo bench --bench throughput`.
+
+extern crate airplane_service;
+extern crate chrono;
+extern crate exonum;
+extern crate exonum_time;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use chrono::{TimeZone, Utc};
+use exonum::blockchain::Transaction;
+use exonum::crypto::{gen_keypair, PublicKey, SecretKey};
+use exonum::storage::{Database, Fork, MemoryDB};
+use exonum_time::schema::TimeSchema;
+
+use airplane_service::schema::{AirplaneRegistration, AirplaneType, Schema};
+use airplane_service::transactions::{
+    TxBatchRegisterAirplanes, TxRegisterAirplane, TxStartTechnicalCheck,
+};
+use airplane_service::validation::validate_iata_code;
+
+/// Number of airplanes registered before each benchmark.
+const FLEET_SIZE: usize = 1000;
+
+const ITERATIONS: usize = 1000;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Runs `op` for every iteration and prints the throughput and allocations per call.
+fn bench<F: FnMut(usize)>(name: &str, iterations: usize, mut op: F) {
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for i in 0..iterations {
+        op(i);
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+
+    println!(
+        "{:<32} {:>12.0} ops/s {:>10.1} allocs/op",
+        name,
+        iterations as f64 / elapsed.as_secs_f64(),
+        allocations as f64 / iterations as f64,
+    );
+}
+
+/// Returns a fork with the time oracle set and `FLEET_SIZE` airplanes registered.
+fn populated_fork(db: &MemoryDB) -> (Fork, Vec<(PublicKey, SecretKey)>) {
+    let mut fork = db.fork();
+    TimeSchema::new(&mut fork)
+        .time_mut()
+        .set(Utc.timestamp_opt(1_500_000_000, 0).unwrap());
+
+    let keys = (0..FLEET_SIZE)
+        .map(|i| {
+            let (pk, sk) = gen_keypair();
+            let tx = TxRegisterAirplane::new(
+                &pk,
+                &format!("Airplane {}", i),
+                "737",
+                &format!("SN{:06}", i),
+                AirplaneType::Commercial as u8,
+                0,
+                0,
+                0,
+                1,
+                &sk,
+            );
+            tx.execute(&mut fork).unwrap();
+            (pk, sk)
+        })
+        .collect();
+    (fork, keys)
+}
+
+fn main() {
+    let db = MemoryDB::new();
+    let (mut fork, keys) = populated_fork(&db);
+
+    let start_checks: Vec<_> = keys
+        .iter()
+        .take(ITERATIONS)
+        .map(|(pk, sk)| TxStartTechnicalCheck::new(pk, pk, 0, 2, sk))
+        .collect();
+    bench("TxStartTechnicalCheck::execute", ITERATIONS, |i| {
+        fork.checkpoint();
+        start_checks[i].execute(&mut fork).unwrap();
+        fork.rollback();
+    });
+
+    let registrations: Vec<_> = (0..ITERATIONS)
+        .map(|i| {
+            let (pk, sk) = gen_keypair();
+            TxRegisterAirplane::new(
+                &pk,
+                &format!("New {}", i),
+                "A320",
+                &format!("NEW{:06}", i),
+                AirplaneType::Commercial as u8,
+                0,
+                0,
+                0,
+                1,
+                &sk,
+            )
+        })
+        .collect();
+    bench("TxRegisterAirplane::execute", ITERATIONS, |i| {
+        fork.checkpoint();
+        registrations[i].execute(&mut fork).unwrap();
+        fork.rollback();
+    });
+
+    // Registration also writes the serial number to `airplane_serials` to keep serial
+    // numbers unique; timing that write alone shows its share of the cost above.
+    let serials: Vec<_> = registrations
+        .iter()
+        .map(|tx| (tx.serial_number().to_owned(), *tx.pub_key()))
+        .collect();
+    bench("airplane_serials write", ITERATIONS, |i| {
+        fork.checkpoint();
+        Schema::new(&mut fork)
+            .airplane_serials_mut()
+            .put(&serials[i].0, serials[i].1);
+        fork.rollback();
+    });
+
+    let (operator_pk, operator_sk) = gen_keypair();
+    for &batch_size in &[100, 1000] {
+        let registrations: Vec<_> = (0..batch_size)
+            .map(|i| {
+                AirplaneRegistration::new(
+                    &gen_keypair().0,
+                    &format!("Batch {}", i),
+                    "A320",
+                    &format!("BATCH{:06}", i),
+                    AirplaneType::Commercial as u8,
+                )
+            })
+            .collect();
+        let batch =
+            TxBatchRegisterAirplanes::new(&operator_pk, registrations, false, 0, 1, &operator_sk);
+        bench(
+            &format!("TxBatchRegisterAirplanes ({})", batch_size),
+            10,
+            |_| {
+                fork.checkpoint();
+                batch.execute(&mut fork).unwrap();
+                fork.rollback();
+            },
+        );
+    }
+
+    bench("Schema::airplane", ITERATIONS, |i| {
+        let schema = Schema::new(&fork);
+        assert!(schema.airplane(&keys[i % keys.len()].0).is_some());
+    });
+
+    bench("Schema::airplanes (1000 entries)", 100, |_| {
+        let schema = Schema::new(&fork);
+        assert_eq!(schema.airplanes().values().count(), FLEET_SIZE);
+    });
+
+    // `FleetStats::total` reads the `airplane_count` entry instead of counting as above.
+    bench("Schema::airplane_count", ITERATIONS, |_| {
+        let schema = Schema::new(&fork);
+        assert_eq!(schema.airplane_count(), FLEET_SIZE as u64);
+    });
+
+    let codes = ["JFK", "jfk", "J1K", "LHRX"];
+    bench("validate_iata_code", 1_000_000, |i| {
+        let _ = validate_iata_code(codes[i % codes.len()]);
+    });
+}
@@ -0,0 +1,60 @@
+//! Restores airplanes exported by `v1/airplanes/export` into the database of a
+//! stopped node.
+//!
+//! Run with `cargo run --example import -- <database path> <snapshot.json> [service name]`.
+//!
+//! The import writes to the database directly instead of going through
+//! transactions, so consensus never sees it. Every node has to import the same
+//! snapshot while the network is stopped; a node that skips it, or imports a
+//! different file, computes another state hash and cannot agree on new blocks.
+//! To keep a partial import from mixing with live data, the tool refuses to
+//! import into a fleet that already has airplanes.
+
+extern crate airplane_service;
+extern crate exonum;
+extern crate serde_json;
+
+use exonum::storage::{Database, DbOptions, RocksDB};
+use std::env;
+use std::fs::File;
+use std::process;
+
+use airplane_service::schema::{Airplane, Schema};
+use airplane_service::service::SERVICE_NAME;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 || args.len() > 4 {
+        eprintln!(
+            "Usage: {} <database path> <snapshot.json> [service name]",
+            args[0]
+        );
+        process::exit(2);
+    }
+    let service_name = args.get(3).map_or(SERVICE_NAME, String::as_str);
+
+    let file = File::open(&args[2]).expect("Unable to open the snapshot");
+    let airplanes: Vec<Airplane> =
+        serde_json::from_reader(file).expect("Unable to parse the snapshot");
+
+    let db = RocksDB::open(&args[1], &DbOptions::default()).expect("Unable to open the database");
+    let mut fork = db.fork();
+    let imported = {
+        let mut schema = Schema::for_service(&mut fork, service_name);
+        let existing = schema.airplane_keys().len();
+        if existing > 0 {
+            eprintln!(
+                "The fleet of {} already has {} airplanes; import into a database without them.",
+                service_name, existing
+            );
+            process::exit(1);
+        }
+        schema
+            .import_airplanes(&airplanes)
+            .expect("Unable to import the snapshot")
+    };
+    db.merge(fork.into_patch())
+        .expect("Unable to write to the database");
+
+    println!("Imported {} airplanes into {}.", imported, service_name);
+}
@@ -0,0 +1,105 @@
+//! Migrates a database with airplanes stored in the version 1 layout and checks
+//! that every value survives the upgrade.
+//!
+//! Run with `cargo run --example migrate`.
+
+extern crate airplane_service;
+extern crate chrono;
+extern crate exonum;
+extern crate exonum_time;
+
+use chrono::{Duration, TimeZone, Utc};
+use exonum::blockchain::Transaction;
+use exonum::crypto::{gen_keypair, PublicKey};
+use exonum::storage::{Database, MapIndex, MemoryDB};
+use exonum_time::schema::TimeSchema;
+
+use airplane_service::schema::{AirplaneState, AirplaneV1, Schema, SCHEMA_VERSION};
+use airplane_service::transactions::TxStartTechnicalCheck;
+
+fn main() {
+    let db = MemoryDB::new();
+    let heating_start = Utc.timestamp_opt(1_500_000_000, 0).unwrap();
+    let now = heating_start + Duration::hours(1);
+    let mut keys = Vec::new();
+
+    // Old nodes stored up to 65 535 seconds of heating in a plain `MapIndex`.
+    let old_airplanes: Vec<AirplaneV1> = vec![
+        (AirplaneState::WaitingForFlight, 0),
+        (AirplaneState::HeatingEngine, u16::MAX),
+        (AirplaneState::Flying, 600),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (state, heating))| {
+        let (pub_key, secret_key) = gen_keypair();
+        keys.push((pub_key, secret_key));
+        AirplaneV1::new(
+            &pub_key,
+            &format!("Airplane {}", i),
+            u8::from(state),
+            &state.to_string(),
+            heating_start,
+            heating,
+        )
+    })
+    .collect();
+
+    let mut fork = db.fork();
+    // The time service keeps the consensus time, from which registrations are renewed.
+    TimeSchema::new(&mut fork).time_mut().set(now);
+    {
+        let mut index: MapIndex<_, PublicKey, AirplaneV1> = MapIndex::new("airplanes", &mut fork);
+        for airplane in &old_airplanes {
+            index.put(airplane.pub_key(), airplane.clone());
+        }
+    }
+    db.merge(fork.into_patch()).unwrap();
+
+    let mut fork = db.fork();
+    let migrated = Schema::new(&mut fork).migrate().expect("migration failed");
+    db.merge(fork.into_patch()).unwrap();
+    assert_eq!(migrated, old_airplanes.len() as u64);
+
+    let snapshot = db.snapshot();
+    let schema = Schema::new(&snapshot);
+    assert_eq!(schema.schema_version(), SCHEMA_VERSION);
+    assert_eq!(schema.airplane_count(), old_airplanes.len() as u64);
+    let validity = Duration::days(i64::from(
+        schema.service_config().registration_validity_days(),
+    ));
+    for old in &old_airplanes {
+        let airplane = schema.airplane(old.pub_key()).expect("airplane was lost");
+        assert_eq!(airplane.name(), old.name());
+        assert_eq!(airplane.state_number(), old.state_number());
+        assert_eq!(airplane.state_str(), old.state_str());
+        assert_eq!(
+            airplane.engine_heating_start_time(),
+            old.engine_heating_start_time()
+        );
+        assert_eq!(
+            airplane.engine_heating_time_seconds(),
+            u32::from(old.engine_heating_time_seconds())
+        );
+        assert_eq!(airplane.registration_expiry(), now + validity);
+        assert_eq!(schema.find_by_name(old.name()), Some(airplane));
+    }
+
+    // A migrated airplane that was waiting for a flight can start a technical check.
+    let (ref pub_key, ref secret_key) = keys[0];
+    let mut fork = db.fork();
+    TxStartTechnicalCheck::new(pub_key, pub_key, 0, 1, secret_key)
+        .execute(&mut fork)
+        .expect("migrated airplane cannot start a technical check");
+    let airplane = Schema::new(&fork).airplane(pub_key).unwrap();
+    assert_eq!(airplane.state().unwrap(), AirplaneState::TechnicalCheck);
+
+    // The version entry makes a second run a no-op.
+    let mut fork = db.fork();
+    assert_eq!(Schema::new(&mut fork).migrate().unwrap(), 0);
+
+    println!(
+        "Migrated {} airplanes to schema version {}.",
+        migrated, SCHEMA_VERSION
+    );
+}
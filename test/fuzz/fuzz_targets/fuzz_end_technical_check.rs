@@ -0,0 +1,112 @@
+//! Ends a technical check with fuzzed `engine_heating_time_seconds` and `is_airplane_ok`,
+//! then tries to take off after a fuzzed delay. Execution must never panic: every input
+//! either succeeds or fails with an `ExecutionError`.
+//!
+//! Input layout, little-endian, missing bytes read as zero:
+//! bytes 0..4 heating seconds, byte 4 bit 0 `is_airplane_ok`, bytes 5..9 seconds waited
+//! before `TxStartFlying`.
+//!
+//! Run from `test/fuzz` with a nightly toolchain and `cargo install cargo-fuzz`:
+//! `cargo fuzz run fuzz_end_technical_check corpus/fuzz_end_technical_check`.
+
+#![no_main]
+
+extern crate airplane_service;
+extern crate chrono;
+extern crate exonum;
+extern crate exonum_time;
+#[macro_use]
+extern crate libfuzzer_sys;
+
+use chrono::{Duration, TimeZone, Utc};
+use exonum::blockchain::Transaction;
+use exonum::crypto::gen_keypair;
+use exonum::storage::{Database, Fork, MemoryDB};
+use exonum_time::schema::TimeSchema;
+
+use airplane_service::schema::{Airplane, AirplaneType, Schema};
+use airplane_service::transactions::{
+    TxAssignRunway, TxEndTechnicalCheck, TxRegisterAirplane, TxStartFlying,
+    TxStartTechnicalCheck,
+};
+
+/// Reads a little-endian `u32` at `offset`, treating missing bytes as zero.
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0u8; 4];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = data.get(offset + i).cloned().unwrap_or(0);
+    }
+    u32::from_le_bytes(bytes)
+}
+
+/// Executes `tx`, discarding its changes if it fails, as the blockchain would.
+fn execute<T: Transaction>(fork: &mut Fork, tx: &T) -> bool {
+    fork.checkpoint();
+    match tx.execute(fork) {
+        Ok(()) => {
+            fork.commit();
+            true
+        }
+        Err(_) => {
+            fork.rollback();
+            false
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let heating_seconds = read_u32(data, 0);
+    let is_airplane_ok = data.get(4).map_or(false, |flags| flags & 1 == 1);
+    let wait_seconds = read_u32(data, 5);
+
+    let mut fork = MemoryDB::new().fork();
+    let start = Utc.timestamp_opt(1_500_000_000, 0).unwrap();
+    TimeSchema::new(&mut fork).time_mut().set(start);
+
+    let (pk, sk) = gen_keypair();
+    let register = TxRegisterAirplane::new(
+        &pk,
+        "Boeing",
+        "737",
+        "SN0001",
+        AirplaneType::Commercial as u8,
+        0,
+        0,
+        0,
+        1,
+        &sk,
+    );
+    assert!(execute(&mut fork, &register));
+    {
+        // Stands in for `TxRenewAirworthinessCertificate`, which needs an authority.
+        let mut schema = Schema::new(&mut fork);
+        let airplane = schema.airplane(&pk).unwrap();
+        schema.airplanes_mut().put(
+            &pk,
+            Airplane::builder_from(&airplane)
+                .airworthiness_cert_expiry(start + Duration::days(365))
+                .build(),
+        );
+    }
+    assert!(execute(&mut fork, &TxAssignRunway::new(&pk, &pk, "09L", 0, 2, &sk)));
+    assert!(execute(&mut fork, &TxStartTechnicalCheck::new(&pk, &pk, 0, 3, &sk)));
+
+    let end_check = TxEndTechnicalCheck::new(
+        &pk, &pk,
+        is_airplane_ok,
+        heating_seconds,
+        "Fuzzed check",
+        0,
+        4,
+        &sk,
+    );
+    end_check.verify();
+    if !execute(&mut fork, &end_check) {
+        return;
+    }
+
+    TimeSchema::new(&mut fork)
+        .time_mut()
+        .set(start + Duration::seconds(i64::from(wait_seconds)));
+    execute(&mut fork, &TxStartFlying::new(&pk, &pk, 0, 5, &sk));
+});
@@ -0,0 +1,59 @@
+//! Registers an airplane under a fuzzed name. Execution must never panic, and a
+//! registration may only succeed for a name accepted by `is_valid_airplane_name`.
+//!
+//! The input is used as the name; inputs that are not UTF-8 are skipped.
+//!
+//! Run from `test/fuzz` with a nightly toolchain and `cargo install cargo-fuzz`:
+//! `cargo fuzz run fuzz_register_airplane corpus/fuzz_register_airplane`.
+
+#![no_main]
+
+extern crate airplane_service;
+extern crate chrono;
+extern crate exonum;
+extern crate exonum_time;
+#[macro_use]
+extern crate libfuzzer_sys;
+
+use chrono::{TimeZone, Utc};
+use exonum::blockchain::Transaction;
+use exonum::crypto::gen_keypair;
+use exonum::storage::{Database, MemoryDB};
+use exonum_time::schema::TimeSchema;
+
+use airplane_service::schema::{is_valid_airplane_name, AirplaneType, Schema};
+use airplane_service::transactions::TxRegisterAirplane;
+
+fuzz_target!(|data: &[u8]| {
+    let name = match std::str::from_utf8(data) {
+        Ok(name) => name,
+        Err(_) => return,
+    };
+
+    let mut fork = MemoryDB::new().fork();
+    TimeSchema::new(&mut fork)
+        .time_mut()
+        .set(Utc.timestamp_opt(1_500_000_000, 0).unwrap());
+
+    let (pk, sk) = gen_keypair();
+    let tx = TxRegisterAirplane::new(
+        &pk,
+        name,
+        "737",
+        "SN0001",
+        AirplaneType::Commercial as u8,
+        0,
+        0,
+        0,
+        1,
+        &sk,
+    );
+    let verified = tx.verify();
+    let registered = tx.execute(&mut fork).is_ok();
+
+    assert!(!registered || is_valid_airplane_name(name));
+    assert!(!verified || is_valid_airplane_name(name));
+    if registered {
+        assert_eq!(Schema::new(&fork).airplane(&pk).unwrap().name(), name);
+    }
+});
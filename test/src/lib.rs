@@ -1,54 +1,461 @@
+extern crate actix_web;
+extern crate bytes;
 extern crate chrono;
 #[macro_use]
 extern crate exonum;
 extern crate exonum_time;
 #[macro_use]
 extern crate failure;
+extern crate futures;
 #[macro_use]
 extern crate log;
+#[macro_use]
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
+#[macro_use]
 extern crate serde_json;
 
 pub mod transactions {
+    // `transactions!` generates a positional constructor with one argument per field.
+    #![allow(clippy::too_many_arguments)]
+
     use exonum::{
-        blockchain::{ExecutionError, ExecutionResult, Transaction},
-        crypto::PublicKey,
-        messages::Message,
-        storage::Fork,
+        blockchain::{
+            ExecutionError, ExecutionResult, Schema as CoreSchema, Transaction, TransactionSet,
+        },
+        crypto::{self, CryptoHash, Hash, PublicKey, SecretKey, SIGNATURE_LENGTH},
+        encoding::{serialize::json::ExonumJson, Error as EncodingError, Field},
+        messages::{Message, RawTransaction, ServiceMessage},
+        storage::{Fork, Snapshot},
     };
 
-    use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+    use chrono::{DateTime, Duration, Utc};
     use exonum_time::schema::TimeSchema;
+    use serde_json::{self, Value};
+
+    use std::collections::BTreeMap;
+    use std::convert::TryFrom;
 
-    use schema::{Airplane, AirplaneState, Schema};
+    use schema::{
+        airplane_with_state, is_valid_airplane_name, normalize_airplane_name, Airplane,
+        AirplaneEvent, AirplaneEventType, AirplaneRegistration, AirplaneServiceConfig,
+        AirplaneState, AirplaneType, CertificationAuthority, CompletedRoute, FlightLogEntry,
+        Incident, MaintenanceRecord, OperatorQuota, RepairFlag, RunwaySchema, ScheduledCheck,
+        Schema, WeatherReport, CRITICAL_INCIDENT_WINDOW_DAYS, EPOCH, MAX_CRITICAL_INCIDENTS,
+        MAX_EMERGENCY_REASON_LEN, MAX_FUEL_LEVEL_PERCENT, MAX_INCIDENT_SEVERITY,
+        MAX_METADATA_FIELDS, MAX_METADATA_KEY_LEN, MAX_METADATA_VALUE_LEN, MAX_PRIORITY,
+        MIN_INCIDENT_SEVERITY, MIN_PRIORITY,
+    };
     use service::SERVICE_ID;
+    use validation::validate_iata_code;
 
     #[derive(Debug, Fail)]
-    #[repr(u8)]
     pub enum Error {
         #[fail(display = "Airplane already exists")]
-        AirplaneAlreadyExists = 0,
+        AirplaneAlreadyExists,
 
-        #[fail(display = "Airplane does not exist")]
-        AirplaneDoesNotExist = 1,
+        #[fail(display = "Airplane {} does not exist", _0)]
+        AirplaneDoesNotExist(PublicKey),
 
-        #[fail(display = "Transaction is not allowed")]
-        TransactionIsNotAllowed = 2,
+        #[fail(
+            display = "Cannot transition from {}; required one of {:?}",
+            found, required
+        )]
+        InvalidStateTransition {
+            found: AirplaneState,
+            required: &'static [AirplaneState],
+        },
 
         #[fail(display = "Engine is not heated")]
-        EngineIsNotHeated = 3,
+        EngineIsNotHeated,
+
+        #[fail(display = "Airplane is decommissioned")]
+        AirplaneIsDecommissioned,
+
+        #[fail(display = "Engine heating time is out of the allowed range")]
+        HeatingTimeOutOfRange,
+
+        #[fail(display = "Airplane name is already taken")]
+        NameAlreadyTaken,
+
+        #[fail(display = "Serial number must be 4 to 20 alphanumeric characters")]
+        InvalidSerialNumber,
+
+        #[fail(display = "Cooling period since the last flight has not elapsed")]
+        CoolingPeriodNotElapsed,
+
+        #[fail(display = "No runway is assigned to the airplane")]
+        NoRunwayAssigned,
+
+        #[fail(display = "Fuel level is below the minimum required for flight")]
+        InsufficientFuel,
+
+        #[fail(display = "Current time is not available from the time oracle")]
+        TimeNotAvailable,
+
+        #[fail(display = "Fleet has reached the configured maximum size")]
+        FleetCapacityReached,
+
+        #[fail(display = "Technical check deadline has passed")]
+        MaintenanceDeadlineExceeded,
+
+        #[fail(display = "Airplane name must be 1 to 64 bytes long")]
+        InvalidAirplaneName,
+
+        #[fail(display = "Stored airplane key does not match the transaction key")]
+        PubKeyMismatch,
+
+        #[fail(display = "Nonce must be greater than the last nonce used for the key")]
+        InvalidNonce,
+
+        #[fail(display = "Service configuration is malformed or out of range")]
+        InvalidConfig,
+
+        #[fail(display = "Transaction is older than the configured expiry")]
+        TransactionExpired,
+
+        #[fail(display = "IATA airport code must be three uppercase letters")]
+        InvalidIataCode,
+
+        #[fail(display = "Cargo weight exceeds the maximum takeoff weight")]
+        OverMaxTakeoffWeight,
+
+        #[fail(display = "Passenger count exceeds the airplane's capacity")]
+        ExceedsPassengerCapacity,
+
+        #[fail(display = "Too many transactions for the key in this block")]
+        RateLimitExceeded,
+
+        #[fail(display = "Airworthiness certificate has expired")]
+        AirworthinessCertificateExpired,
+
+        #[fail(display = "Certification authority is not registered or was revoked")]
+        UnknownAuthority,
+
+        #[fail(display = "Visibility at the home airport is below the minimum for takeoff")]
+        WeatherBelowMinimums,
+
+        #[fail(display = "Airplane registration has expired")]
+        RegistrationExpired,
+
+        #[fail(display = "Registering key has reached its airplane quota")]
+        OperatorQuotaExceeded,
+
+        #[fail(display = "Airplane state is invalid")]
+        InvalidState,
+
+        #[fail(display = "New key already has a registered airplane")]
+        NewKeyAlreadyRegistered,
+
+        #[fail(display = "Emergency reason is empty or too long")]
+        InvalidEmergencyReason,
+
+        #[fail(display = "Serial number is already registered")]
+        SerialNumberAlreadyTaken,
+
+        #[fail(display = "Transaction is not signed by the airplane or its operator")]
+        NotOwnerOrOperator,
+
+        #[fail(display = "Airplane has no operator assigned")]
+        OperatorNotAssigned,
+
+        #[fail(display = "Engine heating has not exceeded the maximum wait time")]
+        HeatingNotExpired,
+
+        #[fail(display = "Runway is occupied by another airplane")]
+        RunwayOccupied,
+
+        #[fail(display = "Airplane already has a runway assigned")]
+        RunwayAlreadyAssigned,
+
+        #[fail(display = "Fuel level must not exceed 100 percent")]
+        InvalidFuelLevel,
+
+        #[fail(display = "Scheduled time must be in the future and after other scheduled checks")]
+        InvalidScheduledTime,
+
+        #[fail(display = "No scheduled technical check is due")]
+        ScheduledCheckNotDue,
+
+        #[fail(display = "Transaction is not signed by the configured safety authority")]
+        NotAdmin,
+
+        #[fail(display = "Airplane is grounded by the safety authority")]
+        AirplaneIsGrounded,
+
+        #[fail(display = "Incident severity must be from 1 to 5")]
+        InvalidIncidentSeverity,
+
+        #[fail(display = "Too many critical incidents were reported in the last 30 days")]
+        TooManyCriticalIncidents,
+
+        #[fail(display = "Transition from {} to {} is not allowed", from, to)]
+        TransitionNotAllowed {
+            from: AirplaneState,
+            to: AirplaneState,
+        },
+
+        #[fail(display = "Airplane type must be Commercial, Cargo, Military or Private")]
+        InvalidAirplaneType,
+
+        #[fail(display = "Priority must be from 1 to 5")]
+        InvalidPriority,
+
+        #[fail(display = "Airplane is already queued for a technical check")]
+        AlreadyInCheckQueue,
+
+        #[fail(display = "Airplane is not next in the check queue or no check slot is free")]
+        QueuedCheckNotReady,
+
+        #[fail(display = "Metadata key must be 1 to 64 and value at most 256 characters long")]
+        InvalidMetadataField,
+
+        #[fail(display = "Airplane already has the maximum number of metadata fields")]
+        TooManyMetadataFields,
+
+        #[fail(display = "Only commercial airplanes can fly with passengers")]
+        PassengersNotAllowed,
+
+        #[fail(display = "Airplane has pending repairs")]
+        RepairsRequired,
+
+        #[fail(display = "Repair flag must be Engine, Avionics, Hydraulics or Fuselage")]
+        InvalidRepairFlag,
+
+        #[fail(display = "Planned departure must be before the planned arrival")]
+        InvalidFlightPlan,
+
+        #[fail(display = "Flight plan overlaps another plan of the airplane")]
+        FlightPlanConflict,
+
+        #[fail(display = "Airplane is inactive until it is reactivated")]
+        AirplaneIsInactive,
+
+        #[fail(display = "Transaction is not signed by the service key of a validator")]
+        NotValidator,
+
+        #[fail(
+            display = "Critical incidents are reported by the safety authority, the operator or a certification authority"
+        )]
+        UntrustedIncidentReporter,
+    }
+
+    impl Error {
+        /// Numeric code reported in `ExecutionError`.
+        pub fn code(&self) -> u8 {
+            match *self {
+                Error::AirplaneAlreadyExists => 0,
+                Error::AirplaneDoesNotExist(..) => 1,
+                Error::InvalidStateTransition { .. } => 2,
+                Error::EngineIsNotHeated => 3,
+                Error::AirplaneIsDecommissioned => 4,
+                Error::HeatingTimeOutOfRange => 5,
+                Error::NameAlreadyTaken => 6,
+                Error::InvalidSerialNumber => 7,
+                Error::CoolingPeriodNotElapsed => 8,
+                Error::NoRunwayAssigned => 9,
+                Error::InsufficientFuel => 10,
+                Error::TimeNotAvailable => 11,
+                Error::FleetCapacityReached => 12,
+                Error::MaintenanceDeadlineExceeded => 13,
+                Error::InvalidAirplaneName => 14,
+                Error::PubKeyMismatch => 15,
+                Error::InvalidNonce => 16,
+                Error::InvalidConfig => 17,
+                Error::TransactionExpired => 18,
+                Error::InvalidIataCode => 19,
+                Error::OverMaxTakeoffWeight => 20,
+                Error::ExceedsPassengerCapacity => 21,
+                Error::RateLimitExceeded => 22,
+                Error::AirworthinessCertificateExpired => 23,
+                Error::UnknownAuthority => 24,
+                Error::WeatherBelowMinimums => 25,
+                Error::RegistrationExpired => 26,
+                Error::OperatorQuotaExceeded => 27,
+                Error::InvalidState => 28,
+                Error::NewKeyAlreadyRegistered => 29,
+                Error::InvalidEmergencyReason => 30,
+                Error::SerialNumberAlreadyTaken => 31,
+                Error::NotOwnerOrOperator => 32,
+                Error::OperatorNotAssigned => 33,
+                Error::HeatingNotExpired => 34,
+                Error::RunwayOccupied => 35,
+                Error::RunwayAlreadyAssigned => 36,
+                Error::InvalidFuelLevel => 37,
+                Error::InvalidScheduledTime => 38,
+                Error::ScheduledCheckNotDue => 39,
+                Error::NotAdmin => 40,
+                Error::AirplaneIsGrounded => 41,
+                Error::InvalidIncidentSeverity => 42,
+                Error::TooManyCriticalIncidents => 43,
+                Error::TransitionNotAllowed { .. } => 44,
+                Error::InvalidAirplaneType => 45,
+                Error::InvalidPriority => 46,
+                Error::AlreadyInCheckQueue => 47,
+                Error::QueuedCheckNotReady => 48,
+                Error::InvalidMetadataField => 49,
+                Error::TooManyMetadataFields => 50,
+                Error::PassengersNotAllowed => 51,
+                Error::RepairsRequired => 52,
+                Error::InvalidRepairFlag => 53,
+                Error::InvalidFlightPlan => 54,
+                Error::FlightPlanConflict => 55,
+                Error::AirplaneIsInactive => 56,
+                Error::NotValidator => 57,
+                Error::UntrustedIncidentReporter => 58,
+            }
+        }
     }
 
     impl From<Error> for ExecutionError {
         fn from(value: Error) -> ExecutionError {
             let description = format!("{}", value);
-            ExecutionError::with_description(value as u8, description)
+            ExecutionError::with_description(value.code(), description)
         }
     }
 
-    transactions! {
+    /// Declares the transactions with `transactions!`, along with `body_fields` and
+    /// `check_body_field`, which let the API name the body field at fault in a malformed
+    /// message, and the conversions of messages to and from other service IDs.
+    macro_rules! checked_transactions {
+        {
+            pub $transaction_set:ident {
+                const SERVICE_ID = $service_id:expr;
+
+                $(
+                    $(#[$tx_attr:meta])*
+                    struct $name:ident {
+                        $(
+                            $(#[$field_attr:meta])*
+                            $field_name:ident : $field_type:ty
+                        ),* $(,)*
+                    }
+                )*
+            }
+        } => {
+            transactions! {
+                pub $transaction_set {
+                    const SERVICE_ID = $service_id;
+
+                    $(
+                        $(#[$tx_attr])*
+                        struct $name {
+                            $(
+                                $(#[$field_attr])*
+                                $field_name: $field_type,
+                            )*
+                        }
+                    )*
+                }
+            }
+
+            /// Names of the body fields of the transaction with `message_id`, in
+            /// declaration order; empty for an unknown `message_id`.
+            pub fn body_fields(message_id: u16) -> &'static [&'static str] {
+                $(
+                    if message_id == <$name as ServiceMessage>::MESSAGE_ID {
+                        return &[$(stringify!($field_name)),*];
+                    }
+                )*
+                &[]
+            }
+
+            /// Checks `value` against the type of `field` in the body of the transaction
+            /// with `message_id`, returning why it does not fit.
+            pub fn check_body_field(
+                message_id: u16,
+                field: &str,
+                value: &Value,
+            ) -> Result<(), String> {
+                $(
+                    if message_id == <$name as ServiceMessage>::MESSAGE_ID {
+                        $(
+                            if field == stringify!($field_name) {
+                                return check_body_value::<$field_type>(value);
+                            }
+                        )*
+                    }
+                )*
+                Err("unknown field".to_owned())
+            }
+
+            impl $transaction_set {
+                /// Parses a message of the service instance with `service_id`.
+                /// `tx_from_raw` only accepts `SERVICE_ID`, which `transactions!` writes
+                /// into every message it creates.
+                pub fn from_raw_for_service(
+                    raw: RawTransaction,
+                    service_id: u16,
+                ) -> Result<Self, EncodingError> {
+                    if raw.service_id() != service_id {
+                        Err(EncodingError::IncorrectServiceId { service_id })?
+                    }
+                    let tx = Self::tx_from_raw(with_service_id(&raw, SERVICE_ID))?;
+                    Ok(match tx {
+                        $($transaction_set::$name(_) => $transaction_set::$name($name { raw }),)*
+                    })
+                }
+
+                /// Copy of the message with `service_id` in the header and the original
+                /// signature, for messages signed for `service_id` but parsed by the
+                /// deserializers of `SERVICE_ID`.
+                pub fn with_service_id(&self, service_id: u16) -> Self {
+                    let raw = with_service_id(self.raw(), service_id);
+                    match *self {
+                        $($transaction_set::$name(_) => $transaction_set::$name($name { raw }),)*
+                    }
+                }
+
+                /// Copy of the message for the service instance with `service_id`, signed
+                /// with `secret_key` since the signature covers the service ID.
+                pub fn signed_for_service(&self, service_id: u16, secret_key: &SecretKey) -> Self {
+                    let raw = sign_raw(with_service_id(self.raw(), service_id), secret_key);
+                    match *self {
+                        $($transaction_set::$name(_) => $transaction_set::$name($name { raw }),)*
+                    }
+                }
+
+                pub fn raw(&self) -> &RawTransaction {
+                    match *self {
+                        $($transaction_set::$name(ref tx) => tx.raw(),)*
+                    }
+                }
+            }
+        };
+    }
+
+    /// Copy of `raw` with `service_id` in the header and the original signature, which
+    /// no longer matches unless `service_id` is the one that was signed.
+    fn with_service_id(raw: &RawTransaction, service_id: u16) -> RawTransaction {
+        let mut bytes = raw.as_ref().to_vec();
+        bytes[4..6].copy_from_slice(&service_id.to_le_bytes());
+        RawTransaction::from_vec(bytes)
+    }
+
+    /// Replaces the signature of `raw` with one made by `secret_key`.
+    fn sign_raw(raw: RawTransaction, secret_key: &SecretKey) -> RawTransaction {
+        let mut bytes = raw.as_ref().to_vec();
+        let signed_len = bytes.len() - SIGNATURE_LENGTH;
+        let signature = crypto::sign(&bytes[..signed_len], secret_key);
+        bytes[signed_len..].copy_from_slice(signature.as_ref());
+        RawTransaction::from_vec(bytes)
+    }
+
+    fn check_body_value<'a, T: ExonumJson + Field<'a>>(value: &Value) -> Result<(), String> {
+        let size = T::field_size();
+        let mut buffer = vec![0; size as usize];
+        T::deserialize_field(value, &mut buffer, 0, size).map_err(|e| e.to_string())
+    }
+
+    // Every transaction ends with `created_at_height`, the blockchain height when it was
+    // created (see `Schema::check_not_expired`), and a `nonce`, which must exceed the last
+    // nonce used by the signing key; see `Schema::use_nonce`. Transactions submitted by
+    // validators from `after_commit` are checked against the validator keys instead.
+    //
+    // Transactions that the airplane's operator may sign on its behalf name the signing key
+    // in `signer`, which is the airplane key itself when the owner signs.
+    checked_transactions! {
         pub AirplaneTransactions {
             const SERVICE_ID = SERVICE_ID;
 
@@ -56,386 +463,8695 @@ pub mod transactions {
                 pub_key: &PublicKey,
 
                 name: &str,
+
+                model: &str,
+
+                serial_number: &str,
+
+                /// `AirplaneType` as a number.
+                airplane_type: u8,
+
+                /// `0` if unknown, in which case `TxStartFlying` does not check the cargo weight.
+                max_takeoff_weight_kg: u32,
+
+                max_passenger_capacity: u16,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxBatchRegisterAirplanes {
+                // Key of the fleet operator submitting the batch.
+                operator_key: &PublicKey,
+
+                registrations: Vec<AirplaneRegistration>,
+
+                // Silently skip entries whose key already has an airplane.
+                skip_existing: bool,
+
+                created_at_height: u64,
+
+                nonce: u64,
             }
 
             struct TxStartTechnicalCheck {
                 pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
             }
 
             struct TxEndTechnicalCheck {
                 pub_key: &PublicKey,
 
+                signer: &PublicKey,
+
                 is_airplane_ok: bool,
 
                 // Total time needed for heating.
-                engine_heating_time_seconds: u16,
+                engine_heating_time_seconds: u32,
+
+                technician_notes: &str,
+
+                created_at_height: u64,
+
+                nonce: u64,
             }
 
             struct TxStartFlying {
                 pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
             }
 
             struct TxEndFlying {
                 pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
             }
-        }
-    }
 
-    impl Transaction for TxRegisterAirplane {
-        fn verify(&self) -> bool {
-            self.verify_signature(self.pub_key())
-        }
+            struct TxStartMaintenance {
+                pub_key: &PublicKey,
 
-        fn execute(&self, view: &mut Fork) -> ExecutionResult {
-            let mut schema = Schema::new(view);
+                signer: &PublicKey,
 
-            if schema.airplane(self.pub_key()).is_none() {
-                let airplane = Airplane::new(
-                    self.pub_key(),
-                    self.name(),
-                    AirplaneState::WaitingForFlight as u8,
-                    AirplaneState::WaitingForFlight.to_string(),
-                    DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp(0, 0), Utc),
-                    0,
-                );
+                created_at_height: u64,
 
-                schema.airplanes_mut().put(self.pub_key(), airplane);
-                Ok(())
-            } else {
-                Err(Error::AirplaneAlreadyExists)?
+                nonce: u64,
             }
-        }
-    }
 
-    impl Transaction for TxStartTechnicalCheck {
-        fn verify(&self) -> bool {
-            self.verify_signature(self.pub_key())
-        }
+            struct TxEndMaintenance {
+                pub_key: &PublicKey,
 
-        fn execute(&self, view: &mut Fork) -> ExecutionResult {
-            let mut schema = Schema::new(view);
+                signer: &PublicKey,
 
-            let airplane = schema.airplane(self.pub_key());
-            if airplane.is_none() {
-                Err(Error::AirplaneDoesNotExist)?
-            } else {
-                let airplane = airplane.unwrap();
-                if airplane.state_number() != AirplaneState::WaitingForFlight as u8 {
-                    Err(Error::TransactionIsNotAllowed)?
-                } else {
-                    let new_airplane = Airplane::new(
-                        self.pub_key(),
-                        airplane.name(),
-                        AirplaneState::TechnicalCheck as u8,
-                        AirplaneState::TechnicalCheck.to_string(),
-                        DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp(0, 0), Utc),
-                        0,
-                    );
+                passed: bool,
 
-                    schema.airplanes_mut().put(self.pub_key(), new_airplane);
+                created_at_height: u64,
 
-                    Ok(())
-                }
+                nonce: u64,
             }
-        }
-    }
-
-    impl Transaction for TxEndTechnicalCheck {
-        fn verify(&self) -> bool {
-            self.verify_signature(self.pub_key())
-        }
 
-        fn execute(&self, view: &mut Fork) -> ExecutionResult {
-            let current_time = TimeSchema::new(&view)
-                .time()
-                .get()
-                .expect("Unexpected error occured while receiving time");
+            struct TxTransferOwnership {
+                pub_key: &PublicKey,
 
-            let mut schema = Schema::new(view);
+                new_pub_key: &PublicKey,
 
-            let airplane = schema.airplane(self.pub_key());
-            if airplane.is_none() {
-                Err(Error::AirplaneDoesNotExist)?
-            } else {
-                let airplane = airplane.unwrap();
-                if airplane.state_number() != AirplaneState::TechnicalCheck as u8 {
-                    Err(Error::TransactionIsNotAllowed)?
-                } else {
-                    let airplane_state: AirplaneState;
-                    let engine_heating_time_seconds: u16;
-                    let start_time: DateTime<Utc>;
+                created_at_height: u64,
 
-                    if self.is_airplane_ok() {
-                        airplane_state = AirplaneState::HeatingEngine;
-                        engine_heating_time_seconds = self.engine_heating_time_seconds();
-                        start_time = current_time;
-                    } else {
-                        airplane_state = AirplaneState::WaitingForFlight;
-                        engine_heating_time_seconds = 0;
-                        start_time =
-                            DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp(0, 0), Utc);
-                    }
+                nonce: u64,
+            }
 
-                    let new_airplane = Airplane::new(
-                        self.pub_key(),
-                        airplane.name(),
-                        airplane_state as u8,
-                        airplane_state.to_string(),
-                        start_time,
-                        engine_heating_time_seconds,
-                    );
+            struct TxDecommissionAirplane {
+                pub_key: &PublicKey,
 
-                    schema.airplanes_mut().put(self.pub_key(), new_airplane);
+                created_at_height: u64,
 
-                    Ok(())
-                }
+                nonce: u64,
             }
-        }
-    }
 
-    impl Transaction for TxStartFlying {
-        fn verify(&self) -> bool {
-            self.verify_signature(self.pub_key())
-        }
+            struct TxUpdateAirplaneName {
+                pub_key: &PublicKey,
 
-        fn execute(&self, view: &mut Fork) -> ExecutionResult {
-            let current_time = TimeSchema::new(&view)
-                .time()
-                .get()
-                .expect("Unexpected error occured while receiving time");
-            let mut schema = Schema::new(view);
+                signer: &PublicKey,
 
-            let airplane = schema.airplane(self.pub_key());
-            if airplane.is_none() {
-                Err(Error::AirplaneDoesNotExist)?
-            } else {
-                let airplane = airplane.unwrap();
-                if airplane.state_number() != AirplaneState::HeatingEngine as u8 {
-                    Err(Error::TransactionIsNotAllowed)?
-                } else {
-                    let start_time = airplane.engine_heating_start_time();
-                    let substract = current_time - start_time;
-                    let min_durarion =
-                        Duration::seconds(airplane.engine_heating_time_seconds() as i64);
-                    if substract < min_durarion {
-                        Err(Error::EngineIsNotHeated)?
-                    } else {
-                        let new_airplane = Airplane::new(
-                            self.pub_key(),
-                            airplane.name(),
-                            AirplaneState::Flying as u8,
-                            AirplaneState::Flying.to_string(),
-                            DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp(0, 0), Utc),
-                            0,
-                        );
+                new_name: &str,
 
-                        schema.airplanes_mut().put(self.pub_key(), new_airplane);
+                created_at_height: u64,
 
-                        Ok(())
-                    }
-                }
+                nonce: u64,
             }
-        }
-    }
 
-    impl Transaction for TxEndFlying {
-        fn verify(&self) -> bool {
-            self.verify_signature(self.pub_key())
-        }
+            struct TxEmergencyLanding {
+                pub_key: &PublicKey,
 
-        fn execute(&self, view: &mut Fork) -> ExecutionResult {
-            let mut schema = Schema::new(view);
+                signer: &PublicKey,
 
-            let airplane = schema.airplane(self.pub_key());
-            if airplane.is_none() {
-                Err(Error::AirplaneDoesNotExist)?
-            } else {
-                let airplane = airplane.unwrap();
-                if airplane.state_number() != AirplaneState::Flying as u8 {
-                    Err(Error::TransactionIsNotAllowed)?
-                } else {
-                    let new_airplane = Airplane::new(
-                        self.pub_key(),
-                        airplane.name(),
-                        AirplaneState::WaitingForFlight as u8,
-                        AirplaneState::WaitingForFlight.to_string(),
-                        DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp(0, 0), Utc),
-                        0,
-                    );
+                reason: &str,
 
-                    schema.airplanes_mut().put(self.pub_key(), new_airplane);
+                created_at_height: u64,
 
-                    Ok(())
-                }
+                nonce: u64,
             }
-        }
-    }
-}
 
-pub mod schema {
-    use exonum::{
-        crypto::PublicKey,
-        storage::{Fork, MapIndex, Snapshot},
-    };
+            struct TxClearEmergency {
+                pub_key: &PublicKey,
 
-    use chrono::{DateTime, Utc};
+                signer: &PublicKey,
 
-    #[derive(Debug, Copy, Clone)]
-    #[repr(u8)]
-    pub enum AirplaneState {
-        WaitingForFlight = 0,
+                created_at_height: u64,
 
-        TechnicalCheck = 1,
+                nonce: u64,
+            }
 
-        HeatingEngine = 2,
+            struct TxAssignOperator {
+                pub_key: &PublicKey,
 
-        Flying = 3,
-    }
+                operator_pub_key: &PublicKey,
 
-    impl AirplaneState {
-        pub fn to_string(&self) -> &str {
-            match *self {
-                AirplaneState::WaitingForFlight => "Waiting for flight",
-                AirplaneState::TechnicalCheck => "Technical check",
-                AirplaneState::HeatingEngine => "Heating engine",
-                AirplaneState::Flying => "Flying",
+                created_at_height: u64,
+
+                nonce: u64,
             }
-        }
-    }
 
-    encoding_struct! {
-        struct Airplane {
-            pub_key: &PublicKey,
+            struct TxUnassignOperator {
+                pub_key: &PublicKey,
 
-            name: &str,
+                created_at_height: u64,
 
-            state_number: u8,
+                nonce: u64,
+            }
 
-            state_str: &str,
+            struct TxCancelFlight {
+                pub_key: &PublicKey,
 
-            engine_heating_start_time: DateTime<Utc>,
+                signer: &PublicKey,
 
-            /// Total time needed for heating.
-            engine_heating_time_seconds: u16,
-        }
-    }
+                reason: &str,
 
-    #[derive(Debug)]
-    pub struct Schema<T> {
-        view: T,
-    }
+                created_at_height: u64,
 
-    impl<T: AsRef<dyn Snapshot>> Schema<T> {
-        pub fn new(view: T) -> Self {
-            Schema { view }
-        }
+                nonce: u64,
+            }
 
-        pub fn airplanes(&self) -> MapIndex<&dyn Snapshot, PublicKey, Airplane> {
-            MapIndex::new("airplanes", self.view.as_ref())
-        }
+            struct TxAssignRunway {
+                airplane_pub_key: &PublicKey,
 
-        pub fn airplane(&self, pub_key: &PublicKey) -> Option<Airplane> {
-            self.airplanes().get(pub_key)
-        }
+                signer: &PublicKey,
+
+                runway_name: &str,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxReleaseRunway {
+                airplane_pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxRecordFlightRoute {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                departure_iata: &str,
+
+                arrival_iata: &str,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxUpdateEngineHeatingTime {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                new_heating_time_seconds: u32,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxRefuelAirplane {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                new_level: u8,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxScheduleTechnicalCheck {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                scheduled_at: DateTime<Utc>,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Submitted by validators from `after_commit` once a scheduled check is due.
+            // Like `TxExpireHeating`, it is checked against the validator keys, not a nonce.
+            struct TxStartScheduledCheck {
+                pub_key: &PublicKey,
+
+                service_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Submitted by validators from `after_commit` for airplanes stuck in heating.
+            // `nonce` is not checked: the airplane's nonces are its owner's, and
+            // `created_at_height` already tells apart submissions from different blocks.
+            struct TxExpireHeating {
+                pub_key: &PublicKey,
+
+                service_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxGroundAirplane {
+                target_pub_key: &PublicKey,
+
+                reason: &str,
+
+                // Must match `admin_pub_key` in the service configuration.
+                admin_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxUngroundAirplane {
+                target_pub_key: &PublicKey,
+
+                admin_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxRequestMaintenanceExtension {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                additional_seconds: u32,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxReportIncident {
+                airplane_pub_key: &PublicKey,
+
+                /// From `MIN_INCIDENT_SEVERITY` to `MAX_INCIDENT_SEVERITY`.
+                severity: u8,
+
+                description: &str,
+
+                // Any crew member may report; the reporter is not the airplane's owner.
+                // Critical incidents are limited to the reporters allowed by
+                // `verify_critical_incident_reporter`.
+                reporter_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxUpdateServiceConfig {
+                // Must match `admin_pub_key` in the current service configuration.
+                admin_pub_key: &PublicKey,
+
+                /// Complete `AirplaneServiceConfig` as JSON.
+                config_json: &str,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxAssignPriority {
+                pub_key: &PublicKey,
+
+                /// From `MIN_PRIORITY` to `MAX_PRIORITY`.
+                priority: u8,
+
+                // Must match `admin_pub_key` in the service configuration.
+                admin_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Submitted by validators from `after_commit` once a technical check slot is free.
+            // Like `TxExpireHeating`, it is checked against the validator keys, not a nonce.
+            struct TxStartQueuedCheck {
+                pub_key: &PublicKey,
+
+                service_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxLoadCargo {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                weight_kg: u32,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Unloading more than is on board empties the airplane.
+            struct TxUnloadCargo {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                weight_kg: u32,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Adds the field or replaces its value.
+            struct TxSetMetadataField {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                key: &str,
+
+                value: &str,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Removing a missing field succeeds without changes.
+            struct TxRemoveMetadataField {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                key: &str,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Unlike `TxStartTechnicalCheck`, the check only starts once the safety authority
+            // approves it with `TxApproveCheckStart`.
+            struct TxRequestTechnicalCheck {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxApproveCheckStart {
+                target_pub_key: &PublicKey,
+
+                // Must match `admin_pub_key` in the service configuration.
+                admin_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxDenyCheckRequest {
+                target_pub_key: &PublicKey,
+
+                // Must match `admin_pub_key` in the service configuration.
+                admin_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Cannot exceed `max_passenger_capacity`.
+            struct TxSetPassengerCount {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                count: u16,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Signed by a registered certification authority; allowed in any state
+            // except `Decommissioned` and `Inactive`.
+            struct TxRenewAirworthinessCertificate {
+                pub_key: &PublicKey,
+
+                expiry: DateTime<Utc>,
+
+                // Must be an active entry of `Schema::authorities`.
+                authority_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Registers a certification authority, or reactivates a revoked one.
+            struct TxRegisterAuthority {
+                pub_key: &PublicKey,
+
+                name: &str,
+
+                // Must match `admin_pub_key` in the service configuration.
+                admin_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxRevokeAuthority {
+                pub_key: &PublicKey,
+
+                // Must match `admin_pub_key` in the service configuration.
+                admin_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Flags a `RepairFlag` system for repair; allowed in any state except
+            // `Decommissioned`.
+            struct TxMarkRepairRequired {
+                pub_key: &PublicKey,
+
+                repair_flag: u8,
+
+                // Must match `admin_pub_key` in the service configuration.
+                admin_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxClearRepair {
+                pub_key: &PublicKey,
+
+                repair_flag: u8,
+
+                // Must match `admin_pub_key` in the service configuration.
+                admin_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Admin-signed; the nonce is taken from `admin_pub_key`.
+            struct TxRecordWeatherConditions {
+                iata: &str,
+
+                visibility_meters: u32,
+
+                wind_speed_knots: u16,
+
+                // Must match `admin_pub_key` in the service configuration.
+                admin_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Allowed in any state except `Decommissioned` and `Inactive`.
+            struct TxSetHomeAirport {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                iata: &str,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Cannot overlap another plan of the same airplane.
+            struct TxPlanFlight {
+                airplane_pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                departure_iata: &str,
+
+                arrival_iata: &str,
+
+                planned_departure: DateTime<Utc>,
+
+                planned_arrival: DateTime<Utc>,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Signed by a registered certification authority; allowed in any state
+            // except `Decommissioned` and `Inactive`.
+            struct TxRenewRegistration {
+                pub_key: &PublicKey,
+
+                new_expiry: DateTime<Utc>,
+
+                // Must be an active entry of `Schema::authorities`.
+                authority_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Takes a waiting airplane out of service until `TxReactivateAirplane`.
+            struct TxDeactivateAirplane {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            struct TxReactivateAirplane {
+                pub_key: &PublicKey,
+
+                signer: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+
+            // Creates or updates the quota of `operator_pub_key`, keeping its current count.
+            struct TxSetOperatorQuota {
+                operator_pub_key: &PublicKey,
+
+                max_airplanes: u32,
+
+                // Must match `admin_pub_key` in the service configuration.
+                admin_pub_key: &PublicKey,
+
+                created_at_height: u64,
+
+                nonce: u64,
+            }
+        }
+    }
+
+    /// Highest message ID in `AirplaneTransactions`; IDs are assigned in declaration order,
+    /// so this must name the last declared transaction.
+    pub const MAX_MESSAGE_ID: u16 = <TxSetOperatorQuota as ServiceMessage>::MESSAGE_ID;
+
+    impl AirplaneTransactions {
+        /// Returns the `TransactionDescription` of the wrapped transaction, if it has one.
+        pub fn description(&self) -> Option<Value> {
+            match *self {
+                AirplaneTransactions::TxRegisterAirplane(ref tx) => Some(tx.description()),
+                AirplaneTransactions::TxStartTechnicalCheck(ref tx) => Some(tx.description()),
+                AirplaneTransactions::TxEndTechnicalCheck(ref tx) => Some(tx.description()),
+                AirplaneTransactions::TxStartFlying(ref tx) => Some(tx.description()),
+                AirplaneTransactions::TxEndFlying(ref tx) => Some(tx.description()),
+                _ => None,
+            }
+        }
+
+        /// Name of the transaction struct wrapped by this variant.
+        pub fn type_name(&self) -> &'static str {
+            match *self {
+                AirplaneTransactions::TxRegisterAirplane(_) => "TxRegisterAirplane",
+                AirplaneTransactions::TxBatchRegisterAirplanes(_) => "TxBatchRegisterAirplanes",
+                AirplaneTransactions::TxStartTechnicalCheck(_) => "TxStartTechnicalCheck",
+                AirplaneTransactions::TxEndTechnicalCheck(_) => "TxEndTechnicalCheck",
+                AirplaneTransactions::TxStartFlying(_) => "TxStartFlying",
+                AirplaneTransactions::TxEndFlying(_) => "TxEndFlying",
+                AirplaneTransactions::TxStartMaintenance(_) => "TxStartMaintenance",
+                AirplaneTransactions::TxEndMaintenance(_) => "TxEndMaintenance",
+                AirplaneTransactions::TxTransferOwnership(_) => "TxTransferOwnership",
+                AirplaneTransactions::TxDecommissionAirplane(_) => "TxDecommissionAirplane",
+                AirplaneTransactions::TxUpdateAirplaneName(_) => "TxUpdateAirplaneName",
+                AirplaneTransactions::TxEmergencyLanding(_) => "TxEmergencyLanding",
+                AirplaneTransactions::TxClearEmergency(_) => "TxClearEmergency",
+                AirplaneTransactions::TxAssignOperator(_) => "TxAssignOperator",
+                AirplaneTransactions::TxUnassignOperator(_) => "TxUnassignOperator",
+                AirplaneTransactions::TxCancelFlight(_) => "TxCancelFlight",
+                AirplaneTransactions::TxAssignRunway(_) => "TxAssignRunway",
+                AirplaneTransactions::TxReleaseRunway(_) => "TxReleaseRunway",
+                AirplaneTransactions::TxRecordFlightRoute(_) => "TxRecordFlightRoute",
+                AirplaneTransactions::TxUpdateEngineHeatingTime(_) => "TxUpdateEngineHeatingTime",
+                AirplaneTransactions::TxRefuelAirplane(_) => "TxRefuelAirplane",
+                AirplaneTransactions::TxScheduleTechnicalCheck(_) => "TxScheduleTechnicalCheck",
+                AirplaneTransactions::TxStartScheduledCheck(_) => "TxStartScheduledCheck",
+                AirplaneTransactions::TxExpireHeating(_) => "TxExpireHeating",
+                AirplaneTransactions::TxGroundAirplane(_) => "TxGroundAirplane",
+                AirplaneTransactions::TxUngroundAirplane(_) => "TxUngroundAirplane",
+                AirplaneTransactions::TxRequestMaintenanceExtension(_) => {
+                    "TxRequestMaintenanceExtension"
+                }
+                AirplaneTransactions::TxReportIncident(_) => "TxReportIncident",
+                AirplaneTransactions::TxUpdateServiceConfig(_) => "TxUpdateServiceConfig",
+                AirplaneTransactions::TxAssignPriority(_) => "TxAssignPriority",
+                AirplaneTransactions::TxStartQueuedCheck(_) => "TxStartQueuedCheck",
+                AirplaneTransactions::TxLoadCargo(_) => "TxLoadCargo",
+                AirplaneTransactions::TxUnloadCargo(_) => "TxUnloadCargo",
+                AirplaneTransactions::TxSetMetadataField(_) => "TxSetMetadataField",
+                AirplaneTransactions::TxRemoveMetadataField(_) => "TxRemoveMetadataField",
+                AirplaneTransactions::TxRequestTechnicalCheck(_) => "TxRequestTechnicalCheck",
+                AirplaneTransactions::TxApproveCheckStart(_) => "TxApproveCheckStart",
+                AirplaneTransactions::TxDenyCheckRequest(_) => "TxDenyCheckRequest",
+                AirplaneTransactions::TxSetPassengerCount(_) => "TxSetPassengerCount",
+                AirplaneTransactions::TxRenewAirworthinessCertificate(_) => {
+                    "TxRenewAirworthinessCertificate"
+                }
+                AirplaneTransactions::TxRegisterAuthority(_) => "TxRegisterAuthority",
+                AirplaneTransactions::TxRevokeAuthority(_) => "TxRevokeAuthority",
+                AirplaneTransactions::TxMarkRepairRequired(_) => "TxMarkRepairRequired",
+                AirplaneTransactions::TxClearRepair(_) => "TxClearRepair",
+                AirplaneTransactions::TxRecordWeatherConditions(_) => "TxRecordWeatherConditions",
+                AirplaneTransactions::TxSetHomeAirport(_) => "TxSetHomeAirport",
+                AirplaneTransactions::TxPlanFlight(_) => "TxPlanFlight",
+                AirplaneTransactions::TxRenewRegistration(_) => "TxRenewRegistration",
+                AirplaneTransactions::TxDeactivateAirplane(_) => "TxDeactivateAirplane",
+                AirplaneTransactions::TxReactivateAirplane(_) => "TxReactivateAirplane",
+                AirplaneTransactions::TxSetOperatorQuota(_) => "TxSetOperatorQuota",
+            }
+        }
+    }
+
+    /// Summary of a transaction with human-readable field labels, served by
+    /// `v1/transactions/description`.
+    pub trait TransactionDescription {
+        fn description(&self) -> Value;
+    }
+
+    impl TransactionDescription for TxRegisterAirplane {
+        fn description(&self) -> Value {
+            json!({
+                "action": "register",
+                "airplane_name": self.name(),
+                "owner": self.pub_key(),
+                "model": self.model(),
+                "serial_number": self.serial_number(),
+                "airplane_type": AirplaneType::try_from(self.airplane_type())
+                    .map(|airplane_type| airplane_type.to_string())
+                    .ok(),
+            })
+        }
+    }
+
+    impl TransactionDescription for TxStartTechnicalCheck {
+        fn description(&self) -> Value {
+            json!({
+                "action": "start_technical_check",
+                "airplane": self.pub_key(),
+            })
+        }
+    }
+
+    impl TransactionDescription for TxEndTechnicalCheck {
+        fn description(&self) -> Value {
+            json!({
+                "action": "end_technical_check",
+                "airplane": self.pub_key(),
+                "passed": self.is_airplane_ok(),
+                "engine_heating_time_seconds": self.engine_heating_time_seconds(),
+                "technician_notes": self.technician_notes(),
+            })
+        }
+    }
+
+    impl TransactionDescription for TxStartFlying {
+        fn description(&self) -> Value {
+            json!({
+                "action": "start_flying",
+                "airplane": self.pub_key(),
+            })
+        }
+    }
+
+    impl TransactionDescription for TxEndFlying {
+        fn description(&self) -> Value {
+            json!({
+                "action": "end_flying",
+                "airplane": self.pub_key(),
+            })
+        }
+    }
+
+    /// Checks that `tx` is signed either by the airplane key or by its assigned operator,
+    /// and returns the signing key.
+    ///
+    /// `verify` of such a transaction only checks that either `pub_key` or the `signer` it
+    /// names signed it; whether `signer` operates the airplane is known from storage only.
+    /// A transaction signed by an unrelated key, or by an operator unassigned since, thus
+    /// passes `verify` and is committed, but fails here with `NotOwnerOrOperator` without
+    /// changing any state. `signer` is not checked when the airplane key signed.
+    fn verify_owner_or_operator<M, T>(
+        tx: &M,
+        schema: &Schema<T>,
+        pub_key: &PublicKey,
+        signer: &PublicKey,
+    ) -> Result<PublicKey, Error>
+    where
+        M: Message,
+        T: AsRef<dyn Snapshot>,
+    {
+        if tx.verify_signature(pub_key) {
+            return Ok(*pub_key);
+        }
+        match schema.airplane_operator(pub_key) {
+            Some(operator) if operator == *signer => Ok(operator),
+            _ => Err(Error::NotOwnerOrOperator),
+        }
+    }
+
+    /// Summarizes the flight of `airplane`, which is still `Flying`, ending at `end_time`.
+    fn flight_log_entry(
+        airplane: &Airplane,
+        end_time: DateTime<Utc>,
+        duration_seconds: u64,
+    ) -> FlightLogEntry {
+        FlightLogEntry::new(
+            airplane.flight_count() + 1,
+            airplane.flight_start_time(),
+            end_time,
+            duration_seconds,
+            airplane.current_departure_iata(),
+            airplane.current_arrival_iata(),
+            // Fuel is only changed by `TxRefuelAirplane`, which is not allowed in flight.
+            0,
+        )
+    }
+
+    /// Lands a `Flying` airplane at `end_time` and moves it to `next_state`: counts and
+    /// logs the flight, completes its route, records the event and frees its runway.
+    fn end_flight(
+        schema: &mut Schema<&mut Fork>,
+        pub_key: &PublicKey,
+        tx_hash: &Hash,
+        end_time: DateTime<Utc>,
+        next_state: AirplaneState,
+    ) -> ExecutionResult {
+        let mut completed_route = None;
+        let mut log_entry = None;
+        schema.transition_airplane(pub_key, tx_hash, AirplaneState::Flying, |airplane| {
+            let flight_seconds = (end_time - airplane.flight_start_time()).num_seconds() as u64;
+            if !airplane.current_departure_iata().is_empty() {
+                completed_route = Some(CompletedRoute::new(
+                    airplane.current_departure_iata(),
+                    airplane.current_arrival_iata(),
+                    flight_seconds,
+                ));
+            }
+            log_entry = Some(flight_log_entry(&airplane, end_time, flight_seconds));
+
+            Ok(Airplane::builder_from(&airplane)
+                .state(next_state)
+                .clear_heating()
+                .flight_count(airplane.flight_count() + 1)
+                .passenger_count(0)
+                .accumulated_flight_seconds(
+                    airplane
+                        .accumulated_flight_seconds()
+                        .saturating_add(flight_seconds),
+                )
+                .clear_flight_start()
+                .last_flight_end_time(end_time)
+                .clear_route()
+                .build())
+        })?;
+
+        if let Some(route) = completed_route {
+            schema.completed_routes_mut(pub_key).push(route);
+        }
+        if let Some(entry) = log_entry {
+            schema.flight_log_mut(pub_key).push(entry);
+        }
+        schema.events_mut(pub_key).push(AirplaneEvent::new(
+            AirplaneEventType::FlightEnded as u8,
+            false,
+            end_time,
+        ));
+        schema.runway_schema_mut().release_runway(pub_key);
+
+        Ok(())
+    }
+
+    /// Replaces the metadata of an airplane that is not decommissioned with the result of
+    /// `update`, which may change the fields in place.
+    fn update_metadata<F>(
+        schema: &mut Schema<&mut Fork>,
+        pub_key: &PublicKey,
+        update: F,
+    ) -> ExecutionResult
+    where
+        F: FnOnce(&mut BTreeMap<String, String>) -> Result<(), Error>,
+    {
+        let airplane = match schema.airplane(pub_key) {
+            Some(airplane) => airplane,
+            None => Err(Error::AirplaneDoesNotExist(*pub_key))?,
+        };
+        if airplane.state()? == AirplaneState::Decommissioned {
+            Err(Error::AirplaneIsDecommissioned)?
+        }
+        if airplane.state()? == AirplaneState::Inactive {
+            Err(Error::AirplaneIsInactive)?
+        }
+
+        let mut fields = airplane.metadata_fields();
+        update(&mut fields)?;
+        let metadata = serde_json::to_string(&fields).expect("string map serializes to JSON");
+        schema.airplanes_mut().put(
+            pub_key,
+            Airplane::builder_from(&airplane)
+                .metadata(&metadata)
+                .build(),
+        );
+        Ok(())
+    }
+
+    /// Implemented for every transaction; the airplane state is read from the schema of
+    /// the service instance the message is addressed to.
+    trait LoggedExecute: Message {
+        /// Runs the `execute` body of the transaction `name`, logging its outcome together
+        /// with the state change of the airplane at `pub_key`.
+        fn logged_execute<F>(
+            &self,
+            view: &mut Fork,
+            name: &str,
+            pub_key: &PublicKey,
+            execute: F,
+        ) -> ExecutionResult
+        where
+            F: FnOnce(&mut Fork) -> ExecutionResult,
+        {
+            let service_id = self.raw().service_id();
+            let state = |view: &Fork| {
+                Schema::for_service_id(view, service_id)
+                    .airplane(pub_key)
+                    .and_then(|airplane| airplane.state().ok())
+            };
+            let old_state = state(view);
+            info!("[{}] airplane={:?} executing", name, pub_key);
+
+            let result = execute(view);
+            match result {
+                Ok(()) => match (old_state, state(view)) {
+                    (Some(old_state), Some(new_state)) if old_state != new_state => info!(
+                        "[{}] airplane={:?} transitioned {:?} -> {:?}",
+                        name, pub_key, old_state, new_state
+                    ),
+                    (None, Some(new_state)) => info!(
+                        "[{}] airplane={:?} created in {:?}",
+                        name, pub_key, new_state
+                    ),
+                    _ => info!("[{}] airplane={:?} executed", name, pub_key),
+                },
+                // The time oracle commits its first value in the first blocks; any later
+                // absence points to a misconfigured network.
+                Err(ref e) if *e == ExecutionError::from(Error::TimeNotAvailable) => {
+                    error!("[{}] airplane={:?} rejected: {:?}", name, pub_key, e)
+                }
+                Err(ref e) => warn!("[{}] airplane={:?} rejected: {:?}", name, pub_key, e),
+            }
+            result
+        }
+    }
+
+    impl<T: Message> LoggedExecute for T {}
+
+    /// Checks that `admin_pub_key` is the safety authority key from the service configuration.
+    fn verify_admin<T: AsRef<dyn Snapshot>>(
+        schema: &Schema<T>,
+        admin_pub_key: &PublicKey,
+    ) -> Result<(), Error> {
+        match schema.service_config().admin() {
+            Some(ref admin) if admin == admin_pub_key => Ok(()),
+            _ => Err(Error::NotAdmin),
+        }
+    }
+
+    /// Checks that `authority_pub_key` belongs to an active certification authority.
+    fn verify_authority<T: AsRef<dyn Snapshot>>(
+        schema: &Schema<T>,
+        authority_pub_key: &PublicKey,
+    ) -> Result<(), Error> {
+        match schema.authority(authority_pub_key) {
+            Some(ref authority) if authority.is_active() => Ok(()),
+            _ => Err(Error::UnknownAuthority),
+        }
+    }
+
+    /// Checks that `reporter_pub_key` may report a critical incident against the airplane:
+    /// critical incidents block technical checks, so anonymous keys cannot report them.
+    fn verify_critical_incident_reporter<T: AsRef<dyn Snapshot>>(
+        schema: &Schema<T>,
+        airplane_pub_key: &PublicKey,
+        reporter_pub_key: &PublicKey,
+    ) -> Result<(), Error> {
+        if verify_admin(schema, reporter_pub_key).is_ok()
+            || schema.airplane_operator(airplane_pub_key) == Some(*reporter_pub_key)
+            || verify_authority(schema, reporter_pub_key).is_ok()
+        {
+            Ok(())
+        } else {
+            Err(Error::UntrustedIncidentReporter)
+        }
+    }
+
+    /// Checks that `service_key` is the service key of a validator in the actual
+    /// configuration.
+    fn verify_validator(view: &Fork, service_key: &PublicKey) -> Result<(), Error> {
+        let is_validator = CoreSchema::new(view)
+            .actual_configuration()
+            .validator_keys
+            .iter()
+            .any(|keys| keys.service_key == *service_key);
+        if !is_validator {
+            Err(Error::NotValidator)?
+        }
+        Ok(())
+    }
+
+    /// Moves the airplane from `from_state` (`WaitingForFlight`, or `AwaitingCheckApproval`
+    /// for an approved request) to `TechnicalCheck` once the cooling period since its last
+    /// flight has elapsed.
+    fn start_technical_check(
+        schema: &mut Schema<&mut Fork>,
+        pub_key: &PublicKey,
+        tx_hash: &Hash,
+        from_state: AirplaneState,
+        current_time: DateTime<Utc>,
+    ) -> ExecutionResult {
+        let config = schema.service_config();
+        let window_start = current_time - Duration::days(CRITICAL_INCIDENT_WINDOW_DAYS);
+        if schema.critical_incidents_since(pub_key, window_start) > MAX_CRITICAL_INCIDENTS {
+            Err(Error::TooManyCriticalIncidents)?
+        }
+
+        schema.transition_airplane(pub_key, tx_hash, from_state, |airplane| {
+            if airplane.required_repairs_bitmask() != 0 {
+                Err(Error::RepairsRequired)?
+            }
+            if airplane.registration_expiry() <= current_time {
+                Err(Error::RegistrationExpired)?
+            }
+            let cooling_period = Duration::seconds(config.cooling_period_seconds() as i64);
+            if current_time - airplane.last_flight_end_time() < cooling_period {
+                Err(Error::CoolingPeriodNotElapsed)?
+            }
+
+            let deadline = if config.technical_check_window_seconds() == 0 {
+                EPOCH
+            } else {
+                current_time + Duration::seconds(config.technical_check_window_seconds() as i64)
+            };
+
+            Ok(Airplane::builder_from(&airplane)
+                .state(AirplaneState::TechnicalCheck)
+                .clear_heating()
+                .clear_flight_start()
+                .technical_check_deadline(deadline)
+                .build())
+        })?;
+
+        schema.events_mut(pub_key).push(AirplaneEvent::new(
+            AirplaneEventType::TechnicalCheckStarted as u8,
+            false,
+            current_time,
+        ));
+
+        Ok(())
+    }
+
+    /// Puts a waiting airplane in the check queue of its priority. `TxStartQueuedCheck`
+    /// starts the check once a slot is free.
+    fn queue_technical_check(
+        schema: &mut Schema<&mut Fork>,
+        pub_key: &PublicKey,
+        current_time: DateTime<Utc>,
+    ) -> ExecutionResult {
+        let airplane = match schema.airplane(pub_key) {
+            Some(airplane) => airplane,
+            None => Err(Error::AirplaneDoesNotExist(*pub_key))?,
+        };
+        match AirplaneState::try_from(airplane.state_number())? {
+            AirplaneState::WaitingForFlight => {}
+            AirplaneState::Decommissioned => Err(Error::AirplaneIsDecommissioned)?,
+            AirplaneState::Inactive => Err(Error::AirplaneIsInactive)?,
+            found => Err(Error::InvalidStateTransition {
+                found,
+                required: &[AirplaneState::WaitingForFlight],
+            })?,
+        }
+        if airplane.required_repairs_bitmask() != 0 {
+            Err(Error::RepairsRequired)?
+        }
+        if airplane.registration_expiry() <= current_time {
+            Err(Error::RegistrationExpired)?
+        }
+        schema.enqueue_check(pub_key, airplane.priority())?;
+        Ok(())
+    }
+
+    impl Transaction for TxRegisterAirplane {
+        fn verify(&self) -> bool {
+            is_valid_airplane_name(self.name())
+                && AirplaneType::try_from(self.airplane_type()).is_ok()
+                && self.verify_signature(self.pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxRegisterAirplane", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.pub_key())?;
+
+                schema.register_airplane(
+                    self.pub_key(),
+                    self.name(),
+                    self.model(),
+                    self.serial_number(),
+                    self.airplane_type(),
+                    self.max_takeoff_weight_kg(),
+                    self.max_passenger_capacity(),
+                    &self.hash(),
+                    current_time,
+                )?;
+                schema.count_against_operator_quota(self.pub_key(), self.pub_key())
+            })
+        }
+    }
+
+    impl Transaction for TxBatchRegisterAirplanes {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.operator_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(
+                view,
+                "TxBatchRegisterAirplanes",
+                self.operator_key(),
+                |view| {
+                    let current_time = TimeSchema::new(&view)
+                        .time()
+                        .get()
+                        .ok_or(Error::TimeNotAvailable)?;
+                    let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                    schema.use_nonce(self.operator_key(), self.nonce())?;
+                    schema.check_not_expired(self.created_at_height())?;
+                    schema.count_transaction(self.operator_key())?;
+
+                    // Any error aborts the transaction, which rolls back the whole batch.
+                    for registration in self.registrations() {
+                        if self.skip_existing() && schema.airplane(registration.pub_key()).is_some()
+                        {
+                            continue;
+                        }
+
+                        schema.register_airplane(
+                            registration.pub_key(),
+                            registration.name(),
+                            registration.model(),
+                            registration.serial_number(),
+                            registration.airplane_type(),
+                            0,
+                            0,
+                            &self.hash(),
+                            current_time,
+                        )?;
+                        schema.count_against_operator_quota(
+                            self.operator_key(),
+                            registration.pub_key(),
+                        )?;
+                    }
+
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    impl Transaction for TxStartTechnicalCheck {
+        // Either the airplane or the key it names as `signer` may sign. A valid signature only
+        // proves who sent the message; `execute` rejects signers that do not currently operate
+        // the airplane (see `verify_owner_or_operator`), so such messages cost a block slot
+        // but change nothing. The nonce of the signing key still prevents their replay.
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxStartTechnicalCheck", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                if schema.service_config().technical_check_slots().is_some() {
+                    return queue_technical_check(&mut schema, self.pub_key(), current_time);
+                }
+                start_technical_check(
+                    &mut schema,
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::WaitingForFlight,
+                    current_time,
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxEndTechnicalCheck {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxEndTechnicalCheck", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+                let config = schema.service_config();
+
+                let (airplane_state, engine_heating_time_seconds, start_time) =
+                    if self.is_airplane_ok() {
+                        (
+                            AirplaneState::HeatingEngine,
+                            self.engine_heating_time_seconds(),
+                            current_time,
+                        )
+                    } else {
+                        (AirplaneState::WaitingForFlight, 0, EPOCH)
+                    };
+
+                schema.transition_airplane_from_any(
+                    self.pub_key(),
+                    &self.hash(),
+                    &[
+                        AirplaneState::TechnicalCheck,
+                        AirplaneState::TechnicalCheckExtended,
+                    ],
+                    |airplane| {
+                        let deadline = airplane.technical_check_deadline();
+                        if deadline != EPOCH && current_time > deadline {
+                            Err(Error::MaintenanceDeadlineExceeded)?
+                        }
+                        if self.is_airplane_ok()
+                            && !config.allows_heating_time(engine_heating_time_seconds)
+                        {
+                            Err(Error::HeatingTimeOutOfRange)?
+                        }
+
+                        Ok(airplane.transition_state_with_time(
+                            airplane_state,
+                            start_time,
+                            engine_heating_time_seconds,
+                        ))
+                    },
+                )?;
+
+                schema
+                    .maintenance_records_mut(self.pub_key())
+                    .push(MaintenanceRecord::new(
+                        self.pub_key(),
+                        current_time,
+                        self.is_airplane_ok(),
+                        self.technician_notes(),
+                        engine_heating_time_seconds,
+                    ));
+
+                let mut events = schema.events_mut(self.pub_key());
+                events.push(AirplaneEvent::new(
+                    AirplaneEventType::TechnicalCheckEnded as u8,
+                    self.is_airplane_ok(),
+                    current_time,
+                ));
+                if self.is_airplane_ok() {
+                    events.push(AirplaneEvent::new(
+                        AirplaneEventType::HeatingStarted as u8,
+                        false,
+                        current_time,
+                    ));
+                }
+
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxStartFlying {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxStartFlying", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                let has_runway = RunwaySchema::for_service_id(&view, self.raw().service_id())
+                    .assigned_runway(self.pub_key())
+                    .is_some();
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+                let config = schema.service_config();
+                let weather = schema
+                    .airplane(self.pub_key())
+                    .and_then(|airplane| schema.weather_report(airplane.home_airport()));
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::HeatingEngine,
+                    |airplane| {
+                        let start_time = airplane.engine_heating_start_time();
+                        let substract = current_time - start_time;
+                        let min_durarion =
+                            Duration::seconds(airplane.engine_heating_time_seconds() as i64);
+                        let type_min_duration = Duration::seconds(i64::from(
+                            config.min_heating_seconds_for(airplane.kind()?),
+                        ));
+                        if substract < min_durarion || substract < type_min_duration {
+                            Err(Error::EngineIsNotHeated)?
+                        }
+                        if airplane.fuel_level_percent() < config.min_fuel_percent_for_flight() {
+                            Err(Error::InsufficientFuel)?
+                        }
+                        if !has_runway {
+                            Err(Error::NoRunwayAssigned)?
+                        }
+                        if airplane.max_takeoff_weight_kg() != 0
+                            && airplane.cargo_weight_kg() > airplane.max_takeoff_weight_kg()
+                        {
+                            Err(Error::OverMaxTakeoffWeight)?
+                        }
+                        if airplane.passenger_count() != 0
+                            && airplane.kind()? != AirplaneType::Commercial
+                        {
+                            Err(Error::PassengersNotAllowed)?
+                        }
+                        if airplane.airworthiness_cert_expiry() <= current_time {
+                            Err(Error::AirworthinessCertificateExpired)?
+                        }
+                        match weather {
+                            Some(ref report)
+                                if report.visibility_meters() < config.min_visibility_meters() =>
+                            {
+                                Err(Error::WeatherBelowMinimums)?
+                            }
+                            _ => {}
+                        }
+
+                        Ok(Airplane::builder_from(&airplane)
+                            .state(AirplaneState::Flying)
+                            .clear_heating()
+                            .flight_start_time(current_time)
+                            .build())
+                    },
+                )?;
+
+                schema.events_mut(self.pub_key()).push(AirplaneEvent::new(
+                    AirplaneEventType::FlightStarted as u8,
+                    false,
+                    current_time,
+                ));
+
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxEndFlying {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxEndFlying", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                end_flight(
+                    &mut schema,
+                    self.pub_key(),
+                    &self.hash(),
+                    current_time,
+                    AirplaneState::WaitingForFlight,
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxStartMaintenance {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxStartMaintenance", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                end_flight(
+                    &mut schema,
+                    self.pub_key(),
+                    &self.hash(),
+                    current_time,
+                    AirplaneState::Maintenance,
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxEndMaintenance {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxEndMaintenance", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                let airplane_state = if self.passed() {
+                    AirplaneState::WaitingForFlight
+                } else {
+                    AirplaneState::Grounded
+                };
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::Maintenance,
+                    |airplane| Ok(airplane.transition_state(airplane_state)),
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxTransferOwnership {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxTransferOwnership", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.pub_key())?;
+
+                let airplane = schema.airplane(self.pub_key());
+                if airplane.is_none() {
+                    Err(Error::AirplaneDoesNotExist(*self.pub_key()))?
+                } else {
+                    let airplane = airplane.unwrap();
+                    match AirplaneState::try_from(airplane.state_number())? {
+                        AirplaneState::WaitingForFlight => {
+                            if schema.airplane(self.new_pub_key()).is_some() {
+                                Err(Error::NewKeyAlreadyRegistered)?
+                            }
+
+                            let new_airplane = Airplane::builder_from(&airplane)
+                                .pub_key(self.new_pub_key())
+                                .build();
+
+                            schema.airplanes_mut().put(self.new_pub_key(), new_airplane);
+                            schema.airplanes_mut().remove(self.pub_key());
+                            schema.replace_airplane_key(self.pub_key(), self.new_pub_key());
+                            schema.airplane_names_mut().put(
+                                &normalize_airplane_name(airplane.name()),
+                                *self.new_pub_key(),
+                            );
+                            schema
+                                .airplane_serials_mut()
+                                .put(&airplane.serial_number().to_owned(), *self.new_pub_key());
+                            // A nonce the new key has already used must stay used.
+                            let nonce = schema
+                                .last_used_nonce(self.pub_key())
+                                .max(schema.last_used_nonce(self.new_pub_key()));
+                            schema.last_used_nonces_mut().put(self.new_pub_key(), nonce);
+                            schema.move_airplane_history(self.pub_key(), self.new_pub_key());
+                            // The operator was chosen by the previous owner.
+                            schema.airplane_operators_mut().remove(self.pub_key());
+                            schema.runway_schema_mut().release_runway(self.pub_key());
+                            schema.update_state_index(
+                                Some(AirplaneState::WaitingForFlight),
+                                None,
+                                self.pub_key(),
+                            );
+                            schema.update_state_index(
+                                None,
+                                Some(AirplaneState::WaitingForFlight),
+                                self.new_pub_key(),
+                            );
+                            let airplane_type = airplane.kind()?;
+                            schema
+                                .airplane_type_index_mut(airplane_type)
+                                .remove(self.pub_key());
+                            schema
+                                .airplane_type_index_mut(airplane_type)
+                                .insert(*self.new_pub_key());
+                            // The airplane keeps counting against the quota it was registered under.
+                            if let Some(operator) = schema.quota_registrant(self.pub_key()) {
+                                schema.quota_registrants_mut().remove(self.pub_key());
+                                schema
+                                    .quota_registrants_mut()
+                                    .put(self.new_pub_key(), operator);
+                            }
+
+                            Ok(())
+                        }
+                        AirplaneState::Decommissioned => Err(Error::AirplaneIsDecommissioned)?,
+                        AirplaneState::Inactive => Err(Error::AirplaneIsInactive)?,
+                        found => Err(Error::InvalidStateTransition {
+                            found,
+                            required: &[AirplaneState::WaitingForFlight],
+                        })?,
+                    }
+                }
+            })
+        }
+    }
+
+    impl Transaction for TxDecommissionAirplane {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxDecommissionAirplane", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.pub_key())?;
+
+                schema.transition_airplane_from_any(
+                    self.pub_key(),
+                    &self.hash(),
+                    &[AirplaneState::WaitingForFlight, AirplaneState::Inactive],
+                    |airplane| Ok(airplane.transition_state(AirplaneState::Decommissioned)),
+                )?;
+
+                let count = schema.airplane_count();
+                schema
+                    .airplane_count_entry_mut()
+                    .set(count.saturating_sub(1));
+
+                schema.runway_schema_mut().release_runway(self.pub_key());
+                schema.release_operator_quota(self.pub_key());
+
+                // Decommissioned airplanes release their name for reuse.
+                if let Some(airplane) = schema.airplane(self.pub_key()) {
+                    schema
+                        .airplane_names_mut()
+                        .remove(&normalize_airplane_name(airplane.name()));
+                }
+
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxUpdateAirplaneName {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxUpdateAirplaneName", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                let airplane = schema.airplane(self.pub_key());
+                if airplane.is_none() {
+                    Err(Error::AirplaneDoesNotExist(*self.pub_key()))?
+                } else {
+                    let airplane = airplane.unwrap();
+                    match AirplaneState::try_from(airplane.state_number())? {
+                        AirplaneState::Decommissioned => Err(Error::AirplaneIsDecommissioned)?,
+                        AirplaneState::Inactive => Err(Error::AirplaneIsInactive)?,
+                        AirplaneState::Grounded => Err(Error::AirplaneIsGrounded)?,
+                        _ => {
+                            let old_name_key = normalize_airplane_name(airplane.name());
+                            let new_name_key = normalize_airplane_name(self.new_name());
+                            match schema.airplane_names().get(&new_name_key) {
+                                Some(ref owner) if owner != self.pub_key() => {
+                                    Err(Error::NameAlreadyTaken)?
+                                }
+                                _ => {}
+                            }
+
+                            let new_airplane = Airplane::builder_from(&airplane)
+                                .name(self.new_name())
+                                .build();
+
+                            schema.airplanes_mut().put(self.pub_key(), new_airplane);
+                            let mut names = schema.airplane_names_mut();
+                            names.remove(&old_name_key);
+                            names.put(&new_name_key, *self.pub_key());
+
+                            Ok(())
+                        }
+                    }
+                }
+            })
+        }
+    }
+
+    impl Transaction for TxEmergencyLanding {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxEmergencyLanding", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::Flying,
+                    |airplane| {
+                        if self.reason().is_empty()
+                            || self.reason().len() > MAX_EMERGENCY_REASON_LEN
+                        {
+                            Err(Error::InvalidEmergencyReason)?
+                        }
+
+                        Ok(Airplane::builder_from(&airplane)
+                            .state(AirplaneState::Emergency)
+                            .clear_heating()
+                            .clear_flight_start()
+                            .last_emergency_reason(self.reason())
+                            .build())
+                    },
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxClearEmergency {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxClearEmergency", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::Emergency,
+                    |airplane| {
+                        Ok(Airplane::builder_from(&airplane)
+                            .state(AirplaneState::WaitingForFlight)
+                            .clear_heating()
+                            .clear_flight_start()
+                            .emergency_count(airplane.emergency_count().saturating_add(1))
+                            .clear_route()
+                            // An emergency landing ends the flight like `TxEndFlying`, so the
+                            // cooling period applies before the next technical check.
+                            .last_flight_end_time(current_time)
+                            .build())
+                    },
+                )?;
+
+                schema.runway_schema_mut().release_runway(self.pub_key());
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxCancelFlight {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxCancelFlight", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::Flying,
+                    |airplane| {
+                        Ok(Airplane::builder_from(&airplane)
+                            .state(AirplaneState::WaitingForFlight)
+                            .clear_heating()
+                            .clear_flight_start()
+                            .last_flight_end_time(current_time)
+                            .last_cancellation_reason(self.reason())
+                            .cancellation_count(airplane.cancellation_count().saturating_add(1))
+                            .clear_route()
+                            .build())
+                    },
+                )?;
+
+                schema.runway_schema_mut().release_runway(self.pub_key());
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxAssignRunway {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.airplane_pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxAssignRunway", self.airplane_pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer = verify_owner_or_operator(
+                    self,
+                    &schema,
+                    self.airplane_pub_key(),
+                    self.signer(),
+                )?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                let airplane = schema.airplane(self.airplane_pub_key());
+                if airplane.is_none() {
+                    Err(Error::AirplaneDoesNotExist(*self.airplane_pub_key()))?
+                } else {
+                    let airplane = airplane.unwrap();
+                    match AirplaneState::try_from(airplane.state_number())? {
+                        AirplaneState::WaitingForFlight => {
+                            schema
+                                .runway_schema_mut()
+                                .assign_runway(self.runway_name(), self.airplane_pub_key())?;
+                            Ok(())
+                        }
+                        AirplaneState::Decommissioned => Err(Error::AirplaneIsDecommissioned)?,
+                        AirplaneState::Inactive => Err(Error::AirplaneIsInactive)?,
+                        found => Err(Error::InvalidStateTransition {
+                            found,
+                            required: &[AirplaneState::WaitingForFlight],
+                        })?,
+                    }
+                }
+            })
+        }
+    }
+
+    impl Transaction for TxReleaseRunway {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.airplane_pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxReleaseRunway", self.airplane_pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer = verify_owner_or_operator(
+                    self,
+                    &schema,
+                    self.airplane_pub_key(),
+                    self.signer(),
+                )?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                let airplane = schema.airplane(self.airplane_pub_key());
+                if airplane.is_none() {
+                    Err(Error::AirplaneDoesNotExist(*self.airplane_pub_key()))?
+                } else {
+                    let airplane = airplane.unwrap();
+                    match AirplaneState::try_from(airplane.state_number())? {
+                        // A flying airplane releases its runway when the flight ends.
+                        AirplaneState::Flying => Err(Error::InvalidStateTransition {
+                            found: AirplaneState::Flying,
+                            required: &[
+                                AirplaneState::WaitingForFlight,
+                                AirplaneState::TechnicalCheck,
+                                AirplaneState::HeatingEngine,
+                                AirplaneState::Maintenance,
+                                AirplaneState::Grounded,
+                                AirplaneState::Decommissioned,
+                                AirplaneState::Emergency,
+                                AirplaneState::TechnicalCheckExtended,
+                                AirplaneState::AwaitingCheckApproval,
+                            ],
+                        })?,
+                        AirplaneState::Inactive => Err(Error::AirplaneIsInactive)?,
+                        _ => {
+                            if !schema
+                                .runway_schema_mut()
+                                .release_runway(self.airplane_pub_key())
+                            {
+                                Err(Error::NoRunwayAssigned)?
+                            }
+                            Ok(())
+                        }
+                    }
+                }
+            })
+        }
+    }
+
+    impl Transaction for TxRecordFlightRoute {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxRecordFlightRoute", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::Flying,
+                    |airplane| {
+                        validate_iata_code(self.departure_iata())?;
+                        validate_iata_code(self.arrival_iata())?;
+
+                        Ok(Airplane::builder_from(&airplane)
+                            .route(self.departure_iata(), self.arrival_iata())
+                            .build())
+                    },
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxUpdateEngineHeatingTime {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxUpdateEngineHeatingTime", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+                let config = schema.service_config();
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::HeatingEngine,
+                    |airplane| {
+                        if !config.allows_heating_time(self.new_heating_time_seconds()) {
+                            Err(Error::HeatingTimeOutOfRange)?
+                        }
+
+                        // The start time is kept, so `TxStartFlying` measures the new duration
+                        // from the original start of heating.
+                        Ok(Airplane::builder_from(&airplane)
+                            .heating_time_seconds(self.new_heating_time_seconds())
+                            .build())
+                    },
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxRefuelAirplane {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxRefuelAirplane", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::WaitingForFlight,
+                    |airplane| {
+                        if self.new_level() > MAX_FUEL_LEVEL_PERCENT {
+                            Err(Error::InvalidFuelLevel)?
+                        }
+
+                        Ok(Airplane::builder_from(&airplane)
+                            .fuel_level_percent(self.new_level())
+                            .build())
+                    },
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxScheduleTechnicalCheck {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxScheduleTechnicalCheck", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let scheduled_by =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&scheduled_by, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&scheduled_by)?;
+
+                let airplane = schema.airplane(self.pub_key());
+                if airplane.is_none() {
+                    Err(Error::AirplaneDoesNotExist(*self.pub_key()))?
+                } else {
+                    let airplane = airplane.unwrap();
+                    match AirplaneState::try_from(airplane.state_number())? {
+                        AirplaneState::WaitingForFlight => {
+                            let after_last = schema
+                                .scheduled_checks(self.pub_key())
+                                .last()
+                                .is_none_or(|check| self.scheduled_at() > check.scheduled_at());
+                            if self.scheduled_at() <= current_time || !after_last {
+                                Err(Error::InvalidScheduledTime)?
+                            }
+
+                            schema
+                                .scheduled_checks_mut(self.pub_key())
+                                .push(ScheduledCheck::new(
+                                    self.pub_key(),
+                                    self.scheduled_at(),
+                                    &scheduled_by,
+                                ));
+                            Ok(())
+                        }
+                        AirplaneState::Decommissioned => Err(Error::AirplaneIsDecommissioned)?,
+                        AirplaneState::Inactive => Err(Error::AirplaneIsInactive)?,
+                        found => Err(Error::InvalidStateTransition {
+                            found,
+                            required: &[AirplaneState::WaitingForFlight],
+                        })?,
+                    }
+                }
+            })
+        }
+    }
+
+    impl Transaction for TxStartScheduledCheck {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.service_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxStartScheduledCheck", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                verify_validator(view, self.service_key())?;
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.check_not_expired(self.created_at_height())?;
+
+                match schema.next_scheduled_check(self.pub_key()) {
+                    Some(ref check) if check.scheduled_at() <= current_time => {}
+                    _ => Err(Error::ScheduledCheckNotDue)?,
+                }
+
+                start_technical_check(
+                    &mut schema,
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::WaitingForFlight,
+                    current_time,
+                )?;
+
+                let started = schema.started_scheduled_checks(self.pub_key());
+                schema
+                    .started_scheduled_checks_mut()
+                    .put(self.pub_key(), started + 1);
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxAssignOperator {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxAssignOperator", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.pub_key())?;
+
+                let airplane = schema.airplane(self.pub_key());
+                if airplane.is_none() {
+                    Err(Error::AirplaneDoesNotExist(*self.pub_key()))?
+                } else {
+                    let airplane = airplane.unwrap();
+                    match AirplaneState::try_from(airplane.state_number())? {
+                        AirplaneState::Decommissioned => Err(Error::AirplaneIsDecommissioned)?,
+                        AirplaneState::Inactive => Err(Error::AirplaneIsInactive)?,
+                        AirplaneState::Grounded => Err(Error::AirplaneIsGrounded)?,
+                        _ => {
+                            schema
+                                .airplane_operators_mut()
+                                .put(self.pub_key(), *self.operator_pub_key());
+                            Ok(())
+                        }
+                    }
+                }
+            })
+        }
+    }
+
+    impl Transaction for TxUnassignOperator {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxUnassignOperator", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.pub_key())?;
+
+                match schema.airplane(self.pub_key()) {
+                    Some(ref airplane) => match airplane.state()? {
+                        AirplaneState::Decommissioned => Err(Error::AirplaneIsDecommissioned)?,
+                        AirplaneState::Inactive => Err(Error::AirplaneIsInactive)?,
+                        _ => {}
+                    },
+                    None => Err(Error::AirplaneDoesNotExist(*self.pub_key()))?,
+                }
+                if schema.airplane_operator(self.pub_key()).is_none() {
+                    Err(Error::OperatorNotAssigned)?
+                }
+
+                schema.airplane_operators_mut().remove(self.pub_key());
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxExpireHeating {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.service_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxExpireHeating", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                verify_validator(view, self.service_key())?;
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.check_not_expired(self.created_at_height())?;
+                let config = schema.service_config();
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::HeatingEngine,
+                    |airplane| {
+                        let max_wait = Duration::seconds(config.max_heating_wait_seconds() as i64);
+                        if current_time - airplane.engine_heating_start_time() <= max_wait {
+                            Err(Error::HeatingNotExpired)?
+                        }
+
+                        Ok(airplane.transition_state(AirplaneState::WaitingForFlight))
+                    },
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxRequestMaintenanceExtension {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(
+                view,
+                "TxRequestMaintenanceExtension",
+                self.pub_key(),
+                |view| {
+                    let current_time = TimeSchema::new(&view)
+                        .time()
+                        .get()
+                        .ok_or(Error::TimeNotAvailable)?;
+
+                    let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                    let signer =
+                        verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                    schema.use_nonce(&signer, self.nonce())?;
+                    schema.check_not_expired(self.created_at_height())?;
+                    schema.count_transaction(&signer)?;
+
+                    // Only `TechnicalCheck` is accepted, so a check can be extended once.
+                    schema.transition_airplane(
+                        self.pub_key(),
+                        &self.hash(),
+                        AirplaneState::TechnicalCheck,
+                        |airplane| {
+                            let deadline = airplane.technical_check_deadline();
+                            let base = if deadline == EPOCH {
+                                current_time
+                            } else {
+                                deadline
+                            };
+
+                            Ok(Airplane::builder_from(&airplane)
+                                .state(AirplaneState::TechnicalCheckExtended)
+                                .technical_check_deadline(
+                                    base + Duration::seconds(i64::from(self.additional_seconds())),
+                                )
+                                .build())
+                        },
+                    )
+                },
+            )
+        }
+    }
+
+    impl Transaction for TxReportIncident {
+        fn verify(&self) -> bool {
+            (MIN_INCIDENT_SEVERITY..=MAX_INCIDENT_SEVERITY).contains(&self.severity())
+                && self.verify_signature(self.reporter_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxReportIncident", self.airplane_pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.reporter_pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.reporter_pub_key())?;
+                match schema.airplane(self.airplane_pub_key()) {
+                    Some(ref airplane) => match airplane.state()? {
+                        AirplaneState::Decommissioned => Err(Error::AirplaneIsDecommissioned)?,
+                        AirplaneState::Inactive => Err(Error::AirplaneIsInactive)?,
+                        _ => {}
+                    },
+                    None => Err(Error::AirplaneDoesNotExist(*self.airplane_pub_key()))?,
+                }
+                if self.severity() == MAX_INCIDENT_SEVERITY {
+                    verify_critical_incident_reporter(
+                        &schema,
+                        self.airplane_pub_key(),
+                        self.reporter_pub_key(),
+                    )?;
+                }
+
+                schema
+                    .incidents_mut(self.airplane_pub_key())
+                    .push(Incident::new(
+                        self.airplane_pub_key(),
+                        current_time,
+                        self.severity(),
+                        self.description(),
+                        self.reporter_pub_key(),
+                    ));
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxGroundAirplane {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.admin_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxGroundAirplane", self.target_pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.admin_pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.admin_pub_key())?;
+                verify_admin(&schema, self.admin_pub_key())?;
+
+                schema.transition_airplane_from_any(
+                    self.target_pub_key(),
+                    &self.hash(),
+                    &[
+                        AirplaneState::WaitingForFlight,
+                        AirplaneState::TechnicalCheck,
+                        AirplaneState::HeatingEngine,
+                        AirplaneState::Flying,
+                        AirplaneState::Maintenance,
+                        AirplaneState::Grounded,
+                        AirplaneState::Emergency,
+                        AirplaneState::TechnicalCheckExtended,
+                        AirplaneState::AwaitingCheckApproval,
+                    ],
+                    |airplane| {
+                        Ok(Airplane::builder_from(&airplane)
+                            .state(AirplaneState::Grounded)
+                            .clear_heating()
+                            .clear_flight_start()
+                            .clear_route()
+                            .grounded_reason(self.reason())
+                            .build())
+                    },
+                )?;
+
+                schema
+                    .runway_schema_mut()
+                    .release_runway(self.target_pub_key());
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxUngroundAirplane {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.admin_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxUngroundAirplane", self.target_pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.admin_pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.admin_pub_key())?;
+                verify_admin(&schema, self.admin_pub_key())?;
+
+                schema.transition_airplane(
+                    self.target_pub_key(),
+                    &self.hash(),
+                    AirplaneState::Grounded,
+                    |airplane| {
+                        Ok(Airplane::builder_from(&airplane)
+                            .state(AirplaneState::WaitingForFlight)
+                            .grounded_reason("")
+                            .build())
+                    },
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxUpdateServiceConfig {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.admin_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(
+                view,
+                "TxUpdateServiceConfig",
+                self.admin_pub_key(),
+                |view| {
+                    let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                    schema.use_nonce(self.admin_pub_key(), self.nonce())?;
+                    schema.check_not_expired(self.created_at_height())?;
+                    schema.count_transaction(self.admin_pub_key())?;
+                    verify_admin(&schema, self.admin_pub_key())?;
+
+                    let config: AirplaneServiceConfig = serde_json::from_str(self.config_json())
+                        .map_err(|_| Error::InvalidConfig)?;
+                    config.validate()?;
+                    schema.service_config_entry_mut().set(config);
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    impl Transaction for TxAssignPriority {
+        fn verify(&self) -> bool {
+            (MIN_PRIORITY..=MAX_PRIORITY).contains(&self.priority())
+                && self.verify_signature(self.admin_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxAssignPriority", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.admin_pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.admin_pub_key())?;
+                verify_admin(&schema, self.admin_pub_key())?;
+
+                let airplane = match schema.airplane(self.pub_key()) {
+                    Some(airplane) => airplane,
+                    None => Err(Error::AirplaneDoesNotExist(*self.pub_key()))?,
+                };
+                if airplane.state_number() == u8::from(AirplaneState::Decommissioned) {
+                    Err(Error::AirplaneIsDecommissioned)?
+                }
+                if airplane.state_number() == u8::from(AirplaneState::Inactive) {
+                    Err(Error::AirplaneIsInactive)?
+                }
+                schema.airplanes_mut().put(
+                    self.pub_key(),
+                    Airplane::builder_from(&airplane)
+                        .priority(self.priority())
+                        .build(),
+                );
+
+                // A queued airplane moves to the end of its new priority's queue.
+                if schema.remove_from_check_queue(self.pub_key()) {
+                    schema.enqueue_check(self.pub_key(), self.priority())?;
+                }
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxStartQueuedCheck {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.service_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxStartQueuedCheck", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                verify_validator(view, self.service_key())?;
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.check_not_expired(self.created_at_height())?;
+
+                if schema.next_queued_check() != Some(*self.pub_key())
+                    || !schema.has_free_check_slot()
+                {
+                    Err(Error::QueuedCheckNotReady)?
+                }
+                schema.remove_from_check_queue(self.pub_key());
+
+                // An airplane that cannot start its check any more, e.g. because it was grounded
+                // while queued, is dropped so that it does not hold up the queue; its owner has
+                // to request the check again. `start_technical_check` writes nothing on failure.
+                if let Err(e) = start_technical_check(
+                    &mut schema,
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::WaitingForFlight,
+                    current_time,
+                ) {
+                    warn!(
+                        "Dropped queued technical check for {:?}: {:?}",
+                        self.pub_key(),
+                        e
+                    );
+                }
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxLoadCargo {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxLoadCargo", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                // The takeoff weight is only checked by `TxStartFlying`, so an airplane can be
+                // loaded above it and then partially unloaded.
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::WaitingForFlight,
+                    |airplane| {
+                        let cargo_weight_kg = airplane
+                            .cargo_weight_kg()
+                            .checked_add(self.weight_kg())
+                            .ok_or(Error::OverMaxTakeoffWeight)?;
+
+                        Ok(Airplane::builder_from(&airplane)
+                            .cargo_weight_kg(cargo_weight_kg)
+                            .build())
+                    },
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxUnloadCargo {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxUnloadCargo", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::WaitingForFlight,
+                    |airplane| {
+                        Ok(Airplane::builder_from(&airplane)
+                            .cargo_weight_kg(
+                                airplane.cargo_weight_kg().saturating_sub(self.weight_kg()),
+                            )
+                            .build())
+                    },
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxSetMetadataField {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxSetMetadataField", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                let key_len = self.key().chars().count();
+                if key_len == 0
+                    || key_len > MAX_METADATA_KEY_LEN
+                    || self.value().chars().count() > MAX_METADATA_VALUE_LEN
+                {
+                    Err(Error::InvalidMetadataField)?
+                }
+
+                update_metadata(&mut schema, self.pub_key(), |fields| {
+                    if !fields.contains_key(self.key()) && fields.len() >= MAX_METADATA_FIELDS {
+                        Err(Error::TooManyMetadataFields)?
+                    }
+                    fields.insert(self.key().to_owned(), self.value().to_owned());
+                    Ok(())
+                })
+            })
+        }
+    }
+
+    impl Transaction for TxRemoveMetadataField {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxRemoveMetadataField", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                update_metadata(&mut schema, self.pub_key(), |fields| {
+                    fields.remove(self.key());
+                    Ok(())
+                })
+            })
+        }
+    }
+
+    impl Transaction for TxSetPassengerCount {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxSetPassengerCount", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::WaitingForFlight,
+                    |airplane| {
+                        if self.count() > airplane.max_passenger_capacity() {
+                            Err(Error::ExceedsPassengerCapacity)?
+                        }
+
+                        Ok(Airplane::builder_from(&airplane)
+                            .passenger_count(self.count())
+                            .build())
+                    },
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxRenewAirworthinessCertificate {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.authority_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(
+                view,
+                "TxRenewAirworthinessCertificate",
+                self.pub_key(),
+                |view| {
+                    let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                    schema.use_nonce(self.authority_pub_key(), self.nonce())?;
+                    schema.check_not_expired(self.created_at_height())?;
+                    schema.count_transaction(self.authority_pub_key())?;
+                    verify_authority(&schema, self.authority_pub_key())?;
+
+                    let airplane = match schema.airplane(self.pub_key()) {
+                        Some(airplane) => airplane,
+                        None => Err(Error::AirplaneDoesNotExist(*self.pub_key()))?,
+                    };
+                    if airplane.state()? == AirplaneState::Decommissioned {
+                        Err(Error::AirplaneIsDecommissioned)?
+                    }
+                    if airplane.state()? == AirplaneState::Inactive {
+                        Err(Error::AirplaneIsInactive)?
+                    }
+                    schema.airplanes_mut().put(
+                        self.pub_key(),
+                        Airplane::builder_from(&airplane)
+                            .airworthiness_cert_expiry(self.expiry())
+                            .build(),
+                    );
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    impl Transaction for TxRegisterAuthority {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.admin_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxRegisterAuthority", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.admin_pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.admin_pub_key())?;
+                verify_admin(&schema, self.admin_pub_key())?;
+
+                schema.authorities_mut().put(
+                    self.pub_key(),
+                    CertificationAuthority::new(self.pub_key(), self.name(), true),
+                );
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxRevokeAuthority {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.admin_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxRevokeAuthority", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.admin_pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.admin_pub_key())?;
+                verify_admin(&schema, self.admin_pub_key())?;
+
+                let authority = match schema.authority(self.pub_key()) {
+                    Some(authority) => authority,
+                    None => Err(Error::UnknownAuthority)?,
+                };
+                schema.authorities_mut().put(
+                    self.pub_key(),
+                    CertificationAuthority::new(self.pub_key(), authority.name(), false),
+                );
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxMarkRepairRequired {
+        fn verify(&self) -> bool {
+            RepairFlag::try_from(self.repair_flag()).is_ok()
+                && self.verify_signature(self.admin_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxMarkRepairRequired", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.admin_pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.admin_pub_key())?;
+                verify_admin(&schema, self.admin_pub_key())?;
+                let flag = RepairFlag::try_from(self.repair_flag())?;
+
+                let airplane = match schema.airplane(self.pub_key()) {
+                    Some(airplane) => airplane,
+                    None => Err(Error::AirplaneDoesNotExist(*self.pub_key()))?,
+                };
+                if airplane.state()? == AirplaneState::Decommissioned {
+                    Err(Error::AirplaneIsDecommissioned)?
+                }
+                if airplane.state()? == AirplaneState::Inactive {
+                    Err(Error::AirplaneIsInactive)?
+                }
+                schema.airplanes_mut().put(
+                    self.pub_key(),
+                    Airplane::builder_from(&airplane)
+                        .required_repairs_bitmask(airplane.required_repairs_bitmask() | flag.bit())
+                        .build(),
+                );
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxClearRepair {
+        fn verify(&self) -> bool {
+            RepairFlag::try_from(self.repair_flag()).is_ok()
+                && self.verify_signature(self.admin_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxClearRepair", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.admin_pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.admin_pub_key())?;
+                verify_admin(&schema, self.admin_pub_key())?;
+                let flag = RepairFlag::try_from(self.repair_flag())?;
+
+                let airplane = match schema.airplane(self.pub_key()) {
+                    Some(airplane) => airplane,
+                    None => Err(Error::AirplaneDoesNotExist(*self.pub_key()))?,
+                };
+                if airplane.state()? == AirplaneState::Decommissioned {
+                    Err(Error::AirplaneIsDecommissioned)?
+                }
+                if airplane.state()? == AirplaneState::Inactive {
+                    Err(Error::AirplaneIsInactive)?
+                }
+                schema.airplanes_mut().put(
+                    self.pub_key(),
+                    Airplane::builder_from(&airplane)
+                        .required_repairs_bitmask(airplane.required_repairs_bitmask() & !flag.bit())
+                        .build(),
+                );
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxRecordWeatherConditions {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.admin_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(
+                view,
+                "TxRecordWeatherConditions",
+                self.admin_pub_key(),
+                |view| {
+                    let current_time = TimeSchema::new(&view)
+                        .time()
+                        .get()
+                        .ok_or(Error::TimeNotAvailable)?;
+                    let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                    schema.use_nonce(self.admin_pub_key(), self.nonce())?;
+                    schema.check_not_expired(self.created_at_height())?;
+                    schema.count_transaction(self.admin_pub_key())?;
+                    verify_admin(&schema, self.admin_pub_key())?;
+                    validate_iata_code(self.iata())?;
+
+                    schema.weather_reports_mut().put(
+                        &self.iata().to_owned(),
+                        WeatherReport::new(
+                            self.iata(),
+                            self.visibility_meters(),
+                            self.wind_speed_knots(),
+                            current_time,
+                        ),
+                    );
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    impl Transaction for TxSetHomeAirport {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxSetHomeAirport", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+                validate_iata_code(self.iata())?;
+
+                let airplane = match schema.airplane(self.pub_key()) {
+                    Some(airplane) => airplane,
+                    None => Err(Error::AirplaneDoesNotExist(*self.pub_key()))?,
+                };
+                if airplane.state()? == AirplaneState::Decommissioned {
+                    Err(Error::AirplaneIsDecommissioned)?
+                }
+                if airplane.state()? == AirplaneState::Inactive {
+                    Err(Error::AirplaneIsInactive)?
+                }
+                schema.airplanes_mut().put(
+                    self.pub_key(),
+                    Airplane::builder_from(&airplane)
+                        .home_airport(self.iata())
+                        .build(),
+                );
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxPlanFlight {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.airplane_pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxPlanFlight", self.airplane_pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer = verify_owner_or_operator(
+                    self,
+                    &schema,
+                    self.airplane_pub_key(),
+                    self.signer(),
+                )?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+                validate_iata_code(self.departure_iata())?;
+                validate_iata_code(self.arrival_iata())?;
+                if self.planned_departure() >= self.planned_arrival() {
+                    Err(Error::InvalidFlightPlan)?
+                }
+
+                let airplane = match schema.airplane(self.airplane_pub_key()) {
+                    Some(airplane) => airplane,
+                    None => Err(Error::AirplaneDoesNotExist(*self.airplane_pub_key()))?,
+                };
+                if airplane.state()? == AirplaneState::Decommissioned {
+                    Err(Error::AirplaneIsDecommissioned)?
+                }
+                if airplane.state()? == AirplaneState::Inactive {
+                    Err(Error::AirplaneIsInactive)?
+                }
+                if schema.has_conflicting_flight_plan(
+                    self.airplane_pub_key(),
+                    self.planned_departure(),
+                    self.planned_arrival(),
+                ) {
+                    Err(Error::FlightPlanConflict)?
+                }
+
+                schema.add_flight_plan(
+                    self.airplane_pub_key(),
+                    self.departure_iata(),
+                    self.arrival_iata(),
+                    self.planned_departure(),
+                    self.planned_arrival(),
+                );
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxRenewRegistration {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.authority_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxRenewRegistration", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.authority_pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.authority_pub_key())?;
+                verify_authority(&schema, self.authority_pub_key())?;
+
+                let airplane = match schema.airplane(self.pub_key()) {
+                    Some(airplane) => airplane,
+                    None => Err(Error::AirplaneDoesNotExist(*self.pub_key()))?,
+                };
+                if airplane.state()? == AirplaneState::Decommissioned {
+                    Err(Error::AirplaneIsDecommissioned)?
+                }
+                if airplane.state()? == AirplaneState::Inactive {
+                    Err(Error::AirplaneIsInactive)?
+                }
+                schema.airplanes_mut().put(
+                    self.pub_key(),
+                    Airplane::builder_from(&airplane)
+                        .registration_expiry(self.new_expiry())
+                        .build(),
+                );
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxDeactivateAirplane {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxDeactivateAirplane", self.pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::WaitingForFlight,
+                    |airplane| {
+                        Ok(Airplane::builder_from(&airplane)
+                            .state(AirplaneState::Inactive)
+                            .inactive_since(current_time)
+                            .build())
+                    },
+                )?;
+
+                // An airplane out of service holds neither a runway nor a check slot.
+                schema.runway_schema_mut().release_runway(self.pub_key());
+                schema.remove_from_check_queue(self.pub_key());
+                Ok(())
+            })
+        }
+    }
+
+    impl Transaction for TxSetOperatorQuota {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.admin_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(
+                view,
+                "TxSetOperatorQuota",
+                self.operator_pub_key(),
+                |view| {
+                    let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                    schema.use_nonce(self.admin_pub_key(), self.nonce())?;
+                    schema.check_not_expired(self.created_at_height())?;
+                    schema.count_transaction(self.admin_pub_key())?;
+                    verify_admin(&schema, self.admin_pub_key())?;
+
+                    let current_count = schema
+                        .operator_quota(self.operator_pub_key())
+                        .map_or(0, |quota| quota.current_count());
+                    schema.operator_quotas_mut().put(
+                        self.operator_pub_key(),
+                        OperatorQuota::new(
+                            self.operator_pub_key(),
+                            self.max_airplanes(),
+                            current_count,
+                        ),
+                    );
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    impl Transaction for TxReactivateAirplane {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxReactivateAirplane", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                schema.transition_airplane_from_any(
+                    self.pub_key(),
+                    &self.hash(),
+                    &[AirplaneState::Inactive],
+                    |airplane| {
+                        Ok(Airplane::builder_from(&airplane)
+                            .state(AirplaneState::WaitingForFlight)
+                            .inactive_since(EPOCH)
+                            .build())
+                    },
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxRequestTechnicalCheck {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.pub_key()) || self.verify_signature(self.signer())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxRequestTechnicalCheck", self.pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                let signer =
+                    verify_owner_or_operator(self, &schema, self.pub_key(), self.signer())?;
+                schema.use_nonce(&signer, self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(&signer)?;
+
+                schema.transition_airplane(
+                    self.pub_key(),
+                    &self.hash(),
+                    AirplaneState::WaitingForFlight,
+                    |airplane| {
+                        Ok(airplane_with_state(
+                            &airplane,
+                            AirplaneState::AwaitingCheckApproval,
+                        ))
+                    },
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxApproveCheckStart {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.admin_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxApproveCheckStart", self.target_pub_key(), |view| {
+                let current_time = TimeSchema::new(&view)
+                    .time()
+                    .get()
+                    .ok_or(Error::TimeNotAvailable)?;
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.admin_pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.admin_pub_key())?;
+                verify_admin(&schema, self.admin_pub_key())?;
+
+                // Approved checks start right away, even when all technical check slots are
+                // taken: the safety authority decides when the check runs.
+                start_technical_check(
+                    &mut schema,
+                    self.target_pub_key(),
+                    &self.hash(),
+                    AirplaneState::AwaitingCheckApproval,
+                    current_time,
+                )
+            })
+        }
+    }
+
+    impl Transaction for TxDenyCheckRequest {
+        fn verify(&self) -> bool {
+            self.verify_signature(self.admin_pub_key())
+        }
+
+        fn execute(&self, view: &mut Fork) -> ExecutionResult {
+            self.logged_execute(view, "TxDenyCheckRequest", self.target_pub_key(), |view| {
+                let mut schema = Schema::for_service_id(view, self.raw().service_id());
+                schema.use_nonce(self.admin_pub_key(), self.nonce())?;
+                schema.check_not_expired(self.created_at_height())?;
+                schema.count_transaction(self.admin_pub_key())?;
+                verify_admin(&schema, self.admin_pub_key())?;
+
+                schema.transition_airplane(
+                    self.target_pub_key(),
+                    &self.hash(),
+                    AirplaneState::AwaitingCheckApproval,
+                    |airplane| {
+                        Ok(airplane_with_state(
+                            &airplane,
+                            AirplaneState::WaitingForFlight,
+                        ))
+                    },
+                )
+            })
+        }
+    }
+}
+
+pub mod schema {
+    // `encoding_struct!` generates a positional constructor with one argument per field.
+    #![allow(clippy::too_many_arguments)]
+
+    use exonum::{
+        blockchain::{ExecutionError, ExecutionResult, Schema as CoreSchema},
+        crypto::{Hash, PublicKey},
+        storage::{
+            Entry, Fork, KeySetIndex, ListIndex, MapIndex, MapProof, ProofListIndex, ProofMapIndex,
+            Snapshot,
+        },
+    };
+
+    use chrono::{DateTime, Duration, Utc};
+    use exonum_time::schema::TimeSchema;
+
+    use serde::{
+        de::{self, Unexpected, Visitor},
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    #[cfg(feature = "debug")]
+    use serde_json::{self, Map, Value};
+
+    use std::{collections::BTreeMap, convert::TryFrom, fmt, slice, str::FromStr};
+
+    use service::{
+        AirplaneReadiness, PaginatedResponse, PaginationParams, SERVICE_ID, SERVICE_NAME,
+    };
+    use transactions::Error;
+    use transitions::can_transition;
+
+    /// Placeholder stored in timestamp fields that are not set.
+    pub const EPOCH: DateTime<Utc> = DateTime::<Utc>::UNIX_EPOCH;
+
+    /// Version of the storage layout, bumped on every breaking change to stored values.
+    pub const SCHEMA_VERSION: u16 = 23;
+
+    /// Table in which Exonum stores the type of each index; not exported by `exonum::storage`.
+    const INDEXES_METADATA_TABLE: &str = "__INDEXES_METADATA__";
+
+    #[derive(Debug, Fail)]
+    pub enum MigrationError {
+        #[fail(display = "Cannot migrate from schema version {}", _0)]
+        UnsupportedVersion(u16),
+
+        #[fail(display = "Migrated {} airplanes, expected {}", migrated, expected)]
+        CountMismatch { migrated: u64, expected: u64 },
+
+        #[fail(display = "Time oracle has no time to renew registrations from")]
+        TimeNotAvailable,
+
+        #[fail(
+            display = "Airplane {} has unknown state number {}",
+            pub_key, state_number
+        )]
+        InvalidState {
+            pub_key: PublicKey,
+            state_number: u8,
+        },
+    }
+
+    /// Problem with a stored airplane found by `Schema::validate_integrity`.
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+    pub struct IntegrityViolation {
+        pub pub_key: PublicKey,
+
+        pub violation: String,
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    #[repr(u8)]
+    pub enum AirplaneState {
+        WaitingForFlight = 0,
+
+        TechnicalCheck = 1,
+
+        HeatingEngine = 2,
+
+        Flying = 3,
+
+        Maintenance = 4,
+
+        Grounded = 5,
+
+        Decommissioned = 6,
+
+        Emergency = 7,
+
+        /// Technical check whose deadline has been extended once.
+        TechnicalCheckExtended = 8,
+
+        /// Technical check requested with `TxRequestTechnicalCheck`, waiting for the
+        /// safety authority to approve or deny it.
+        AwaitingCheckApproval = 9,
+
+        /// Temporarily out of service, e.g. in seasonal storage, until `TxReactivateAirplane`.
+        Inactive = 10,
+    }
+
+    impl fmt::Display for AirplaneState {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = match *self {
+                AirplaneState::WaitingForFlight => "Waiting for flight",
+                AirplaneState::TechnicalCheck => "Technical check",
+                AirplaneState::HeatingEngine => "Heating engine",
+                AirplaneState::Flying => "Flying",
+                AirplaneState::Maintenance => "Maintenance",
+                AirplaneState::Grounded => "Grounded",
+                AirplaneState::Decommissioned => "Decommissioned",
+                AirplaneState::Emergency => "Emergency",
+                AirplaneState::TechnicalCheckExtended => "Technical check extended",
+                AirplaneState::AwaitingCheckApproval => "Awaiting check approval",
+                AirplaneState::Inactive => "Inactive",
+            };
+            f.write_str(name)
+        }
+    }
+
+    impl FromStr for AirplaneState {
+        type Err = String;
+
+        /// Parses a state from its display name, ignoring ASCII case.
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let states = AirplaneState::all();
+            states
+                .iter()
+                .find(|state| state.to_string().eq_ignore_ascii_case(s))
+                .cloned()
+                .ok_or_else(|| {
+                    let names: Vec<String> = states.iter().map(ToString::to_string).collect();
+                    format!(
+                        "Unknown airplane state '{}', expected one of: {}",
+                        s,
+                        names.join(", ")
+                    )
+                })
+        }
+    }
+
+    /// States are serialized as their display name.
+    impl Serialize for AirplaneState {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    /// Accepts either the display name (case-insensitive) or the numeric discriminant,
+    /// so clients still sending `state_number` values keep working.
+    impl<'de> Deserialize<'de> for AirplaneState {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct StateVisitor;
+
+            impl<'de> Visitor<'de> for StateVisitor {
+                type Value = AirplaneState;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("an airplane state name or number")
+                }
+
+                fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                    u8::try_from(value)
+                        .ok()
+                        .and_then(|value| AirplaneState::try_from(value).ok())
+                        .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(value), &self))
+                }
+
+                fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                    u64::try_from(value)
+                        .map_err(|_| E::invalid_value(Unexpected::Signed(value), &self))
+                        .and_then(|value| self.visit_u64(value))
+                }
+
+                fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                    match value.parse::<u64>() {
+                        Ok(number) => self.visit_u64(number),
+                        Err(_) => value.parse().map_err(E::custom),
+                    }
+                }
+            }
+
+            deserializer.deserialize_any(StateVisitor)
+        }
+    }
+
+    impl AirplaneState {
+        /// Returns the state as a one-element slice, as used by `Error::InvalidStateTransition`.
+        pub fn as_slice(self) -> &'static [AirplaneState] {
+            match self {
+                AirplaneState::WaitingForFlight => &[AirplaneState::WaitingForFlight],
+                AirplaneState::TechnicalCheck => &[AirplaneState::TechnicalCheck],
+                AirplaneState::HeatingEngine => &[AirplaneState::HeatingEngine],
+                AirplaneState::Flying => &[AirplaneState::Flying],
+                AirplaneState::Maintenance => &[AirplaneState::Maintenance],
+                AirplaneState::Grounded => &[AirplaneState::Grounded],
+                AirplaneState::Decommissioned => &[AirplaneState::Decommissioned],
+                AirplaneState::Emergency => &[AirplaneState::Emergency],
+                AirplaneState::TechnicalCheckExtended => &[AirplaneState::TechnicalCheckExtended],
+                AirplaneState::AwaitingCheckApproval => &[AirplaneState::AwaitingCheckApproval],
+                AirplaneState::Inactive => &[AirplaneState::Inactive],
+            }
+        }
+
+        /// Returns every known state in discriminant order.
+        pub fn all() -> Vec<AirplaneState> {
+            (0..=u8::MAX)
+                .filter_map(|value| AirplaneState::try_from(value).ok())
+                .collect()
+        }
+    }
+
+    /// The `#[repr(u8)]` discriminant, stored as `Airplane::state_number`.
+    impl From<AirplaneState> for u8 {
+        fn from(state: AirplaneState) -> u8 {
+            state as u8
+        }
+    }
+
+    impl From<AirplaneState> for u16 {
+        fn from(state: AirplaneState) -> u16 {
+            u16::from(u8::from(state))
+        }
+    }
+
+    impl From<AirplaneState> for u32 {
+        fn from(state: AirplaneState) -> u32 {
+            u32::from(u8::from(state))
+        }
+    }
+
+    impl TryFrom<u8> for AirplaneState {
+        type Error = Error;
+
+        fn try_from(value: u8) -> Result<Self, Self::Error> {
+            match value {
+                0 => Ok(AirplaneState::WaitingForFlight),
+                1 => Ok(AirplaneState::TechnicalCheck),
+                2 => Ok(AirplaneState::HeatingEngine),
+                3 => Ok(AirplaneState::Flying),
+                4 => Ok(AirplaneState::Maintenance),
+                5 => Ok(AirplaneState::Grounded),
+                6 => Ok(AirplaneState::Decommissioned),
+                7 => Ok(AirplaneState::Emergency),
+                8 => Ok(AirplaneState::TechnicalCheckExtended),
+                9 => Ok(AirplaneState::AwaitingCheckApproval),
+                10 => Ok(AirplaneState::Inactive),
+                _ => Err(Error::InvalidState),
+            }
+        }
+    }
+
+    /// Regulatory category of an airplane, which sets its minimum heating time before flight.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    #[repr(u8)]
+    pub enum AirplaneType {
+        Commercial = 0,
+
+        Cargo = 1,
+
+        Military = 2,
+
+        Private = 3,
+    }
+
+    impl AirplaneType {
+        /// Returns every known type in discriminant order.
+        pub fn all() -> Vec<AirplaneType> {
+            (0..=u8::MAX)
+                .filter_map(|value| AirplaneType::try_from(value).ok())
+                .collect()
+        }
+    }
+
+    impl fmt::Display for AirplaneType {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = match *self {
+                AirplaneType::Commercial => "Commercial",
+                AirplaneType::Cargo => "Cargo",
+                AirplaneType::Military => "Military",
+                AirplaneType::Private => "Private",
+            };
+            f.write_str(name)
+        }
+    }
+
+    impl FromStr for AirplaneType {
+        type Err = String;
+
+        /// Parses a type from its display name, ignoring ASCII case.
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            AirplaneType::all()
+                .into_iter()
+                .find(|airplane_type| airplane_type.to_string().eq_ignore_ascii_case(s))
+                .ok_or_else(|| {
+                    format!(
+                        "Unknown airplane type '{}', expected one of: Commercial, Cargo, \
+                         Military, Private",
+                        s
+                    )
+                })
+        }
+    }
+
+    impl TryFrom<u8> for AirplaneType {
+        type Error = Error;
+
+        fn try_from(value: u8) -> Result<Self, Self::Error> {
+            match value {
+                0 => Ok(AirplaneType::Commercial),
+                1 => Ok(AirplaneType::Cargo),
+                2 => Ok(AirplaneType::Military),
+                3 => Ok(AirplaneType::Private),
+                _ => Err(Error::InvalidAirplaneType),
+            }
+        }
+    }
+
+    /// Airplane system that `TxMarkRepairRequired` can flag for repair.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    #[repr(u8)]
+    pub enum RepairFlag {
+        Engine = 0,
+
+        Avionics = 1,
+
+        Hydraulics = 2,
+
+        Fuselage = 3,
+    }
+
+    impl RepairFlag {
+        /// Returns every known flag in discriminant order.
+        pub fn all() -> Vec<RepairFlag> {
+            (0..=u8::MAX)
+                .filter_map(|value| RepairFlag::try_from(value).ok())
+                .collect()
+        }
+
+        /// Bit of the flag in `Airplane::required_repairs_bitmask`.
+        pub fn bit(self) -> u8 {
+            1 << self as u8
+        }
+    }
+
+    impl fmt::Display for RepairFlag {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = match *self {
+                RepairFlag::Engine => "Engine",
+                RepairFlag::Avionics => "Avionics",
+                RepairFlag::Hydraulics => "Hydraulics",
+                RepairFlag::Fuselage => "Fuselage",
+            };
+            f.write_str(name)
+        }
+    }
+
+    impl TryFrom<u8> for RepairFlag {
+        type Error = Error;
+
+        fn try_from(value: u8) -> Result<Self, Self::Error> {
+            match value {
+                0 => Ok(RepairFlag::Engine),
+                1 => Ok(RepairFlag::Avionics),
+                2 => Ok(RepairFlag::Hydraulics),
+                3 => Ok(RepairFlag::Fuselage),
+                _ => Err(Error::InvalidRepairFlag),
+            }
+        }
+    }
+
+    /// Flags are serialized as their display name.
+    impl Serialize for RepairFlag {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    /// Returns the key under which `name` is stored in the name uniqueness index.
+    pub fn normalize_airplane_name(name: &str) -> String {
+        name.trim().to_lowercase()
+    }
+
+    /// Minimum length of an airplane name, in bytes.
+    pub const MIN_AIRPLANE_NAME_LEN: usize = 1;
+    /// Maximum length of an airplane name, in bytes.
+    pub const MAX_AIRPLANE_NAME_LEN: usize = 64;
+
+    /// Checks that `name` is `MIN_AIRPLANE_NAME_LEN` to `MAX_AIRPLANE_NAME_LEN` bytes long.
+    ///
+    /// The length is measured in UTF-8 bytes, so non-ASCII names are accepted
+    /// but fit fewer characters.
+    pub fn is_valid_airplane_name(name: &str) -> bool {
+        (MIN_AIRPLANE_NAME_LEN..=MAX_AIRPLANE_NAME_LEN).contains(&name.len())
+    }
+
+    /// Checks that `serial_number` consists of 4 to 20 ASCII letters and digits.
+    pub fn is_valid_serial_number(serial_number: &str) -> bool {
+        (4..=20).contains(&serial_number.len())
+            && serial_number.chars().all(|c| c.is_ascii_alphanumeric())
+    }
+
+    /// Fuel level of a full tank; newly registered airplanes start with it.
+    pub const MAX_FUEL_LEVEL_PERCENT: u8 = 100;
+
+    pub const MIN_INCIDENT_SEVERITY: u8 = 1;
+    /// Incidents of this severity are critical.
+    pub const MAX_INCIDENT_SEVERITY: u8 = 5;
+    /// A technical check cannot start after more critical incidents than this within
+    /// `CRITICAL_INCIDENT_WINDOW_DAYS`.
+    pub const MAX_CRITICAL_INCIDENTS: u64 = 3;
+    pub const CRITICAL_INCIDENT_WINDOW_DAYS: i64 = 30;
+
+    pub const MIN_PRIORITY: u8 = 1;
+    /// The most urgent priority.
+    pub const MAX_PRIORITY: u8 = 5;
+    pub const DEFAULT_PRIORITY: u8 = 3;
+
+    /// Maximum length of a metadata key, in characters.
+    pub const MAX_METADATA_KEY_LEN: usize = 64;
+    /// Maximum length of a metadata value, in characters.
+    pub const MAX_METADATA_VALUE_LEN: usize = 256;
+    pub const MAX_METADATA_FIELDS: usize = 20;
+
+    /// Maximum number of entries returned by `Schema::global_timeline`.
+    pub const MAX_TIMELINE_ENTRIES: usize = 500;
+
+    /// Maximum length of an emergency reason, in bytes.
+    pub const MAX_EMERGENCY_REASON_LEN: usize = 256;
+
+    pub const DEFAULT_MIN_HEATING_SECONDS: u32 = 10;
+    pub const DEFAULT_MAX_HEATING_SECONDS: u32 = 86_400;
+    pub const DEFAULT_COOLING_PERIOD_SECONDS: u32 = 0;
+    pub const DEFAULT_MAX_HEATING_WAIT_SECONDS: u32 = 2 * DEFAULT_MAX_HEATING_SECONDS;
+    pub const DEFAULT_MIN_FUEL_PERCENT_FOR_FLIGHT: u8 = 20;
+    /// `0` leaves the fleet size unlimited.
+    pub const DEFAULT_MAX_FLEET_SIZE: u64 = 0;
+    /// `0` leaves technical checks without a deadline.
+    pub const DEFAULT_TECHNICAL_CHECK_WINDOW_SECONDS: u32 = 0;
+    pub const DEFAULT_COMMERCIAL_MIN_HEATING_SECONDS: u32 = 60;
+    pub const DEFAULT_CARGO_MIN_HEATING_SECONDS: u32 = 45;
+    pub const DEFAULT_MILITARY_MIN_HEATING_SECONDS: u32 = 30;
+    pub const DEFAULT_PRIVATE_MIN_HEATING_SECONDS: u32 = 15;
+    /// `0` starts every technical check right away instead of queueing it.
+    pub const DEFAULT_MAX_CONCURRENT_TECHNICAL_CHECKS: u32 = 0;
+    pub const DEFAULT_TX_EXPIRY_BLOCKS: u32 = 100;
+    /// `0` leaves the number of transactions per key unlimited.
+    pub const DEFAULT_MAX_TX_PER_BLOCK_PER_KEY: u32 = 0;
+    pub const DEFAULT_MIN_VISIBILITY_METERS: u32 = 300;
+    pub const DEFAULT_REGISTRATION_VALIDITY_DAYS: u32 = 365;
+
+    encoding_struct! {
+        /// Single entry of `TxBatchRegisterAirplanes`.
+        struct AirplaneRegistration {
+            pub_key: &PublicKey,
+
+            name: &str,
+
+            model: &str,
+
+            serial_number: &str,
+
+            /// `AirplaneType` as a number.
+            airplane_type: u8,
+        }
+    }
+
+    encoding_struct! {
+        struct AirplaneServiceConfig {
+            /// Lower bound for `engine_heating_time_seconds` in `TxEndTechnicalCheck`.
+            min_heating_seconds: u32,
+
+            /// Upper bound for `engine_heating_time_seconds` in `TxEndTechnicalCheck`.
+            max_heating_seconds: u32,
+
+            /// Minimum time between the end of a flight and the next `TxStartTechnicalCheck`.
+            cooling_period_seconds: u32,
+
+            /// Time in `HeatingEngine` after which validators submit `TxExpireHeating`.
+            max_heating_wait_seconds: u32,
+
+            /// Minimum `fuel_level_percent` required by `TxStartFlying`.
+            min_fuel_percent_for_flight: u8,
+
+            /// Safety authority allowed to submit `TxGroundAirplane`; the zero key if there is none.
+            admin_pub_key: &PublicKey,
+
+            /// Maximum number of airplanes that are not decommissioned; `0` for no limit.
+            max_fleet_size: u64,
+
+            /// Time to complete a technical check before `TxRequestMaintenanceExtension` is
+            /// needed; `0` for no deadline.
+            technical_check_window_seconds: u32,
+
+            /// Minimum time between the start of heating and `TxStartFlying`, per `AirplaneType`.
+            commercial_min_heating_seconds: u32,
+
+            cargo_min_heating_seconds: u32,
+
+            military_min_heating_seconds: u32,
+
+            private_min_heating_seconds: u32,
+
+            /// Number of airplanes that can be in a technical check at once. When set,
+            /// `TxStartTechnicalCheck` queues the airplane by priority; `0` for no limit.
+            max_concurrent_technical_checks: u32,
+
+            /// Number of blocks after `created_at_height` in which a transaction can still
+            /// be executed; `0` for no expiry.
+            tx_expiry_blocks: u32,
+
+            /// Number of transactions per nonce key that can succeed in one block; `0` for
+            /// no limit. Failed transactions are not counted, so this limits how fast a key
+            /// can change state, not how many invalid transactions it can flood the pool with.
+            max_tx_per_block_per_key: u32,
+
+            /// Visibility at the home airport required by `TxStartFlying` when a weather
+            /// report exists for it.
+            min_visibility_meters: u32,
+
+            /// Time from registration to `Airplane::registration_expiry`.
+            registration_validity_days: u32,
+        }
+    }
+
+    impl AirplaneServiceConfig {
+        /// Checks `seconds` against `min_heating_seconds..=max_heating_seconds`.
+        pub fn allows_heating_time(&self, seconds: u32) -> bool {
+            (self.min_heating_seconds()..=self.max_heating_seconds()).contains(&seconds)
+        }
+
+        /// Default configuration with the given safety authority key.
+        pub fn with_admin(admin_pub_key: Option<PublicKey>) -> Self {
+            AirplaneServiceConfig::new(
+                DEFAULT_MIN_HEATING_SECONDS,
+                DEFAULT_MAX_HEATING_SECONDS,
+                DEFAULT_COOLING_PERIOD_SECONDS,
+                DEFAULT_MAX_HEATING_WAIT_SECONDS,
+                DEFAULT_MIN_FUEL_PERCENT_FOR_FLIGHT,
+                &admin_pub_key.unwrap_or_else(PublicKey::zero),
+                DEFAULT_MAX_FLEET_SIZE,
+                DEFAULT_TECHNICAL_CHECK_WINDOW_SECONDS,
+                DEFAULT_COMMERCIAL_MIN_HEATING_SECONDS,
+                DEFAULT_CARGO_MIN_HEATING_SECONDS,
+                DEFAULT_MILITARY_MIN_HEATING_SECONDS,
+                DEFAULT_PRIVATE_MIN_HEATING_SECONDS,
+                DEFAULT_MAX_CONCURRENT_TECHNICAL_CHECKS,
+                DEFAULT_TX_EXPIRY_BLOCKS,
+                DEFAULT_MAX_TX_PER_BLOCK_PER_KEY,
+                DEFAULT_MIN_VISIBILITY_METERS,
+                DEFAULT_REGISTRATION_VALIDITY_DAYS,
+            )
+        }
+
+        /// Minimum heating time before `TxStartFlying` for airplanes of `airplane_type`.
+        pub fn min_heating_seconds_for(&self, airplane_type: AirplaneType) -> u32 {
+            match airplane_type {
+                AirplaneType::Commercial => self.commercial_min_heating_seconds(),
+                AirplaneType::Cargo => self.cargo_min_heating_seconds(),
+                AirplaneType::Military => self.military_min_heating_seconds(),
+                AirplaneType::Private => self.private_min_heating_seconds(),
+            }
+        }
+
+        /// Returns the safety authority key, if one is configured.
+        pub fn admin(&self) -> Option<PublicKey> {
+            Some(*self.admin_pub_key()).filter(|key| *key != PublicKey::zero())
+        }
+
+        /// Returns the fleet size limit, if one is configured.
+        pub fn fleet_limit(&self) -> Option<u64> {
+            Some(self.max_fleet_size()).filter(|&limit| limit != 0)
+        }
+
+        /// Returns the number of technical check slots, if it is limited.
+        pub fn technical_check_slots(&self) -> Option<u64> {
+            Some(u64::from(self.max_concurrent_technical_checks())).filter(|&slots| slots != 0)
+        }
+
+        /// Checks that the heating bounds are ordered, that every per-type minimum can be
+        /// reached before `TxExpireHeating` resets the airplane, and that the fuel minimum
+        /// is a percentage.
+        pub fn validate(&self) -> Result<(), Error> {
+            let type_minimums_reachable = AirplaneType::all().into_iter().all(|airplane_type| {
+                self.min_heating_seconds_for(airplane_type) <= self.max_heating_wait_seconds()
+            });
+            if self.min_heating_seconds() > self.max_heating_seconds()
+                || !type_minimums_reachable
+                || self.min_fuel_percent_for_flight() > MAX_FUEL_LEVEL_PERCENT
+            {
+                Err(Error::InvalidConfig)?
+            }
+            Ok(())
+        }
+    }
+
+    impl Default for AirplaneServiceConfig {
+        fn default() -> Self {
+            AirplaneServiceConfig::with_admin(None)
+        }
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    #[repr(u8)]
+    pub enum AirplaneEventType {
+        Registered = 0,
+
+        TechnicalCheckStarted = 1,
+
+        TechnicalCheckEnded = 2,
+
+        HeatingStarted = 3,
+
+        FlightStarted = 4,
+
+        FlightEnded = 5,
+    }
+
+    encoding_struct! {
+        struct AirplaneEvent {
+            event_type: u8,
+
+            /// Outcome of the check for `TechnicalCheckEnded` events, `false` otherwise.
+            passed: bool,
+
+            timestamp: DateTime<Utc>,
+        }
+    }
+
+    encoding_struct! {
+        /// Result of a single technical check.
+        struct MaintenanceRecord {
+            airplane_pub_key: &PublicKey,
+
+            timestamp: DateTime<Utc>,
+
+            passed: bool,
+
+            technician_notes: &str,
+
+            /// Heating time assigned by the check, `0` if the check failed.
+            engine_heating_time_seconds: u32,
+        }
+    }
+
+    encoding_struct! {
+        /// Operational incident reported against an airplane. Incidents do not change
+        /// the airplane's state.
+        struct Incident {
+            airplane_pub_key: &PublicKey,
+
+            reported_at: DateTime<Utc>,
+
+            severity: u8,
+
+            description: &str,
+
+            reporter_pub_key: &PublicKey,
+        }
+    }
+
+    encoding_struct! {
+        /// Airplane layout of schema version 1, kept to migrate old databases.
+        struct AirplaneV1 {
+            pub_key: &PublicKey,
+
+            name: &str,
+
+            state_number: u8,
+
+            state_str: &str,
+
+            engine_heating_start_time: DateTime<Utc>,
+
+            engine_heating_time_seconds: u16,
+        }
+    }
+
+    encoding_struct! {
+        /// `Clone` and `PartialEq` are derived by `encoding_struct!`; equality compares the
+        /// serialized bytes, so timestamps must match to the nanosecond.
+        struct Airplane {
+            pub_key: &PublicKey,
+
+            name: &str,
+
+            state_number: u8,
+
+            state_str: &str,
+
+            engine_heating_start_time: DateTime<Utc>,
+
+            /// Total time needed for heating.
+            engine_heating_time_seconds: u32,
+
+            /// Number of completed flights.
+            flight_count: u32,
+
+            /// Total time spent in the air. Saturates at `u64::MAX` seconds, which is
+            /// far beyond any realistic airframe lifetime.
+            accumulated_flight_seconds: u64,
+
+            /// Start time of the current flight.
+            flight_start_time: DateTime<Utc>,
+
+            /// Reason given in the latest `TxEmergencyLanding`.
+            last_emergency_reason: &str,
+
+            /// Number of resolved emergencies.
+            emergency_count: u16,
+
+            airplane_model: &str,
+
+            serial_number: &str,
+
+            /// End time of the latest flight; the epoch if the airplane has never flown.
+            last_flight_end_time: DateTime<Utc>,
+
+            /// Reason given in the latest `TxCancelFlight`.
+            last_cancellation_reason: &str,
+
+            /// Number of cancelled flights. Cancelled flights do not count towards `flight_count`.
+            cancellation_count: u16,
+
+            /// Fuel level, from 0 to `MAX_FUEL_LEVEL_PERCENT`.
+            fuel_level_percent: u8,
+
+            /// IATA code of the departure airport of the current flight; empty if not recorded.
+            current_departure_iata: &str,
+
+            /// IATA code of the arrival airport of the current flight; empty if not recorded.
+            current_arrival_iata: &str,
+
+            /// Time of the `TxRegisterAirplane` or batch registration that created the airplane.
+            registered_at: DateTime<Utc>,
+
+            /// Reason given by the safety authority in `TxGroundAirplane`; empty otherwise.
+            grounded_reason: &str,
+
+            /// Time by which `TxEndTechnicalCheck` must be submitted; the epoch if there is no deadline.
+            /// Only meaningful in `TechnicalCheck` and `TechnicalCheckExtended`.
+            technical_check_deadline: DateTime<Utc>,
+
+            /// `AirplaneType` as a number.
+            airplane_type: u8,
+
+            /// From `MIN_PRIORITY` to `MAX_PRIORITY`. Queued technical checks of airplanes
+            /// with a higher priority start first.
+            priority: u8,
+
+            /// Weight of the cargo on board, changed by `TxLoadCargo` and `TxUnloadCargo`.
+            cargo_weight_kg: u32,
+
+            /// `TxStartFlying` requires `cargo_weight_kg` not to exceed this; `0` if unknown, in
+            /// which case the cargo weight is not checked.
+            max_takeoff_weight_kg: u32,
+
+            /// Operator-defined tags as a JSON object of strings; see `Airplane::metadata_fields`.
+            metadata: &str,
+
+            /// Reset to `0` on landing.
+            passenger_count: u16,
+
+            /// Upper bound for `passenger_count`, set at registration.
+            max_passenger_capacity: u16,
+
+            /// `TxStartFlying` requires it to be in the future; `EPOCH` until the first
+            /// `TxRenewAirworthinessCertificate`.
+            airworthiness_cert_expiry: DateTime<Utc>,
+
+            /// `1 << flag` for every `RepairFlag` pending repair; `TxStartTechnicalCheck`
+            /// requires it to be `0`.
+            required_repairs_bitmask: u8,
+
+            /// IATA code set with `TxSetHomeAirport`; empty until then. `TxStartFlying`
+            /// checks the weather reported for it.
+            home_airport: &str,
+
+            /// `registered_at` plus `registration_validity_days`, moved by
+            /// `TxRenewRegistration`. `TxStartTechnicalCheck` requires it to be in the future.
+            registration_expiry: DateTime<Utc>,
+
+            /// Time of the last `TxDeactivateAirplane`; `EPOCH` unless the airplane is `Inactive`.
+            inactive_since: DateTime<Utc>,
+
+            /// Time of the last change of `state_number`; registration time until the first transition.
+            last_state_change_time: DateTime<Utc>,
+
+            /// Seconds spent in each earlier state, indexed by `AirplaneState` discriminant. Trailing
+            /// states that were never left are omitted.
+            time_in_state_seconds: Vec<u64>,
+        }
+    }
+
+    encoding_struct! {
+        /// Entry of the fleet-wide `global_audit` log.
+        struct TimelineEntry {
+            airplane_pub_key: &PublicKey,
+
+            audit_entry: AuditEntry,
+        }
+    }
+
+    encoding_struct! {
+        struct AuditEntry {
+            tx_hash: &Hash,
+
+            block_height: u64,
+
+            old_state: u8,
+
+            new_state: u8,
+
+            timestamp: DateTime<Utc>,
+        }
+    }
+
+    encoding_struct! {
+        /// Issuer of airworthiness certificates, registered by the safety authority.
+        struct CertificationAuthority {
+            pub_key: &PublicKey,
+
+            name: &str,
+
+            /// Cleared by `TxRevokeAuthority`; revoked authorities cannot issue
+            /// certificates.
+            is_active: bool,
+        }
+    }
+
+    encoding_struct! {
+        /// Latest conditions at an airport, recorded by `TxRecordWeatherConditions`.
+        struct WeatherReport {
+            airport_iata: &str,
+
+            visibility_meters: u32,
+
+            wind_speed_knots: u16,
+
+            recorded_at: DateTime<Utc>,
+        }
+    }
+
+    encoding_struct! {
+        /// Transactions counted for a key by `Schema::count_transaction`.
+        struct BlockTxCount {
+            block_height: u64,
+
+            count: u32,
+        }
+    }
+
+    encoding_struct! {
+        struct CompletedRoute {
+            departure: &str,
+
+            arrival: &str,
+
+            duration_seconds: u64,
+        }
+    }
+
+    encoding_struct! {
+        struct FlightLogEntry {
+            /// One-based number of the flight, in the order the flights ended.
+            flight_number: u32,
+
+            start_time: DateTime<Utc>,
+
+            end_time: DateTime<Utc>,
+
+            duration_seconds: u64,
+
+            /// Empty if no route was recorded for the flight.
+            departure_iata: &str,
+
+            arrival_iata: &str,
+
+            fuel_consumed_percent: u8,
+        }
+    }
+
+    encoding_struct! {
+        struct ScheduledCheck {
+            airplane_pub_key: &PublicKey,
+
+            scheduled_at: DateTime<Utc>,
+
+            /// Airplane or operator key that signed the booking.
+            scheduled_by: &PublicKey,
+        }
+    }
+
+    encoding_struct! {
+        /// Flight submitted ahead of time with `TxPlanFlight`.
+        struct FlightPlan {
+            /// Assigned in submission order, starting from `0`.
+            plan_id: u64,
+
+            airplane_pub_key: &PublicKey,
+
+            departure_iata: &str,
+
+            arrival_iata: &str,
+
+            planned_departure: DateTime<Utc>,
+
+            planned_arrival: DateTime<Utc>,
+
+            /// `FLIGHT_PLAN_PLANNED` for every plan submitted so far.
+            status: u8,
+        }
+    }
+
+    /// `FlightPlan::status` of a submitted plan.
+    pub const FLIGHT_PLAN_PLANNED: u8 = 0;
+
+    encoding_struct! {
+        /// Limit on the airplanes registered by a key, set with `TxSetOperatorQuota`.
+        struct OperatorQuota {
+            operator_pub_key: &PublicKey,
+
+            max_airplanes: u32,
+
+            /// Registered airplanes that are not decommissioned.
+            current_count: u32,
+        }
+    }
+
+    encoding_struct! {
+        struct Runway {
+            name: &str,
+
+            is_occupied: bool,
+
+            /// Zero key when the runway is free; check `is_occupied` first.
+            assigned_airplane: &PublicKey,
+        }
+    }
+
+    impl Airplane {
+        /// Decodes `state_number`.
+        pub fn state(&self) -> Result<AirplaneState, Error> {
+            AirplaneState::try_from(self.state_number())
+        }
+
+        /// Decodes `airplane_type`.
+        pub fn kind(&self) -> Result<AirplaneType, Error> {
+            AirplaneType::try_from(self.airplane_type())
+        }
+
+        /// Seconds until `engine_heating_time_seconds` have passed since the heating started,
+        /// `0` once they have, or `None` outside `HeatingEngine`. The per-type minimum
+        /// checked by `TxStartFlying` is not taken into account.
+        pub fn remaining_heating_seconds(&self, current_time: DateTime<Utc>) -> Option<i64> {
+            if self.state_number() != u8::from(AirplaneState::HeatingEngine) {
+                return None;
+            }
+            let elapsed = (current_time - self.engine_heating_start_time()).num_seconds();
+            Some((i64::from(self.engine_heating_time_seconds()) - elapsed).max(0))
+        }
+
+        /// Seconds spent in `state` up to `current_time`, including the time since the last
+        /// transition if the airplane is still in `state`.
+        pub fn time_in_state(&self, state: AirplaneState, current_time: DateTime<Utc>) -> u64 {
+            let earlier = self
+                .time_in_state_seconds()
+                .get(usize::from(u8::from(state)))
+                .cloned()
+                .unwrap_or(0);
+            if self.state_number() == u8::from(state) {
+                earlier + seconds_between(self.last_state_change_time(), current_time)
+            } else {
+                earlier
+            }
+        }
+
+        /// Errors the dispatch of the airplane would fail with at `current_time`: an expired
+        /// airworthiness certificate or registration, no fuel or less than the configured
+        /// minimum, or pending repairs. The state of the airplane is not checked.
+        pub fn dispatch_blockers(
+            &self,
+            config: &AirplaneServiceConfig,
+            current_time: DateTime<Utc>,
+        ) -> Vec<Error> {
+            let mut blockers = Vec::new();
+            if self.airworthiness_cert_expiry() <= current_time {
+                blockers.push(Error::AirworthinessCertificateExpired);
+            }
+            if self.fuel_level_percent() == 0
+                || self.fuel_level_percent() < config.min_fuel_percent_for_flight()
+            {
+                blockers.push(Error::InsufficientFuel);
+            }
+            if self.required_repairs_bitmask() != 0 {
+                blockers.push(Error::RepairsRequired);
+            }
+            if self.registration_expiry() <= current_time {
+                blockers.push(Error::RegistrationExpired);
+            }
+            blockers
+        }
+
+        /// Decodes `required_repairs_bitmask` into its flags, in discriminant order.
+        pub fn required_repairs(&self) -> Vec<RepairFlag> {
+            RepairFlag::all()
+                .into_iter()
+                .filter(|flag| self.required_repairs_bitmask() & flag.bit() != 0)
+                .collect()
+        }
+
+        /// Decodes `metadata`. A `BTreeMap` keeps the keys sorted, so re-encoding the fields
+        /// gives the same bytes on every node.
+        pub fn metadata_fields(&self) -> BTreeMap<String, String> {
+            serde_json::from_str(self.metadata()).unwrap_or_default()
+        }
+
+        /// Copies the airplane into `new_state`, resetting the engine heating and
+        /// flight start times to the epoch. Counters and other fields are preserved.
+        pub fn transition_state(&self, new_state: AirplaneState) -> Airplane {
+            Airplane::builder_from(self)
+                .state(new_state)
+                .clear_heating()
+                .clear_flight_start()
+                .build()
+        }
+
+        /// Like `transition_state`, but records a new engine heating start time and duration.
+        pub fn transition_state_with_time(
+            &self,
+            new_state: AirplaneState,
+            time: DateTime<Utc>,
+            heating_secs: u32,
+        ) -> Airplane {
+            Airplane::builder_from(self)
+                .state(new_state)
+                .heating_start_time(time)
+                .heating_time_seconds(heating_secs)
+                .clear_flight_start()
+                .build()
+        }
+
+        /// Starts a builder with all fields copied from `existing`, except `state_str`, which
+        /// is derived from `state_number` again.
+        pub fn builder_from(existing: &Airplane) -> AirplaneBuilder {
+            AirplaneBuilder {
+                pub_key: *existing.pub_key(),
+                name: existing.name().to_owned(),
+                state_number: existing.state_number(),
+                engine_heating_start_time: existing.engine_heating_start_time(),
+                engine_heating_time_seconds: existing.engine_heating_time_seconds(),
+                flight_count: existing.flight_count(),
+                accumulated_flight_seconds: existing.accumulated_flight_seconds(),
+                flight_start_time: existing.flight_start_time(),
+                last_emergency_reason: existing.last_emergency_reason().to_owned(),
+                emergency_count: existing.emergency_count(),
+                airplane_model: existing.airplane_model().to_owned(),
+                serial_number: existing.serial_number().to_owned(),
+                last_flight_end_time: existing.last_flight_end_time(),
+                last_cancellation_reason: existing.last_cancellation_reason().to_owned(),
+                cancellation_count: existing.cancellation_count(),
+                fuel_level_percent: existing.fuel_level_percent(),
+                current_departure_iata: existing.current_departure_iata().to_owned(),
+                current_arrival_iata: existing.current_arrival_iata().to_owned(),
+                registered_at: existing.registered_at(),
+                grounded_reason: existing.grounded_reason().to_owned(),
+                technical_check_deadline: existing.technical_check_deadline(),
+                airplane_type: existing.airplane_type(),
+                priority: existing.priority(),
+                cargo_weight_kg: existing.cargo_weight_kg(),
+                max_takeoff_weight_kg: existing.max_takeoff_weight_kg(),
+                metadata: existing.metadata().to_owned(),
+                passenger_count: existing.passenger_count(),
+                max_passenger_capacity: existing.max_passenger_capacity(),
+                airworthiness_cert_expiry: existing.airworthiness_cert_expiry(),
+                required_repairs_bitmask: existing.required_repairs_bitmask(),
+                home_airport: existing.home_airport().to_owned(),
+                registration_expiry: existing.registration_expiry(),
+                inactive_since: existing.inactive_since(),
+                last_state_change_time: existing.last_state_change_time(),
+                time_in_state_seconds: existing.time_in_state_seconds(),
+            }
+        }
+    }
+
+    /// Whole seconds from `from` to `to`, or `0` if `to` is not later.
+    fn seconds_between(from: DateTime<Utc>, to: DateTime<Utc>) -> u64 {
+        u64::try_from((to - from).num_seconds()).unwrap_or(0)
+    }
+
+    /// Copies `existing` into `state`, setting `state_number` and `state_str` together.
+    pub fn airplane_with_state(existing: &Airplane, state: AirplaneState) -> Airplane {
+        Airplane::builder_from(existing).state(state).build()
+    }
+
+    /// Named-field alternative to the positional `Airplane::new`.
+    #[derive(Debug, Clone)]
+    pub struct AirplaneBuilder {
+        pub_key: PublicKey,
+        name: String,
+        state_number: u8,
+        engine_heating_start_time: DateTime<Utc>,
+        engine_heating_time_seconds: u32,
+        flight_count: u32,
+        accumulated_flight_seconds: u64,
+        flight_start_time: DateTime<Utc>,
+        last_emergency_reason: String,
+        emergency_count: u16,
+        airplane_model: String,
+        serial_number: String,
+        last_flight_end_time: DateTime<Utc>,
+        last_cancellation_reason: String,
+        cancellation_count: u16,
+        fuel_level_percent: u8,
+        current_departure_iata: String,
+        current_arrival_iata: String,
+        registered_at: DateTime<Utc>,
+        grounded_reason: String,
+        technical_check_deadline: DateTime<Utc>,
+        airplane_type: u8,
+        priority: u8,
+        cargo_weight_kg: u32,
+        max_takeoff_weight_kg: u32,
+        metadata: String,
+        passenger_count: u16,
+        max_passenger_capacity: u16,
+        airworthiness_cert_expiry: DateTime<Utc>,
+        required_repairs_bitmask: u8,
+        home_airport: String,
+        registration_expiry: DateTime<Utc>,
+        inactive_since: DateTime<Utc>,
+        last_state_change_time: DateTime<Utc>,
+        time_in_state_seconds: Vec<u64>,
+    }
+
+    impl AirplaneBuilder {
+        /// Starts a builder for a freshly registered airplane waiting for its first flight.
+        pub fn new(pub_key: &PublicKey, name: &str, model: &str, serial_number: &str) -> Self {
+            AirplaneBuilder {
+                pub_key: *pub_key,
+                name: name.to_owned(),
+                state_number: u8::from(AirplaneState::WaitingForFlight),
+                engine_heating_start_time: EPOCH,
+                engine_heating_time_seconds: 0,
+                flight_count: 0,
+                accumulated_flight_seconds: 0,
+                flight_start_time: EPOCH,
+                last_emergency_reason: String::new(),
+                emergency_count: 0,
+                airplane_model: model.to_owned(),
+                serial_number: serial_number.to_owned(),
+                last_flight_end_time: EPOCH,
+                last_cancellation_reason: String::new(),
+                cancellation_count: 0,
+                fuel_level_percent: MAX_FUEL_LEVEL_PERCENT,
+                current_departure_iata: String::new(),
+                current_arrival_iata: String::new(),
+                registered_at: EPOCH,
+                grounded_reason: String::new(),
+                technical_check_deadline: EPOCH,
+                airplane_type: AirplaneType::Commercial as u8,
+                priority: DEFAULT_PRIORITY,
+                cargo_weight_kg: 0,
+                max_takeoff_weight_kg: 0,
+                metadata: "{}".to_owned(),
+                passenger_count: 0,
+                max_passenger_capacity: 0,
+                airworthiness_cert_expiry: EPOCH,
+                required_repairs_bitmask: 0,
+                home_airport: String::new(),
+                registration_expiry: EPOCH,
+                inactive_since: EPOCH,
+                last_state_change_time: EPOCH,
+                time_in_state_seconds: Vec::new(),
+            }
+        }
+
+        pub fn pub_key(mut self, pub_key: &PublicKey) -> Self {
+            self.pub_key = *pub_key;
+            self
+        }
+
+        pub fn name(mut self, name: &str) -> Self {
+            self.name = name.to_owned();
+            self
+        }
+
+        /// Sets `state_number`; `build` derives `state_str` from it.
+        pub fn state(mut self, state: AirplaneState) -> Self {
+            self.state_number = u8::from(state);
+            self
+        }
+
+        pub fn heating_start_time(mut self, time: DateTime<Utc>) -> Self {
+            self.engine_heating_start_time = time;
+            self
+        }
+
+        pub fn heating_time_seconds(mut self, seconds: u32) -> Self {
+            self.engine_heating_time_seconds = seconds;
+            self
+        }
+
+        /// Resets the heating start time to the epoch and the heating time to zero.
+        pub fn clear_heating(self) -> Self {
+            self.heating_start_time(EPOCH).heating_time_seconds(0)
+        }
+
+        pub fn flight_count(mut self, flight_count: u32) -> Self {
+            self.flight_count = flight_count;
+            self
+        }
+
+        pub fn accumulated_flight_seconds(mut self, seconds: u64) -> Self {
+            self.accumulated_flight_seconds = seconds;
+            self
+        }
+
+        pub fn flight_start_time(mut self, time: DateTime<Utc>) -> Self {
+            self.flight_start_time = time;
+            self
+        }
+
+        /// Resets the flight start time to the epoch.
+        pub fn clear_flight_start(self) -> Self {
+            self.flight_start_time(EPOCH)
+        }
+
+        pub fn last_emergency_reason(mut self, reason: &str) -> Self {
+            self.last_emergency_reason = reason.to_owned();
+            self
+        }
+
+        pub fn emergency_count(mut self, emergency_count: u16) -> Self {
+            self.emergency_count = emergency_count;
+            self
+        }
+
+        pub fn last_flight_end_time(mut self, time: DateTime<Utc>) -> Self {
+            self.last_flight_end_time = time;
+            self
+        }
+
+        pub fn last_cancellation_reason(mut self, reason: &str) -> Self {
+            self.last_cancellation_reason = reason.to_owned();
+            self
+        }
+
+        pub fn cancellation_count(mut self, cancellation_count: u16) -> Self {
+            self.cancellation_count = cancellation_count;
+            self
+        }
+
+        pub fn fuel_level_percent(mut self, fuel_level_percent: u8) -> Self {
+            self.fuel_level_percent = fuel_level_percent;
+            self
+        }
+
+        /// Sets the departure and arrival airports of the current flight.
+        pub fn route(mut self, departure_iata: &str, arrival_iata: &str) -> Self {
+            self.current_departure_iata = departure_iata.to_owned();
+            self.current_arrival_iata = arrival_iata.to_owned();
+            self
+        }
+
+        /// Forgets the route of the current flight.
+        pub fn clear_route(self) -> Self {
+            self.route("", "")
+        }
+
+        pub fn registered_at(mut self, registered_at: DateTime<Utc>) -> Self {
+            self.registered_at = registered_at;
+            self
+        }
+
+        pub fn grounded_reason(mut self, grounded_reason: &str) -> Self {
+            self.grounded_reason = grounded_reason.to_owned();
+            self
+        }
+
+        pub fn technical_check_deadline(mut self, technical_check_deadline: DateTime<Utc>) -> Self {
+            self.technical_check_deadline = technical_check_deadline;
+            self
+        }
+
+        pub fn airplane_type(mut self, airplane_type: AirplaneType) -> Self {
+            self.airplane_type = airplane_type as u8;
+            self
+        }
+
+        pub fn priority(mut self, priority: u8) -> Self {
+            self.priority = priority;
+            self
+        }
+
+        pub fn cargo_weight_kg(mut self, cargo_weight_kg: u32) -> Self {
+            self.cargo_weight_kg = cargo_weight_kg;
+            self
+        }
+
+        pub fn max_takeoff_weight_kg(mut self, max_takeoff_weight_kg: u32) -> Self {
+            self.max_takeoff_weight_kg = max_takeoff_weight_kg;
+            self
+        }
+
+        pub fn metadata(mut self, metadata: &str) -> Self {
+            self.metadata = metadata.to_owned();
+            self
+        }
+
+        pub fn passenger_count(mut self, passenger_count: u16) -> Self {
+            self.passenger_count = passenger_count;
+            self
+        }
+
+        pub fn max_passenger_capacity(mut self, max_passenger_capacity: u16) -> Self {
+            self.max_passenger_capacity = max_passenger_capacity;
+            self
+        }
+
+        pub fn airworthiness_cert_expiry(
+            mut self,
+            airworthiness_cert_expiry: DateTime<Utc>,
+        ) -> Self {
+            self.airworthiness_cert_expiry = airworthiness_cert_expiry;
+            self
+        }
+
+        pub fn required_repairs_bitmask(mut self, required_repairs_bitmask: u8) -> Self {
+            self.required_repairs_bitmask = required_repairs_bitmask;
+            self
+        }
+
+        pub fn home_airport(mut self, home_airport: &str) -> Self {
+            self.home_airport = home_airport.to_owned();
+            self
+        }
+
+        pub fn registration_expiry(mut self, registration_expiry: DateTime<Utc>) -> Self {
+            self.registration_expiry = registration_expiry;
+            self
+        }
+
+        pub fn inactive_since(mut self, inactive_since: DateTime<Utc>) -> Self {
+            self.inactive_since = inactive_since;
+            self
+        }
+
+        pub fn last_state_change_time(mut self, last_state_change_time: DateTime<Utc>) -> Self {
+            self.last_state_change_time = last_state_change_time;
+            self
+        }
+
+        /// Adds `seconds` to the time spent in `state`.
+        pub fn add_time_in_state(mut self, state: AirplaneState, seconds: u64) -> Self {
+            let index = usize::from(u8::from(state));
+            if self.time_in_state_seconds.len() <= index {
+                self.time_in_state_seconds.resize(index + 1, 0);
+            }
+            self.time_in_state_seconds[index] += seconds;
+            self
+        }
+
+        /// Builds the airplane with `state_str` set to the display name of `state_number`,
+        /// or empty if the number is not a known state.
+        pub fn build(self) -> Airplane {
+            let state_str = AirplaneState::try_from(self.state_number)
+                .map(|state| state.to_string())
+                .unwrap_or_default();
+            Airplane::new(
+                &self.pub_key,
+                &self.name,
+                self.state_number,
+                &state_str,
+                self.engine_heating_start_time,
+                self.engine_heating_time_seconds,
+                self.flight_count,
+                self.accumulated_flight_seconds,
+                self.flight_start_time,
+                &self.last_emergency_reason,
+                self.emergency_count,
+                &self.airplane_model,
+                &self.serial_number,
+                self.last_flight_end_time,
+                &self.last_cancellation_reason,
+                self.cancellation_count,
+                self.fuel_level_percent,
+                &self.current_departure_iata,
+                &self.current_arrival_iata,
+                self.registered_at,
+                &self.grounded_reason,
+                self.technical_check_deadline,
+                self.airplane_type,
+                self.priority,
+                self.cargo_weight_kg,
+                self.max_takeoff_weight_kg,
+                &self.metadata,
+                self.passenger_count,
+                self.max_passenger_capacity,
+                self.airworthiness_cert_expiry,
+                self.required_repairs_bitmask,
+                &self.home_airport,
+                self.registration_expiry,
+                self.inactive_since,
+                self.last_state_change_time,
+                self.time_in_state_seconds,
+            )
+        }
+    }
+
+    /// Index shared by all service instances, so its name carries no prefix.
+    const SERVICE_INSTANCES: &str = "airplane_service_instances";
+
+    /// Names of the initialized service instances keyed by their ID.
+    pub fn service_instances(view: &dyn Snapshot) -> MapIndex<&dyn Snapshot, u16, String> {
+        MapIndex::new(SERVICE_INSTANCES, view)
+    }
+
+    /// The indexes of the `SERVICE_NAME` instance keep their unprefixed names, so databases
+    /// created before the service could run under another name stay readable. Every other
+    /// instance prefixes its index names with its own name, so two instances in one
+    /// blockchain never share data.
+    fn index_name(service_name: &str, name: &str) -> String {
+        if service_name == SERVICE_NAME {
+            name.to_owned()
+        } else {
+            format!("{}.{}", service_name, name)
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct Schema<T> {
+        view: T,
+
+        service_name: String,
+    }
+
+    impl<T: AsRef<dyn Snapshot>> Schema<T> {
+        /// Schema of the service instance named `SERVICE_NAME`.
+        pub fn new(view: T) -> Self {
+            Schema::for_service(view, SERVICE_NAME)
+        }
+
+        /// Schema of the service instance named `service_name`; see `index_name`.
+        pub fn for_service(view: T, service_name: &str) -> Self {
+            Schema {
+                view,
+                service_name: service_name.to_owned(),
+            }
+        }
+
+        /// Schema of the service instance with the given ID. Transactions only carry the
+        /// ID of their instance, so the name is looked up in `service_instances`.
+        ///
+        /// # Panics
+        ///
+        /// If no instance with `service_id` has been initialized; `SERVICE_ID` falls back
+        /// to `SERVICE_NAME` for databases created before instances were recorded.
+        pub fn for_service_id(view: T, service_id: u16) -> Self {
+            let service_name = service_instances(view.as_ref())
+                .get(&service_id)
+                .or_else(|| {
+                    if service_id == SERVICE_ID {
+                        Some(SERVICE_NAME.to_owned())
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or_else(|| panic!("Service instance {} is not initialized", service_id));
+            Schema { view, service_name }
+        }
+
+        /// Name of the instance the schema belongs to.
+        pub fn service_name(&self) -> &str {
+            &self.service_name
+        }
+
+        fn index_name(&self, name: &str) -> String {
+            index_name(&self.service_name, name)
+        }
+
+        pub fn airplanes(&self) -> ProofMapIndex<&dyn Snapshot, PublicKey, Airplane> {
+            ProofMapIndex::new(self.index_name("airplanes"), self.view.as_ref())
+        }
+
+        pub fn airplane(&self, pub_key: &PublicKey) -> Option<Airplane> {
+            debug!("Reading airplane {:?}", pub_key);
+            self.airplanes().get(pub_key)
+        }
+
+        pub fn airplane_proof(&self, pub_key: &PublicKey) -> MapProof<PublicKey, Airplane> {
+            self.airplanes().get_proof(*pub_key)
+        }
+
+        /// Keys of all airplanes, decommissioned ones included, in the order they were
+        /// registered. A transferred airplane keeps its place under the new key.
+        pub fn airplane_keys(&self) -> ListIndex<&dyn Snapshot, PublicKey> {
+            ListIndex::new(self.index_name("airplane_keys"), self.view.as_ref())
+        }
+
+        /// Position of each key in `airplane_keys`.
+        pub fn airplane_key_positions(&self) -> MapIndex<&dyn Snapshot, PublicKey, u64> {
+            MapIndex::new(
+                self.index_name("airplane_key_positions"),
+                self.view.as_ref(),
+            )
+        }
+
+        /// All airplanes, decommissioned ones included, in registration order.
+        pub fn airplanes_in_order<'a>(&'a self) -> impl Iterator<Item = Airplane> + 'a {
+            let keys = self.airplane_keys();
+            (0..keys.len())
+                .filter_map(move |position| keys.get(position))
+                .filter_map(move |pub_key| self.airplane(&pub_key))
+        }
+
+        /// Returns a page of the airplanes that are not decommissioned, in registration order.
+        pub fn airplanes_page(&self, params: PaginationParams) -> PaginatedResponse<Airplane> {
+            PaginatedResponse::from_iter(
+                self.airplanes_in_order().filter(|airplane| {
+                    airplane.state_number() != u8::from(AirplaneState::Decommissioned)
+                }),
+                params,
+            )
+        }
+
+        /// Looks up an airplane only if it is decommissioned. Decommissioned airplanes keep
+        /// their entry in `airplanes`, along with their logs, so their history and proofs
+        /// stay available.
+        pub fn decommissioned_airplane(&self, pub_key: &PublicKey) -> Option<Airplane> {
+            self.airplane(pub_key).filter(|airplane| {
+                airplane.state_number() == u8::from(AirplaneState::Decommissioned)
+            })
+        }
+
+        /// Returns every decommissioned airplane, ordered by public key.
+        pub fn all_decommissioned(&self) -> Vec<Airplane> {
+            self.airplanes_by_state(AirplaneState::Decommissioned)
+                .iter()
+                .filter_map(|pub_key| self.airplane(&pub_key))
+                .collect()
+        }
+
+        /// Returns the airplanes deactivated before `inactive_before`, ordered by public key.
+        pub fn inactive_airplanes(&self, inactive_before: DateTime<Utc>) -> Vec<Airplane> {
+            self.airplanes_by_state(AirplaneState::Inactive)
+                .iter()
+                .filter_map(|pub_key| self.airplane(&pub_key))
+                .filter(|airplane| airplane.inactive_since() < inactive_before)
+                .collect()
+        }
+
+        /// Airplanes in `WaitingForFlight`, ordered by public key, with the reasons each
+        /// cannot be dispatched at `current_time`; see `Airplane::dispatch_blockers`.
+        pub fn dispatch_readiness(&self, current_time: DateTime<Utc>) -> Vec<AirplaneReadiness> {
+            let config = self.service_config();
+            self.airplanes_by_state(AirplaneState::WaitingForFlight)
+                .iter()
+                .filter_map(|pub_key| self.airplane(&pub_key))
+                .map(|airplane| AirplaneReadiness {
+                    blocking_reasons: airplane
+                        .dispatch_blockers(&config, current_time)
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                    airplane,
+                })
+                .collect()
+        }
+
+        /// Maps normalized airplane names to the keys of their owners.
+        pub fn airplane_names(&self) -> MapIndex<&dyn Snapshot, String, PublicKey> {
+            MapIndex::new(self.index_name("airplane_names"), self.view.as_ref())
+        }
+
+        /// Maps serial numbers to the keys of their airplanes.
+        pub fn airplane_serials(&self) -> MapIndex<&dyn Snapshot, String, PublicKey> {
+            MapIndex::new(self.index_name("airplane_serials"), self.view.as_ref())
+        }
+
+        /// Operator keys allowed to act on behalf of an airplane, keyed by airplane key.
+        pub fn airplane_operators(&self) -> MapIndex<&dyn Snapshot, PublicKey, PublicKey> {
+            MapIndex::new(self.index_name("airplane_operators"), self.view.as_ref())
+        }
+
+        pub fn last_used_nonces(&self) -> MapIndex<&dyn Snapshot, PublicKey, u64> {
+            MapIndex::new(self.index_name("last_used_nonces"), self.view.as_ref())
+        }
+
+        /// Transactions executed for each nonce key in the last block it was used in.
+        pub fn tx_count_by_block(&self) -> MapIndex<&dyn Snapshot, PublicKey, BlockTxCount> {
+            MapIndex::new(self.index_name("tx_count_by_block"), self.view.as_ref())
+        }
+
+        /// Certification authorities keyed by their public key, including revoked ones.
+        pub fn authorities(&self) -> MapIndex<&dyn Snapshot, PublicKey, CertificationAuthority> {
+            MapIndex::new(self.index_name("authorities"), self.view.as_ref())
+        }
+
+        pub fn authority(&self, pub_key: &PublicKey) -> Option<CertificationAuthority> {
+            self.authorities().get(pub_key)
+        }
+
+        /// Latest weather report for each airport, keyed by IATA code.
+        pub fn weather_reports(&self) -> MapIndex<&dyn Snapshot, String, WeatherReport> {
+            MapIndex::new(self.index_name("weather_reports"), self.view.as_ref())
+        }
+
+        pub fn weather_report(&self, iata: &str) -> Option<WeatherReport> {
+            self.weather_reports().get(&iata.to_owned())
+        }
+
+        /// Number of committed blocks, which is also the height of the block being built.
+        pub fn height(&self) -> u64 {
+            CoreSchema::new(self.view.as_ref())
+                .block_hashes_by_height()
+                .len()
+        }
+
+        /// Fails with `TransactionExpired` if more than `tx_expiry_blocks` blocks were
+        /// committed since `created_at_height`.
+        ///
+        /// Exonum 0.9 messages do not record when they entered the pool, so the height
+        /// is part of the signed transaction and set by its author. Exonum commits each
+        /// transaction at most once and committed blocks are final, so the expiry only
+        /// bounds how long a transaction may wait in the pool; it does not affect a
+        /// transaction once it is in a block. An expired transaction is still committed,
+        /// with this error as its result, and does not use up its nonce.
+        pub fn check_not_expired(&self, created_at_height: u64) -> Result<(), Error> {
+            let expiry_blocks = u64::from(self.service_config().tx_expiry_blocks());
+            if expiry_blocks != 0 && self.height() > created_at_height.saturating_add(expiry_blocks)
+            {
+                Err(Error::TransactionExpired)?
+            }
+            Ok(())
+        }
+
+        /// Nonce of the last executed transaction for `pub_key`, `0` if there was none.
+        pub fn last_used_nonce(&self, pub_key: &PublicKey) -> u64 {
+            self.last_used_nonces().get(pub_key).unwrap_or(0)
+        }
+
+        /// Airplanes waiting for a technical check slot with the given priority, in the
+        /// order they were queued.
+        pub fn check_queue(&self, priority: u8) -> ListIndex<&dyn Snapshot, PublicKey> {
+            ListIndex::new_in_family(
+                self.index_name("priority_check_queue"),
+                &priority,
+                self.view.as_ref(),
+            )
+        }
+
+        /// Priority under which each queued airplane is stored in `check_queue`.
+        pub fn queued_check_priorities(&self) -> MapIndex<&dyn Snapshot, PublicKey, u8> {
+            MapIndex::new(
+                self.index_name("queued_check_priorities"),
+                self.view.as_ref(),
+            )
+        }
+
+        /// Every queued airplane in the order their checks will start: by priority, then
+        /// by the time they were queued.
+        pub fn queued_checks(&self) -> Vec<PublicKey> {
+            (MIN_PRIORITY..=MAX_PRIORITY)
+                .rev()
+                .flat_map(|priority| self.check_queue(priority).iter().collect::<Vec<_>>())
+                .collect()
+        }
+
+        /// The airplane whose technical check starts when a slot becomes free.
+        pub fn next_queued_check(&self) -> Option<PublicKey> {
+            (MIN_PRIORITY..=MAX_PRIORITY)
+                .rev()
+                .filter_map(|priority| self.check_queue(priority).get(0))
+                .next()
+        }
+
+        /// Checks whether fewer airplanes are in a technical check than the configured
+        /// number of slots.
+        pub fn has_free_check_slot(&self) -> bool {
+            let in_check = self
+                .airplanes_by_state(AirplaneState::TechnicalCheck)
+                .iter()
+                .count()
+                + self
+                    .airplanes_by_state(AirplaneState::TechnicalCheckExtended)
+                    .iter()
+                    .count();
+            self.service_config()
+                .technical_check_slots()
+                .is_none_or(|slots| (in_check as u64) < slots)
+        }
+
+        /// Looks up an active airplane by its name, ignoring case and surrounding whitespace.
+        pub fn find_by_name(&self, name: &str) -> Option<Airplane> {
+            self.airplane_names()
+                .get(&normalize_airplane_name(name))
+                .and_then(|pub_key| self.airplane(&pub_key))
+        }
+
+        pub fn airplane_operator(&self, pub_key: &PublicKey) -> Option<PublicKey> {
+            self.airplane_operators().get(pub_key)
+        }
+
+        pub fn airplanes_by_state(
+            &self,
+            state: AirplaneState,
+        ) -> KeySetIndex<&dyn Snapshot, PublicKey> {
+            KeySetIndex::new_in_family(
+                self.index_name("airplanes_by_state"),
+                &u8::from(state),
+                self.view.as_ref(),
+            )
+        }
+
+        pub fn airplanes_in_state(&self, state: AirplaneState) -> Vec<PublicKey> {
+            self.airplanes_by_state(state).iter().collect()
+        }
+
+        pub fn airplane_type_index(
+            &self,
+            airplane_type: AirplaneType,
+        ) -> KeySetIndex<&dyn Snapshot, PublicKey> {
+            KeySetIndex::new_in_family(
+                self.index_name("airplanes_by_type"),
+                &(airplane_type as u8),
+                self.view.as_ref(),
+            )
+        }
+
+        /// Returns the airplanes of `airplane_type`, ordered by public key.
+        pub fn airplanes_by_type(&self, airplane_type: AirplaneType) -> Vec<Airplane> {
+            self.airplane_type_index(airplane_type)
+                .iter()
+                .filter_map(|pub_key| self.airplane(&pub_key))
+                .collect()
+        }
+
+        pub fn events(&self, pub_key: &PublicKey) -> ProofListIndex<&dyn Snapshot, AirplaneEvent> {
+            ProofListIndex::new_in_family(
+                self.index_name("airplane_events"),
+                pub_key,
+                self.view.as_ref(),
+            )
+        }
+
+        pub fn maintenance_records(
+            &self,
+            pub_key: &PublicKey,
+        ) -> ListIndex<&dyn Snapshot, MaintenanceRecord> {
+            ListIndex::new_in_family(
+                self.index_name("maintenance_records"),
+                pub_key,
+                self.view.as_ref(),
+            )
+        }
+
+        pub fn incidents(&self, pub_key: &PublicKey) -> ListIndex<&dyn Snapshot, Incident> {
+            ListIndex::new_in_family(self.index_name("incidents"), pub_key, self.view.as_ref())
+        }
+
+        /// Number of incidents of `MAX_INCIDENT_SEVERITY` reported at or after `since`.
+        pub fn critical_incidents_since(&self, pub_key: &PublicKey, since: DateTime<Utc>) -> u64 {
+            self.incidents(pub_key)
+                .iter()
+                .filter(|incident| {
+                    incident.severity() == MAX_INCIDENT_SEVERITY && incident.reported_at() >= since
+                })
+                .count() as u64
+        }
+
+        /// Tamper-evident log of state changes of the airplane.
+        pub fn audit_log(&self, pub_key: &PublicKey) -> ProofListIndex<&dyn Snapshot, AuditEntry> {
+            ProofListIndex::new_in_family(self.index_name("audit_log"), pub_key, self.view.as_ref())
+        }
+
+        /// Entries of every airplane's audit log in the order they were appended. Entries
+        /// written before this log was introduced are only in the per-airplane logs.
+        pub fn global_audit(&self) -> ListIndex<&dyn Snapshot, TimelineEntry> {
+            ListIndex::new(self.index_name("global_audit"), self.view.as_ref())
+        }
+
+        /// Up to `MAX_TIMELINE_ENTRIES` state changes of the whole fleet with timestamps from
+        /// `from` to `to` inclusive, oldest first.
+        ///
+        /// The time oracle never goes back, so `global_audit` is ordered by timestamp and the
+        /// start of the window is found by binary search.
+        pub fn global_timeline(
+            &self,
+            from: DateTime<Utc>,
+            to: DateTime<Utc>,
+        ) -> Vec<TimelineEntry> {
+            let global_audit = self.global_audit();
+            let timestamp = |index| {
+                global_audit
+                    .get(index)
+                    .expect("index is within the log")
+                    .audit_entry()
+                    .timestamp()
+            };
+            let (mut low, mut high) = (0, global_audit.len());
+            while low < high {
+                let middle = low + (high - low) / 2;
+                if timestamp(middle) < from {
+                    low = middle + 1;
+                } else {
+                    high = middle;
+                }
+            }
+
+            global_audit
+                .iter_from(low)
+                .take_while(|entry| entry.audit_entry().timestamp() <= to)
+                .take(MAX_TIMELINE_ENTRIES)
+                .collect()
+        }
+
+        /// Merkle roots of the per-airplane audit logs; its own root is part of `state_hash`.
+        pub fn audit_roots(&self) -> ProofMapIndex<&dyn Snapshot, PublicKey, Hash> {
+            ProofMapIndex::new(self.index_name("audit_roots"), self.view.as_ref())
+        }
+
+        /// Routes of landed flights, oldest first.
+        pub fn completed_routes(
+            &self,
+            pub_key: &PublicKey,
+        ) -> ListIndex<&dyn Snapshot, CompletedRoute> {
+            ListIndex::new_in_family(
+                self.index_name("completed_routes"),
+                pub_key,
+                self.view.as_ref(),
+            )
+        }
+
+        /// Summaries of finished flights, oldest first.
+        pub fn flight_log(
+            &self,
+            pub_key: &PublicKey,
+        ) -> ProofListIndex<&dyn Snapshot, FlightLogEntry> {
+            ProofListIndex::new_in_family(
+                self.index_name("flight_log"),
+                pub_key,
+                self.view.as_ref(),
+            )
+        }
+
+        /// Booked technical checks in chronological order.
+        pub fn scheduled_checks(
+            &self,
+            pub_key: &PublicKey,
+        ) -> ListIndex<&dyn Snapshot, ScheduledCheck> {
+            ListIndex::new_in_family(
+                self.index_name("scheduled_checks"),
+                pub_key,
+                self.view.as_ref(),
+            )
+        }
+
+        /// Number of leading `scheduled_checks` entries that have already been started.
+        pub fn started_scheduled_checks(&self, pub_key: &PublicKey) -> u64 {
+            MapIndex::<_, PublicKey, u64>::new(
+                self.index_name("scheduled_checks_started"),
+                self.view.as_ref(),
+            )
+            .get(pub_key)
+            .unwrap_or(0)
+        }
+
+        pub fn next_scheduled_check(&self, pub_key: &PublicKey) -> Option<ScheduledCheck> {
+            self.scheduled_checks(pub_key)
+                .get(self.started_scheduled_checks(pub_key))
+        }
+
+        /// Airplane quotas keyed by the registering key they apply to.
+        pub fn operator_quotas(&self) -> MapIndex<&dyn Snapshot, PublicKey, OperatorQuota> {
+            MapIndex::new(self.index_name("operator_quotas"), self.view.as_ref())
+        }
+
+        pub fn operator_quota(&self, operator_pub_key: &PublicKey) -> Option<OperatorQuota> {
+            self.operator_quotas().get(operator_pub_key)
+        }
+
+        /// Key whose quota each airplane was counted against at registration.
+        pub fn quota_registrants(&self) -> MapIndex<&dyn Snapshot, PublicKey, PublicKey> {
+            MapIndex::new(self.index_name("quota_registrants"), self.view.as_ref())
+        }
+
+        pub fn quota_registrant(&self, pub_key: &PublicKey) -> Option<PublicKey> {
+            self.quota_registrants().get(pub_key)
+        }
+
+        /// Flight plans keyed by `plan_id`.
+        pub fn flight_plans(&self) -> MapIndex<&dyn Snapshot, u64, FlightPlan> {
+            MapIndex::new(self.index_name("flight_plans"), self.view.as_ref())
+        }
+
+        /// `plan_id` of every flight plan of the airplane, in submission order.
+        pub fn airplane_flight_plans(&self, pub_key: &PublicKey) -> ListIndex<&dyn Snapshot, u64> {
+            ListIndex::new_in_family(
+                self.index_name("airplane_flight_plans"),
+                pub_key,
+                self.view.as_ref(),
+            )
+        }
+
+        /// `plan_id` of the next flight plan.
+        pub fn next_flight_plan_id_entry(&self) -> Entry<&dyn Snapshot, u64> {
+            Entry::new(self.index_name("next_flight_plan_id"), self.view.as_ref())
+        }
+
+        /// Checks whether a plan of the airplane overlaps `departure..arrival`. Plans that
+        /// only touch, one arriving when the other departs, do not conflict.
+        pub fn has_conflicting_flight_plan(
+            &self,
+            pub_key: &PublicKey,
+            departure: DateTime<Utc>,
+            arrival: DateTime<Utc>,
+        ) -> bool {
+            let flight_plans = self.flight_plans();
+            self.airplane_flight_plans(pub_key)
+                .iter()
+                .filter_map(|plan_id| flight_plans.get(&plan_id))
+                .any(|plan| {
+                    plan.planned_departure() < arrival && departure < plan.planned_arrival()
+                })
+        }
+
+        pub fn airplane_count_entry(&self) -> Entry<&dyn Snapshot, u64> {
+            Entry::new(self.index_name("airplane_count"), self.view.as_ref())
+        }
+
+        /// Number of registered airplanes that are not decommissioned.
+        ///
+        /// `ProofMapIndex` has no `len()`, so the count is kept in a separate entry.
+        pub fn airplane_count(&self) -> u64 {
+            self.airplane_count_entry().get().unwrap_or(0)
+        }
+
+        /// Checks every stored airplane for an unknown `state_number`, a `state_str` that
+        /// does not match it, a `last_state_change_time` before registration or in the
+        /// future, per-state durations for unknown states, and engine heating data outside
+        /// `HeatingEngine`.
+        pub fn validate_integrity(&self) -> Vec<IntegrityViolation> {
+            let current_time = TimeSchema::new(&self.view).time().get();
+            let mut violations = Vec::new();
+            for (pub_key, airplane) in self.airplanes().iter() {
+                let mut report =
+                    |violation: String| violations.push(IntegrityViolation { pub_key, violation });
+
+                let state = match airplane.state() {
+                    Ok(state) => state,
+                    Err(_) => {
+                        report(format!(
+                            "state_number {} is not a known state",
+                            airplane.state_number()
+                        ));
+                        continue;
+                    }
+                };
+                if airplane.state_str() != state.to_string() {
+                    report(format!(
+                        "state_str '{}' does not match state_number {} ({})",
+                        airplane.state_str(),
+                        airplane.state_number(),
+                        state
+                    ));
+                }
+                if airplane.last_state_change_time() < airplane.registered_at() {
+                    report("last_state_change_time is before registered_at".to_owned());
+                }
+                if current_time.is_some_and(|time| airplane.last_state_change_time() > time) {
+                    report("last_state_change_time is in the future".to_owned());
+                }
+                if airplane.time_in_state_seconds().len() > AirplaneState::all().len() {
+                    report(format!(
+                        "time_in_state_seconds has {} entries for {} states",
+                        airplane.time_in_state_seconds().len(),
+                        AirplaneState::all().len()
+                    ));
+                }
+                if state != AirplaneState::HeatingEngine {
+                    if airplane.engine_heating_time_seconds() > 0 {
+                        report(format!(
+                            "engine_heating_time_seconds is {} outside HeatingEngine",
+                            airplane.engine_heating_time_seconds()
+                        ));
+                    }
+                    if airplane.engine_heating_start_time() != EPOCH {
+                        report("engine_heating_start_time is set outside HeatingEngine".to_owned());
+                    }
+                }
+            }
+            violations
+        }
+
+        /// Returns all airplanes, including decommissioned ones, ordered by public key bytes.
+        pub fn export_airplanes(&self) -> Vec<Airplane> {
+            let mut airplanes: Vec<Airplane> = self.airplanes().values().collect();
+            airplanes.sort_by_key(|airplane| *airplane.pub_key());
+            airplanes
+        }
+
+        /// Dumps the registry, the service configuration and the number of events of
+        /// each airplane, keyed by its public key, for manual inspection.
+        #[cfg(feature = "debug")]
+        pub fn dump_to_json(&self) -> Value {
+            let airplanes = self.export_airplanes();
+            let event_counts: Map<String, Value> = airplanes
+                .iter()
+                .map(|airplane| {
+                    let pub_key = airplane.pub_key();
+                    (pub_key.to_hex(), json!(self.events(pub_key).len()))
+                })
+                .collect();
+            json!({
+                "airplane_count": self.airplane_count(),
+                "airplanes": airplanes,
+                "config": self.service_config(),
+                "event_counts": event_counts,
+            })
+        }
+
+        pub fn schema_version_entry(&self) -> Entry<&dyn Snapshot, u16> {
+            Entry::new(self.index_name("schema_version"), self.view.as_ref())
+        }
+
+        /// Version of the stored data. Databases created before the version was
+        /// recorded use the version 1 layout.
+        pub fn schema_version(&self) -> u16 {
+            self.schema_version_entry().get().unwrap_or(1)
+        }
+
+        pub fn service_config_entry(&self) -> Entry<&dyn Snapshot, AirplaneServiceConfig> {
+            Entry::new(self.index_name("service_config"), self.view.as_ref())
+        }
+
+        /// Returns the stored service configuration, falling back to the defaults
+        /// if the service has not been initialized.
+        pub fn service_config(&self) -> AirplaneServiceConfig {
+            debug!("Reading service configuration");
+            self.service_config_entry().get().unwrap_or_default()
+        }
+
+        pub fn state_hash(&self) -> Vec<Hash> {
+            vec![
+                self.airplanes().merkle_root(),
+                self.audit_roots().merkle_root(),
+            ]
+        }
+    }
+
+    impl<'a> Schema<&'a mut Fork> {
+        /// Records `service_id` as the ID of this instance in `service_instances`.
+        pub fn register_service_id(&mut self, service_id: u16) {
+            MapIndex::new(SERVICE_INSTANCES, &mut *self.view)
+                .put(&service_id, self.service_name.clone());
+        }
+
+        pub fn airplanes_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, Airplane> {
+            ProofMapIndex::new(self.index_name("airplanes"), &mut self.view)
+        }
+
+        pub fn airplane_keys_mut(&mut self) -> ListIndex<&mut Fork, PublicKey> {
+            ListIndex::new(self.index_name("airplane_keys"), &mut self.view)
+        }
+
+        pub fn airplane_key_positions_mut(&mut self) -> MapIndex<&mut Fork, PublicKey, u64> {
+            MapIndex::new(self.index_name("airplane_key_positions"), &mut self.view)
+        }
+
+        /// Appends a newly stored airplane to `airplane_keys`.
+        pub fn append_airplane_key(&mut self, pub_key: &PublicKey) {
+            let position = self.airplane_keys().len();
+            self.airplane_keys_mut().push(*pub_key);
+            self.airplane_key_positions_mut().put(pub_key, position);
+        }
+
+        /// Puts `new_pub_key` in the place of `old_pub_key` in `airplane_keys`.
+        pub fn replace_airplane_key(&mut self, old_pub_key: &PublicKey, new_pub_key: &PublicKey) {
+            if let Some(position) = self.airplane_key_positions().get(old_pub_key) {
+                self.airplane_keys_mut().set(position, *new_pub_key);
+                self.airplane_key_positions_mut().remove(old_pub_key);
+                self.airplane_key_positions_mut().put(new_pub_key, position);
+            }
+        }
+
+        pub fn airplane_names_mut(&mut self) -> MapIndex<&mut Fork, String, PublicKey> {
+            MapIndex::new(self.index_name("airplane_names"), &mut self.view)
+        }
+
+        pub fn airplane_serials_mut(&mut self) -> MapIndex<&mut Fork, String, PublicKey> {
+            MapIndex::new(self.index_name("airplane_serials"), &mut self.view)
+        }
+
+        pub fn airplane_operators_mut(&mut self) -> MapIndex<&mut Fork, PublicKey, PublicKey> {
+            MapIndex::new(self.index_name("airplane_operators"), &mut self.view)
+        }
+
+        pub fn last_used_nonces_mut(&mut self) -> MapIndex<&mut Fork, PublicKey, u64> {
+            MapIndex::new(self.index_name("last_used_nonces"), &mut self.view)
+        }
+
+        pub fn authorities_mut(
+            &mut self,
+        ) -> MapIndex<&mut Fork, PublicKey, CertificationAuthority> {
+            MapIndex::new(self.index_name("authorities"), &mut self.view)
+        }
+
+        pub fn weather_reports_mut(&mut self) -> MapIndex<&mut Fork, String, WeatherReport> {
+            MapIndex::new(self.index_name("weather_reports"), &mut self.view)
+        }
+
+        pub fn tx_count_by_block_mut(&mut self) -> MapIndex<&mut Fork, PublicKey, BlockTxCount> {
+            MapIndex::new(self.index_name("tx_count_by_block"), &mut self.view)
+        }
+
+        /// Counts a transaction for `pub_key` in the block being built and fails with
+        /// `RateLimitExceeded` once more than `max_tx_per_block_per_key` were counted.
+        ///
+        /// Only the latest block is kept per key, so the index does not grow with the
+        /// height and needs no pruning; `after_commit` only sees a read-only snapshot
+        /// and could not prune it anyway. Changes of failed transactions are rolled
+        /// back, so only transactions that succeed count towards the limit.
+        ///
+        /// This is therefore no protection against flooding: a key can still fill blocks
+        /// with transactions that fail, since each of them is verified, committed and
+        /// executed before it fails. Such spam has to be stopped outside the service, as
+        /// `verify` has no storage access to count it.
+        pub fn count_transaction(&mut self, pub_key: &PublicKey) -> Result<(), Error> {
+            let limit = self.service_config().max_tx_per_block_per_key();
+            if limit == 0 {
+                return Ok(());
+            }
+
+            let height = self.height();
+            let count = match self.tx_count_by_block().get(pub_key) {
+                Some(ref counted) if counted.block_height() == height => counted.count() + 1,
+                _ => 1,
+            };
+            if count > limit {
+                Err(Error::RateLimitExceeded)?
+            }
+            self.tx_count_by_block_mut()
+                .put(pub_key, BlockTxCount::new(height, count));
+            Ok(())
+        }
+
+        /// Records `nonce` for `pub_key` if it is greater than the last one used.
+        ///
+        /// Exonum already drops a message whose hash is committed, so a captured transaction
+        /// cannot be replayed byte for byte. Without a nonce, though, a legitimate repeat of
+        /// the same transaction (e.g. `TxStartFlying` for the next flight) would have the
+        /// same hash and be dropped too. The nonce makes each submission unique, and the
+        /// ordering also rejects an older transaction that was never committed. State
+        /// checks remain the main guard against out-of-order transactions.
+        ///
+        /// `pub_key` must be the key that signed the transaction; otherwise anyone able to
+        /// sign for an airplane could exhaust its nonces, e.g. with `u64::MAX`.
+        pub fn use_nonce(&mut self, pub_key: &PublicKey, nonce: u64) -> Result<(), Error> {
+            if nonce <= self.last_used_nonce(pub_key) {
+                Err(Error::InvalidNonce)?
+            }
+            self.last_used_nonces_mut().put(pub_key, nonce);
+            Ok(())
+        }
+
+        pub fn check_queue_mut(&mut self, priority: u8) -> ListIndex<&mut Fork, PublicKey> {
+            ListIndex::new_in_family(
+                self.index_name("priority_check_queue"),
+                &priority,
+                &mut self.view,
+            )
+        }
+
+        pub fn queued_check_priorities_mut(&mut self) -> MapIndex<&mut Fork, PublicKey, u8> {
+            MapIndex::new(self.index_name("queued_check_priorities"), &mut self.view)
+        }
+
+        /// Appends `pub_key` to the check queue of `priority`.
+        pub fn enqueue_check(&mut self, pub_key: &PublicKey, priority: u8) -> Result<(), Error> {
+            if self.queued_check_priorities().contains(pub_key) {
+                Err(Error::AlreadyInCheckQueue)?
+            }
+            self.check_queue_mut(priority).push(*pub_key);
+            self.queued_check_priorities_mut().put(pub_key, priority);
+            Ok(())
+        }
+
+        /// Takes `pub_key` out of the check queue. Returns `false` if it was not queued.
+        pub fn remove_from_check_queue(&mut self, pub_key: &PublicKey) -> bool {
+            let priority = match self.queued_check_priorities().get(pub_key) {
+                Some(priority) => priority,
+                None => return false,
+            };
+            // `ListIndex` cannot remove items from the middle, so the queue is rebuilt.
+            let remaining: Vec<PublicKey> = self
+                .check_queue(priority)
+                .iter()
+                .filter(|queued| queued != pub_key)
+                .collect();
+            let mut queue = self.check_queue_mut(priority);
+            queue.clear();
+            queue.extend(remaining);
+            self.queued_check_priorities_mut().remove(pub_key);
+            true
+        }
+
+        pub fn runway_schema_mut(&mut self) -> RunwaySchema<&mut Fork> {
+            RunwaySchema::for_service(&mut *self.view, &self.service_name)
+        }
+
+        pub fn airplane_type_index_mut(
+            &mut self,
+            airplane_type: AirplaneType,
+        ) -> KeySetIndex<&mut Fork, PublicKey> {
+            KeySetIndex::new_in_family(
+                self.index_name("airplanes_by_type"),
+                &(airplane_type as u8),
+                &mut self.view,
+            )
+        }
+
+        pub fn airplanes_by_state_mut(
+            &mut self,
+            state: AirplaneState,
+        ) -> KeySetIndex<&mut Fork, PublicKey> {
+            KeySetIndex::new_in_family(
+                self.index_name("airplanes_by_state"),
+                &u8::from(state),
+                &mut self.view,
+            )
+        }
+
+        pub fn airplane_count_entry_mut(&mut self) -> Entry<&mut Fork, u64> {
+            Entry::new(self.index_name("airplane_count"), &mut self.view)
+        }
+
+        pub fn schema_version_entry_mut(&mut self) -> Entry<&mut Fork, u16> {
+            Entry::new(self.index_name("schema_version"), &mut self.view)
+        }
+
+        /// Upgrades data stored with the version 1 layout to `SCHEMA_VERSION` and
+        /// returns the number of migrated airplanes. Does nothing if the data is current.
+        ///
+        /// Version 1 kept airplanes in a plain `MapIndex` with a 2-byte
+        /// `engine_heating_time_seconds`. Every airplane is rewritten with the heating
+        /// time widened to `u32` and the newer fields set to their defaults, and the
+        /// name, state and count indexes are rebuilt. Version 1 had no registration
+        /// expiry, so registrations are renewed for `registration_validity_days` from the
+        /// time oracle's current time, which all nodes share.
+        pub fn migrate(&mut self) -> Result<u64, MigrationError> {
+            match self.schema_version() {
+                SCHEMA_VERSION => return Ok(0),
+                1 => {}
+                version => return Err(MigrationError::UnsupportedVersion(version)),
+            }
+            let now = TimeSchema::new(&self.view)
+                .time()
+                .get()
+                .ok_or(MigrationError::TimeNotAvailable)?;
+            let validity = Duration::days(i64::from(
+                self.service_config().registration_validity_days(),
+            ));
+
+            let old_airplanes: Vec<AirplaneV1> = {
+                let index: MapIndex<&dyn Snapshot, PublicKey, AirplaneV1> =
+                    MapIndex::new(self.index_name("airplanes"), self.view.as_ref());
+                index.values().collect()
+            };
+            // States are checked before anything is removed, so a corrupt entry leaves the
+            // old data in place.
+            let states = old_airplanes
+                .iter()
+                .map(|old| {
+                    AirplaneState::try_from(old.state_number()).map_err(|_| {
+                        MigrationError::InvalidState {
+                            pub_key: *old.pub_key(),
+                            state_number: old.state_number(),
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            // Exonum records the type of every index, so the old `Map` record has to be
+            // dropped along with the data before `airplanes` is recreated as a `ProofMap`.
+            let airplanes = self.index_name("airplanes");
+            self.view.remove_by_prefix(&airplanes, None);
+            self.view
+                .remove(INDEXES_METADATA_TABLE, airplanes.into_bytes());
+
+            let mut active = 0;
+            for (old, &state) in old_airplanes.iter().zip(&states) {
+                let airplane = AirplaneBuilder::new(old.pub_key(), old.name(), "", "")
+                    .state(state)
+                    .heating_start_time(old.engine_heating_start_time())
+                    .heating_time_seconds(u32::from(old.engine_heating_time_seconds()))
+                    .registration_expiry(now + validity)
+                    .build();
+
+                self.airplanes_mut().put(old.pub_key(), airplane);
+                self.append_airplane_key(old.pub_key());
+                self.update_state_index(None, Some(state), old.pub_key());
+                self.airplane_type_index_mut(AirplaneType::Commercial)
+                    .insert(*old.pub_key());
+                if state != AirplaneState::Decommissioned {
+                    self.airplane_names_mut()
+                        .put(&normalize_airplane_name(old.name()), *old.pub_key());
+                    active += 1;
+                }
+            }
+
+            let expected = old_airplanes.len() as u64;
+            let migrated = self.airplanes().keys().count() as u64;
+            if migrated != expected {
+                return Err(MigrationError::CountMismatch { migrated, expected });
+            }
+
+            self.airplane_count_entry_mut().set(active);
+            self.schema_version_entry_mut().set(SCHEMA_VERSION);
+            Ok(migrated)
+        }
+
+        /// Stores `airplanes` as is, without the checks of `register_airplane`, and indexes
+        /// their names, serial numbers and states. Meant for restoring an export made by
+        /// `export_airplanes` into an empty database of a stopped node; see `examples/import.rs`.
+        ///
+        /// Returns the number of imported airplanes.
+        pub fn import_airplanes(&mut self, airplanes: &[Airplane]) -> Result<u64, Error> {
+            for airplane in airplanes {
+                let pub_key = airplane.pub_key();
+                let state = AirplaneState::try_from(airplane.state_number())?;
+                let old_state = match self.airplane(pub_key) {
+                    Some(old) => Some(AirplaneState::try_from(old.state_number())?),
+                    None => None,
+                };
+                let was_active = old_state.is_some_and(|old| old != AirplaneState::Decommissioned);
+                let is_active = state != AirplaneState::Decommissioned;
+                let count = self.airplane_count();
+                match (was_active, is_active) {
+                    (false, true) => self.airplane_count_entry_mut().set(count + 1),
+                    (true, false) => self.airplane_count_entry_mut().set(count.saturating_sub(1)),
+                    _ => {}
+                }
+
+                if let Some(old) = self.airplane(pub_key) {
+                    self.airplane_type_index_mut(old.kind()?).remove(pub_key);
+                }
+                self.airplane_type_index_mut(airplane.kind()?)
+                    .insert(*pub_key);
+                if old_state.is_none() {
+                    self.append_airplane_key(pub_key);
+                }
+                self.airplanes_mut().put(pub_key, airplane.clone());
+                self.update_state_index(old_state, Some(state), pub_key);
+                if is_active {
+                    self.airplane_names_mut()
+                        .put(&normalize_airplane_name(airplane.name()), *pub_key);
+                }
+                if !airplane.serial_number().is_empty() {
+                    self.airplane_serials_mut()
+                        .put(&airplane.serial_number().to_owned(), *pub_key);
+                }
+            }
+            Ok(airplanes.len() as u64)
+        }
+
+        pub fn service_config_entry_mut(&mut self) -> Entry<&mut Fork, AirplaneServiceConfig> {
+            Entry::new(self.index_name("service_config"), &mut self.view)
+        }
+
+        pub fn audit_log_mut(
+            &mut self,
+            pub_key: &PublicKey,
+        ) -> ProofListIndex<&mut Fork, AuditEntry> {
+            ProofListIndex::new_in_family(self.index_name("audit_log"), pub_key, &mut self.view)
+        }
+
+        pub fn audit_roots_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, Hash> {
+            ProofMapIndex::new(self.index_name("audit_roots"), &mut self.view)
+        }
+
+        pub fn completed_routes_mut(
+            &mut self,
+            pub_key: &PublicKey,
+        ) -> ListIndex<&mut Fork, CompletedRoute> {
+            ListIndex::new_in_family(self.index_name("completed_routes"), pub_key, &mut self.view)
+        }
+
+        pub fn flight_log_mut(
+            &mut self,
+            pub_key: &PublicKey,
+        ) -> ProofListIndex<&mut Fork, FlightLogEntry> {
+            ProofListIndex::new_in_family(self.index_name("flight_log"), pub_key, &mut self.view)
+        }
+
+        pub fn scheduled_checks_mut(
+            &mut self,
+            pub_key: &PublicKey,
+        ) -> ListIndex<&mut Fork, ScheduledCheck> {
+            ListIndex::new_in_family(self.index_name("scheduled_checks"), pub_key, &mut self.view)
+        }
+
+        pub fn operator_quotas_mut(&mut self) -> MapIndex<&mut Fork, PublicKey, OperatorQuota> {
+            MapIndex::new(self.index_name("operator_quotas"), &mut self.view)
+        }
+
+        pub fn quota_registrants_mut(&mut self) -> MapIndex<&mut Fork, PublicKey, PublicKey> {
+            MapIndex::new(self.index_name("quota_registrants"), &mut self.view)
+        }
+
+        /// Counts the airplane registered under `pub_key` against the quota of
+        /// `registrant`, if it has one.
+        ///
+        /// Fails with `OperatorQuotaExceeded` once the quota is used up.
+        pub fn count_against_operator_quota(
+            &mut self,
+            registrant: &PublicKey,
+            pub_key: &PublicKey,
+        ) -> ExecutionResult {
+            let quota = match self.operator_quota(registrant) {
+                Some(quota) => quota,
+                None => return Ok(()),
+            };
+            if quota.current_count() >= quota.max_airplanes() {
+                Err(Error::OperatorQuotaExceeded)?
+            }
+            self.operator_quotas_mut().put(
+                registrant,
+                OperatorQuota::new(registrant, quota.max_airplanes(), quota.current_count() + 1),
+            );
+            self.quota_registrants_mut().put(pub_key, *registrant);
+            Ok(())
+        }
+
+        /// Returns the quota slot of a decommissioned airplane to the key it was counted
+        /// against.
+        pub fn release_operator_quota(&mut self, pub_key: &PublicKey) {
+            let registrant = match self.quota_registrant(pub_key) {
+                Some(registrant) => registrant,
+                None => return,
+            };
+            self.quota_registrants_mut().remove(pub_key);
+            if let Some(quota) = self.operator_quota(&registrant) {
+                self.operator_quotas_mut().put(
+                    &registrant,
+                    OperatorQuota::new(
+                        &registrant,
+                        quota.max_airplanes(),
+                        quota.current_count().saturating_sub(1),
+                    ),
+                );
+            }
+        }
+
+        pub fn flight_plans_mut(&mut self) -> MapIndex<&mut Fork, u64, FlightPlan> {
+            MapIndex::new(self.index_name("flight_plans"), &mut self.view)
+        }
+
+        pub fn airplane_flight_plans_mut(
+            &mut self,
+            pub_key: &PublicKey,
+        ) -> ListIndex<&mut Fork, u64> {
+            ListIndex::new_in_family(
+                self.index_name("airplane_flight_plans"),
+                pub_key,
+                &mut self.view,
+            )
+        }
+
+        pub fn next_flight_plan_id_entry_mut(&mut self) -> Entry<&mut Fork, u64> {
+            Entry::new(self.index_name("next_flight_plan_id"), &mut self.view)
+        }
+
+        /// Stores a planned flight under the next `plan_id` and returns the id.
+        pub fn add_flight_plan(
+            &mut self,
+            pub_key: &PublicKey,
+            departure_iata: &str,
+            arrival_iata: &str,
+            planned_departure: DateTime<Utc>,
+            planned_arrival: DateTime<Utc>,
+        ) -> u64 {
+            let plan_id = self.next_flight_plan_id_entry().get().unwrap_or(0);
+            self.next_flight_plan_id_entry_mut().set(plan_id + 1);
+            self.flight_plans_mut().put(
+                &plan_id,
+                FlightPlan::new(
+                    plan_id,
+                    pub_key,
+                    departure_iata,
+                    arrival_iata,
+                    planned_departure,
+                    planned_arrival,
+                    FLIGHT_PLAN_PLANNED,
+                ),
+            );
+            self.airplane_flight_plans_mut(pub_key).push(plan_id);
+            plan_id
+        }
+
+        pub fn started_scheduled_checks_mut(&mut self) -> MapIndex<&mut Fork, PublicKey, u64> {
+            MapIndex::new(self.index_name("scheduled_checks_started"), &mut self.view)
+        }
+
+        pub fn incidents_mut(&mut self, pub_key: &PublicKey) -> ListIndex<&mut Fork, Incident> {
+            ListIndex::new_in_family(self.index_name("incidents"), pub_key, &mut self.view)
+        }
+
+        pub fn maintenance_records_mut(
+            &mut self,
+            pub_key: &PublicKey,
+        ) -> ListIndex<&mut Fork, MaintenanceRecord> {
+            ListIndex::new_in_family(
+                self.index_name("maintenance_records"),
+                pub_key,
+                &mut self.view,
+            )
+        }
+
+        pub fn events_mut(
+            &mut self,
+            pub_key: &PublicKey,
+        ) -> ProofListIndex<&mut Fork, AirplaneEvent> {
+            ProofListIndex::new_in_family(
+                self.index_name("airplane_events"),
+                pub_key,
+                &mut self.view,
+            )
+        }
+
+        /// Moves `pub_key` between the per-state sets. `None` stands for an airplane
+        /// that is being added to or removed from the registry.
+        pub fn update_state_index(
+            &mut self,
+            old_state: Option<AirplaneState>,
+            new_state: Option<AirplaneState>,
+            pub_key: &PublicKey,
+        ) {
+            if let Some(old_state) = old_state {
+                self.airplanes_by_state_mut(old_state).remove(pub_key);
+            }
+            if let Some(new_state) = new_state {
+                self.airplanes_by_state_mut(new_state).insert(*pub_key);
+            }
+        }
+
+        /// Stores a new airplane in the `WaitingForFlight` state and indexes its name and serial.
+        pub fn register_airplane(
+            &mut self,
+            pub_key: &PublicKey,
+            name: &str,
+            model: &str,
+            serial_number: &str,
+            airplane_type: u8,
+            max_takeoff_weight_kg: u32,
+            max_passenger_capacity: u16,
+            tx_hash: &Hash,
+            timestamp: DateTime<Utc>,
+        ) -> ExecutionResult {
+            if let Some(airplane) = self.airplane(pub_key) {
+                match AirplaneState::try_from(airplane.state_number())? {
+                    AirplaneState::Decommissioned => Err(Error::AirplaneIsDecommissioned)?,
+                    _ => Err(Error::AirplaneAlreadyExists)?,
+                }
+            }
+
+            if !is_valid_airplane_name(name) {
+                Err(Error::InvalidAirplaneName)?
+            }
+            let name_key = normalize_airplane_name(name);
+            if self.airplane_names().contains(&name_key) {
+                Err(Error::NameAlreadyTaken)?
+            }
+            if !is_valid_serial_number(serial_number) {
+                Err(Error::InvalidSerialNumber)?
+            }
+            let airplane_type = AirplaneType::try_from(airplane_type)?;
+            if self.airplane_serials().contains(&serial_number.to_owned()) {
+                Err(Error::SerialNumberAlreadyTaken)?
+            }
+            if let Some(limit) = self.service_config().fleet_limit() {
+                if self.airplane_count() >= limit {
+                    Err(Error::FleetCapacityReached)?
+                }
+            }
+
+            let validity = Duration::days(i64::from(
+                self.service_config().registration_validity_days(),
+            ));
+            let airplane = AirplaneBuilder::new(pub_key, name, model, serial_number)
+                .registered_at(timestamp)
+                .last_state_change_time(timestamp)
+                .registration_expiry(timestamp + validity)
+                .airplane_type(airplane_type)
+                .max_takeoff_weight_kg(max_takeoff_weight_kg)
+                .max_passenger_capacity(max_passenger_capacity)
+                .build();
+
+            self.airplanes_mut().put(pub_key, airplane);
+            self.append_airplane_key(pub_key);
+            self.airplane_names_mut().put(&name_key, *pub_key);
+            self.airplane_serials_mut()
+                .put(&serial_number.to_owned(), *pub_key);
+            self.update_state_index(None, Some(AirplaneState::WaitingForFlight), pub_key);
+            self.airplane_type_index_mut(airplane_type).insert(*pub_key);
+            let count = self.airplane_count();
+            self.airplane_count_entry_mut().set(count + 1);
+            self.events_mut(pub_key).push(AirplaneEvent::new(
+                AirplaneEventType::Registered as u8,
+                false,
+                timestamp,
+            ));
+            self.append_audit_entry(
+                pub_key,
+                tx_hash,
+                AirplaneState::WaitingForFlight,
+                AirplaneState::WaitingForFlight,
+            );
+
+            Ok(())
+        }
+
+        /// Moves the airplane out of `expected_state` by storing the value built by `updater`.
+        ///
+        /// Fails with `AirplaneDoesNotExist` if there is no such airplane and with
+        /// `InvalidStateTransition` (or `AirplaneIsDecommissioned` / `AirplaneIsInactive`) if it
+        /// is in another state.
+        /// The state index is updated from the state of the returned airplane, and the change is
+        /// recorded in the audit log under `tx_hash`.
+        pub fn transition_airplane<F>(
+            &mut self,
+            pub_key: &PublicKey,
+            tx_hash: &Hash,
+            expected_state: AirplaneState,
+            updater: F,
+        ) -> ExecutionResult
+        where
+            F: FnOnce(Airplane) -> Result<Airplane, ExecutionError>,
+        {
+            self.transition_airplane_from_any(pub_key, tx_hash, expected_state.as_slice(), updater)
+        }
+
+        /// Same as `transition_airplane`, but accepts any of `allowed_states` as the source state.
+        pub fn transition_airplane_from_any<F>(
+            &mut self,
+            pub_key: &PublicKey,
+            tx_hash: &Hash,
+            allowed_states: &'static [AirplaneState],
+            updater: F,
+        ) -> ExecutionResult
+        where
+            F: FnOnce(Airplane) -> Result<Airplane, ExecutionError>,
+        {
+            let airplane = match self.airplane(pub_key) {
+                Some(airplane) => airplane,
+                None => Err(Error::AirplaneDoesNotExist(*pub_key))?,
+            };
+            // The signature check in `verify` only covers the key in the message. This guards
+            // against a stored airplane whose own key disagrees with the index key, e.g. after
+            // a faulty migration or import, so that a transaction never updates another airplane.
+            if airplane.pub_key() != pub_key {
+                Err(Error::PubKeyMismatch)?
+            }
+
+            let old_state = AirplaneState::try_from(airplane.state_number())?;
+            if !allowed_states.contains(&old_state) {
+                if old_state == AirplaneState::Decommissioned {
+                    Err(Error::AirplaneIsDecommissioned)?
+                }
+                if old_state == AirplaneState::Inactive {
+                    Err(Error::AirplaneIsInactive)?
+                }
+                Err(Error::InvalidStateTransition {
+                    found: old_state,
+                    required: allowed_states,
+                })?
+            }
+
+            let new_airplane = updater(airplane)?;
+            let new_state = AirplaneState::try_from(new_airplane.state_number())?;
+            if !can_transition(old_state, new_state) {
+                Err(Error::TransitionNotAllowed {
+                    from: old_state,
+                    to: new_state,
+                })?
+            }
+            // Time is only missing before the time oracle commits its first value.
+            let new_airplane = match TimeSchema::new(&self.view).time().get() {
+                Some(current_time) if new_state != old_state => {
+                    let elapsed =
+                        seconds_between(new_airplane.last_state_change_time(), current_time);
+                    Airplane::builder_from(&new_airplane)
+                        .add_time_in_state(old_state, elapsed)
+                        .last_state_change_time(current_time)
+                        .build()
+                }
+                _ => new_airplane,
+            };
+
+            self.airplanes_mut().put(pub_key, new_airplane);
+            self.update_state_index(Some(old_state), Some(new_state), pub_key);
+            self.append_audit_entry(pub_key, tx_hash, old_state, new_state);
+            Ok(())
+        }
+
+        /// Appends an entry to the airplane's audit log and refreshes its root in `audit_roots`.
+        pub fn append_audit_entry(
+            &mut self,
+            pub_key: &PublicKey,
+            tx_hash: &Hash,
+            old_state: AirplaneState,
+            new_state: AirplaneState,
+        ) {
+            // The genesis block is committed before any transaction runs, so the number of
+            // committed blocks is the height of the block being built.
+            let block_height = CoreSchema::new(&self.view).block_hashes_by_height().len();
+            // Time is only missing before the time oracle commits its first value.
+            let timestamp = TimeSchema::new(&self.view).time().get().unwrap_or(EPOCH);
+
+            let entry = AuditEntry::new(
+                tx_hash,
+                block_height,
+                u8::from(old_state),
+                u8::from(new_state),
+                timestamp,
+            );
+
+            let mut audit_log = self.audit_log_mut(pub_key);
+            audit_log.push(entry.clone());
+            let root = audit_log.merkle_root();
+            self.audit_roots_mut().put(pub_key, root);
+            self.global_audit_mut()
+                .push(TimelineEntry::new(pub_key, entry));
+        }
+
+        pub fn global_audit_mut(&mut self) -> ListIndex<&mut Fork, TimelineEntry> {
+            ListIndex::new(self.index_name("global_audit"), &mut self.view)
+        }
+
+        /// Moves the records kept for the airplane at `from` to `to`: its events, audit log,
+        /// incidents, maintenance records, routes, flight log, scheduled and queued checks and
+        /// flight plans. The indexes at `from` are left empty, so an airplane registered
+        /// under that key later starts without history. `global_audit` is a record of the
+        /// past and keeps the key each entry was written under.
+        pub fn move_airplane_history(&mut self, from: &PublicKey, to: &PublicKey) {
+            let events: Vec<_> = self.events(from).iter().collect();
+            self.events_mut(from).clear();
+            self.events_mut(to).extend(events);
+
+            let audit_log: Vec<_> = self.audit_log(from).iter().collect();
+            self.audit_log_mut(from).clear();
+            let mut new_audit_log = self.audit_log_mut(to);
+            new_audit_log.extend(audit_log);
+            let root = new_audit_log.merkle_root();
+            self.audit_roots_mut().remove(from);
+            self.audit_roots_mut().put(to, root);
+
+            let incidents: Vec<_> = self.incidents(from).iter().collect();
+            self.incidents_mut(from).clear();
+            self.incidents_mut(to).extend(incidents);
+
+            let maintenance_records: Vec<_> = self.maintenance_records(from).iter().collect();
+            self.maintenance_records_mut(from).clear();
+            self.maintenance_records_mut(to).extend(maintenance_records);
+
+            let completed_routes: Vec<_> = self.completed_routes(from).iter().collect();
+            self.completed_routes_mut(from).clear();
+            self.completed_routes_mut(to).extend(completed_routes);
+
+            let flight_log: Vec<_> = self.flight_log(from).iter().collect();
+            self.flight_log_mut(from).clear();
+            self.flight_log_mut(to).extend(flight_log);
+
+            let scheduled_checks: Vec<_> = self.scheduled_checks(from).iter().collect();
+            self.scheduled_checks_mut(from).clear();
+            self.scheduled_checks_mut(to).extend(scheduled_checks);
+            let started = self.started_scheduled_checks(from);
+            self.started_scheduled_checks_mut().remove(from);
+            if started > 0 {
+                self.started_scheduled_checks_mut().put(to, started);
+            }
+
+            let plan_ids: Vec<_> = self.airplane_flight_plans(from).iter().collect();
+            for plan_id in &plan_ids {
+                if let Some(plan) = self.flight_plans().get(plan_id) {
+                    let moved = FlightPlan::new(
+                        plan.plan_id(),
+                        to,
+                        plan.departure_iata(),
+                        plan.arrival_iata(),
+                        plan.planned_departure(),
+                        plan.planned_arrival(),
+                        plan.status(),
+                    );
+                    self.flight_plans_mut().put(plan_id, moved);
+                }
+            }
+            self.airplane_flight_plans_mut(from).clear();
+            self.airplane_flight_plans_mut(to).extend(plan_ids);
+
+            // The airplane keeps its place in the check queue.
+            if let Some(priority) = self.queued_check_priorities().get(from) {
+                let queue: Vec<_> = self
+                    .check_queue(priority)
+                    .iter()
+                    .map(|queued| if queued == *from { *to } else { queued })
+                    .collect();
+                let mut check_queue = self.check_queue_mut(priority);
+                check_queue.clear();
+                check_queue.extend(queue);
+                self.queued_check_priorities_mut().remove(from);
+                self.queued_check_priorities_mut().put(to, priority);
+            }
+        }
+    }
+
+    /// Shortcuts for tests. They write straight to the database without any of the checks
+    /// done by transactions and leave the state hash inconsistent with the blockchain, so
+    /// they must never be used outside tests.
+    impl<'a> Schema<&'a mut Fork> {
+        /// Removes every airplane together with its indexes, logs and runway assignment.
+        /// The service configuration and schema version are kept.
+        #[doc(hidden)]
+        pub fn clear_test_data(&mut self) {
+            let pub_keys: Vec<PublicKey> = self.airplanes().keys().collect();
+            for pub_key in &pub_keys {
+                self.audit_log_mut(pub_key).clear();
+                self.completed_routes_mut(pub_key).clear();
+                self.flight_log_mut(pub_key).clear();
+                self.scheduled_checks_mut(pub_key).clear();
+                self.incidents_mut(pub_key).clear();
+                self.maintenance_records_mut(pub_key).clear();
+                self.events_mut(pub_key).clear();
+            }
+            for state in AirplaneState::all() {
+                self.airplanes_by_state_mut(state).clear();
+            }
+            for airplane_type in AirplaneType::all() {
+                self.airplane_type_index_mut(airplane_type).clear();
+            }
+            self.airplanes_mut().clear();
+            self.airplane_keys_mut().clear();
+            self.airplane_key_positions_mut().clear();
+            self.airplane_names_mut().clear();
+            self.airplane_serials_mut().clear();
+            self.airplane_operators_mut().clear();
+            self.last_used_nonces_mut().clear();
+            self.tx_count_by_block_mut().clear();
+            self.audit_roots_mut().clear();
+            self.global_audit_mut().clear();
+            self.started_scheduled_checks_mut().clear();
+            for priority in MIN_PRIORITY..=MAX_PRIORITY {
+                self.check_queue_mut(priority).clear();
+            }
+            self.queued_check_priorities_mut().clear();
+            self.airplane_count_entry_mut().remove();
+            let mut runways = self.runway_schema_mut();
+            runways.runway_assignments_mut().clear();
+            runways.runways_mut().clear();
+        }
+
+        /// Stores a `Commercial` airplane named `name` in `state` and indexes it. Its
+        /// registration is valid for `registration_validity_days` from the current time.
+        #[doc(hidden)]
+        pub fn seed_test_airplane(
+            &mut self,
+            pub_key: &PublicKey,
+            name: &str,
+            state: AirplaneState,
+        ) -> Airplane {
+            let now = TimeSchema::new(&self.view).time().get().unwrap_or(EPOCH);
+            let validity = Duration::days(i64::from(
+                self.service_config().registration_validity_days(),
+            ));
+            let airplane = AirplaneBuilder::new(pub_key, name, "", "")
+                .state(state)
+                .last_state_change_time(now)
+                .registration_expiry(now + validity)
+                .build();
+            self.import_airplanes(slice::from_ref(&airplane))
+                .expect("seeded airplane has a valid state and type");
+            airplane
+        }
+    }
+
+    /// Runways are created on their first assignment and never removed.
+    #[derive(Debug)]
+    pub struct RunwaySchema<T> {
+        view: T,
+
+        service_name: String,
+    }
+
+    impl<T: AsRef<dyn Snapshot>> RunwaySchema<T> {
+        /// Runways of the service instance named `SERVICE_NAME`.
+        pub fn new(view: T) -> Self {
+            RunwaySchema::for_service(view, SERVICE_NAME)
+        }
+
+        pub fn for_service(view: T, service_name: &str) -> Self {
+            RunwaySchema {
+                view,
+                service_name: service_name.to_owned(),
+            }
+        }
+
+        /// Runways of the service instance with the given ID; see `Schema::for_service_id`.
+        pub fn for_service_id(view: T, service_id: u16) -> Self {
+            let schema = Schema::for_service_id(view, service_id);
+            RunwaySchema {
+                view: schema.view,
+                service_name: schema.service_name,
+            }
+        }
+
+        fn index_name(&self, name: &str) -> String {
+            index_name(&self.service_name, name)
+        }
+
+        pub fn runways(&self) -> MapIndex<&dyn Snapshot, String, Runway> {
+            MapIndex::new(self.index_name("runways"), self.view.as_ref())
+        }
+
+        pub fn runway(&self, name: &str) -> Option<Runway> {
+            self.runways().get(&name.to_owned())
+        }
+
+        /// Runway names keyed by the airplane they are assigned to.
+        pub fn runway_assignments(&self) -> MapIndex<&dyn Snapshot, PublicKey, String> {
+            MapIndex::new(self.index_name("runway_assignments"), self.view.as_ref())
+        }
+
+        pub fn assigned_runway(&self, airplane_pub_key: &PublicKey) -> Option<Runway> {
+            self.runway_assignments()
+                .get(airplane_pub_key)
+                .and_then(|name| self.runway(&name))
+        }
+    }
+
+    impl<'a> RunwaySchema<&'a mut Fork> {
+        pub fn runways_mut(&mut self) -> MapIndex<&mut Fork, String, Runway> {
+            MapIndex::new(self.index_name("runways"), &mut self.view)
+        }
+
+        pub fn runway_assignments_mut(&mut self) -> MapIndex<&mut Fork, PublicKey, String> {
+            MapIndex::new(self.index_name("runway_assignments"), &mut self.view)
+        }
+
+        pub fn assign_runway(
+            &mut self,
+            runway_name: &str,
+            airplane_pub_key: &PublicKey,
+        ) -> Result<(), Error> {
+            if self.runway_assignments().contains(airplane_pub_key) {
+                Err(Error::RunwayAlreadyAssigned)?
+            }
+            if self
+                .runway(runway_name)
+                .is_some_and(|runway| runway.is_occupied())
+            {
+                Err(Error::RunwayOccupied)?
+            }
+
+            self.runways_mut().put(
+                &runway_name.to_owned(),
+                Runway::new(runway_name, true, airplane_pub_key),
+            );
+            self.runway_assignments_mut()
+                .put(airplane_pub_key, runway_name.to_owned());
+            Ok(())
+        }
+
+        /// Frees the runway held by the airplane. Returns `false` if it held none.
+        pub fn release_runway(&mut self, airplane_pub_key: &PublicKey) -> bool {
+            match self.runway_assignments().get(airplane_pub_key) {
+                Some(name) => {
+                    self.runways_mut()
+                        .put(&name, Runway::new(&name, false, &PublicKey::zero()));
+                    self.runway_assignments_mut().remove(airplane_pub_key);
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+}
+
+/// Checks of transaction fields shared by several transactions.
+pub mod validation {
+    use transactions::Error;
+
+    /// Airports accepted by `validate_known_iata_code`, sorted for binary search.
+    pub static KNOWN_AIRPORTS: &[&str] = &[
+        "AMS", "ATL", "CDG", "DEN", "DFW", "DXB", "FRA", "HKG", "HND", "IST", "JFK", "LAX", "LHR",
+        "MAD", "ORD", "PEK", "SFO", "SIN", "SVO", "SYD",
+    ];
+
+    /// Checks that `code` is an IATA airport code: exactly three uppercase ASCII letters.
+    pub fn validate_iata_code(code: &str) -> Result<(), Error> {
+        if code.len() == 3 && code.bytes().all(|c| c.is_ascii_uppercase()) {
+            Ok(())
+        } else {
+            Err(Error::InvalidIataCode)
+        }
+    }
+
+    /// Like `validate_iata_code`, but also requires the airport to be in `KNOWN_AIRPORTS`.
+    pub fn validate_known_iata_code(code: &str) -> Result<(), Error> {
+        validate_iata_code(code)?;
+        KNOWN_AIRPORTS
+            .binary_search(&code)
+            .map(|_| ())
+            .map_err(|_| Error::InvalidIataCode)
+    }
+}
+
+/// The airplane state machine as data, for validating transitions and listing
+/// the actions available in a given state.
+pub mod transitions {
+    use schema::AirplaneState::{self, *};
+
+    /// Every allowed `(from, to, transaction)` triple. Transactions that keep the state
+    /// but require a particular one are listed with `from == to`. Transactions that do
+    /// not depend on the state, such as `TxAssignOperator`, are not listed.
+    pub static TRANSITIONS: &[(AirplaneState, AirplaneState, &str)] = &[
+        (WaitingForFlight, TechnicalCheck, "TxStartTechnicalCheck"),
+        (WaitingForFlight, TechnicalCheck, "TxStartScheduledCheck"),
+        (WaitingForFlight, TechnicalCheck, "TxStartQueuedCheck"),
+        (
+            WaitingForFlight,
+            AwaitingCheckApproval,
+            "TxRequestTechnicalCheck",
+        ),
+        (AwaitingCheckApproval, TechnicalCheck, "TxApproveCheckStart"),
+        (
+            AwaitingCheckApproval,
+            WaitingForFlight,
+            "TxDenyCheckRequest",
+        ),
+        (WaitingForFlight, WaitingForFlight, "TxLoadCargo"),
+        (WaitingForFlight, WaitingForFlight, "TxSetPassengerCount"),
+        (WaitingForFlight, WaitingForFlight, "TxUnloadCargo"),
+        // Queued instead while technical check slots are limited.
+        (WaitingForFlight, WaitingForFlight, "TxStartTechnicalCheck"),
+        (WaitingForFlight, WaitingForFlight, "TxTransferOwnership"),
+        (WaitingForFlight, WaitingForFlight, "TxRefuelAirplane"),
+        (
+            WaitingForFlight,
+            WaitingForFlight,
+            "TxScheduleTechnicalCheck",
+        ),
+        (WaitingForFlight, WaitingForFlight, "TxAssignRunway"),
+        (WaitingForFlight, Decommissioned, "TxDecommissionAirplane"),
+        (WaitingForFlight, Inactive, "TxDeactivateAirplane"),
+        (Inactive, WaitingForFlight, "TxReactivateAirplane"),
+        (Inactive, Decommissioned, "TxDecommissionAirplane"),
+        (TechnicalCheck, HeatingEngine, "TxEndTechnicalCheck"),
+        (TechnicalCheck, WaitingForFlight, "TxEndTechnicalCheck"),
+        (
+            TechnicalCheck,
+            TechnicalCheckExtended,
+            "TxRequestMaintenanceExtension",
+        ),
+        (TechnicalCheckExtended, HeatingEngine, "TxEndTechnicalCheck"),
+        (
+            TechnicalCheckExtended,
+            WaitingForFlight,
+            "TxEndTechnicalCheck",
+        ),
+        (HeatingEngine, Flying, "TxStartFlying"),
+        (HeatingEngine, WaitingForFlight, "TxExpireHeating"),
+        (HeatingEngine, HeatingEngine, "TxUpdateEngineHeatingTime"),
+        (Flying, WaitingForFlight, "TxEndFlying"),
+        (Flying, WaitingForFlight, "TxCancelFlight"),
+        (Flying, Maintenance, "TxStartMaintenance"),
+        (Flying, Emergency, "TxEmergencyLanding"),
+        (Flying, Flying, "TxRecordFlightRoute"),
+        (Maintenance, WaitingForFlight, "TxEndMaintenance"),
+        (Maintenance, Grounded, "TxEndMaintenance"),
+        (Emergency, WaitingForFlight, "TxClearEmergency"),
+        (WaitingForFlight, Grounded, "TxGroundAirplane"),
+        (TechnicalCheck, Grounded, "TxGroundAirplane"),
+        (TechnicalCheckExtended, Grounded, "TxGroundAirplane"),
+        (HeatingEngine, Grounded, "TxGroundAirplane"),
+        (Flying, Grounded, "TxGroundAirplane"),
+        (Maintenance, Grounded, "TxGroundAirplane"),
+        (Emergency, Grounded, "TxGroundAirplane"),
+        (AwaitingCheckApproval, Grounded, "TxGroundAirplane"),
+        (Grounded, Grounded, "TxGroundAirplane"),
+        (Grounded, WaitingForFlight, "TxUngroundAirplane"),
+        (WaitingForFlight, WaitingForFlight, "TxUpdateAirplaneName"),
+        (TechnicalCheck, TechnicalCheck, "TxUpdateAirplaneName"),
+        (
+            TechnicalCheckExtended,
+            TechnicalCheckExtended,
+            "TxUpdateAirplaneName",
+        ),
+        (HeatingEngine, HeatingEngine, "TxUpdateAirplaneName"),
+        (Flying, Flying, "TxUpdateAirplaneName"),
+        (Maintenance, Maintenance, "TxUpdateAirplaneName"),
+        (Emergency, Emergency, "TxUpdateAirplaneName"),
+        (
+            AwaitingCheckApproval,
+            AwaitingCheckApproval,
+            "TxUpdateAirplaneName",
+        ),
+        // A flying airplane releases its runway when the flight ends.
+        (WaitingForFlight, WaitingForFlight, "TxReleaseRunway"),
+        (TechnicalCheck, TechnicalCheck, "TxReleaseRunway"),
+        (
+            TechnicalCheckExtended,
+            TechnicalCheckExtended,
+            "TxReleaseRunway",
+        ),
+        (HeatingEngine, HeatingEngine, "TxReleaseRunway"),
+        (Maintenance, Maintenance, "TxReleaseRunway"),
+        (Grounded, Grounded, "TxReleaseRunway"),
+        (Emergency, Emergency, "TxReleaseRunway"),
+        (
+            AwaitingCheckApproval,
+            AwaitingCheckApproval,
+            "TxReleaseRunway",
+        ),
+        (Decommissioned, Decommissioned, "TxReleaseRunway"),
+        (WaitingForFlight, WaitingForFlight, "TxSetMetadataField"),
+        (TechnicalCheck, TechnicalCheck, "TxSetMetadataField"),
+        (
+            TechnicalCheckExtended,
+            TechnicalCheckExtended,
+            "TxSetMetadataField",
+        ),
+        (HeatingEngine, HeatingEngine, "TxSetMetadataField"),
+        (Flying, Flying, "TxSetMetadataField"),
+        (Maintenance, Maintenance, "TxSetMetadataField"),
+        (Grounded, Grounded, "TxSetMetadataField"),
+        (Emergency, Emergency, "TxSetMetadataField"),
+        (
+            AwaitingCheckApproval,
+            AwaitingCheckApproval,
+            "TxSetMetadataField",
+        ),
+        (WaitingForFlight, WaitingForFlight, "TxRemoveMetadataField"),
+        (TechnicalCheck, TechnicalCheck, "TxRemoveMetadataField"),
+        (
+            TechnicalCheckExtended,
+            TechnicalCheckExtended,
+            "TxRemoveMetadataField",
+        ),
+        (HeatingEngine, HeatingEngine, "TxRemoveMetadataField"),
+        (Flying, Flying, "TxRemoveMetadataField"),
+        (Maintenance, Maintenance, "TxRemoveMetadataField"),
+        (Grounded, Grounded, "TxRemoveMetadataField"),
+        (Emergency, Emergency, "TxRemoveMetadataField"),
+        (
+            AwaitingCheckApproval,
+            AwaitingCheckApproval,
+            "TxRemoveMetadataField",
+        ),
+    ];
+
+    /// Checks whether any transaction moves an airplane from `from` to `to`.
+    pub fn can_transition(from: AirplaneState, to: AirplaneState) -> bool {
+        TRANSITIONS
+            .iter()
+            .any(|&(source, target, _)| source == from && target == to)
+    }
+
+    /// Names of the state-dependent transactions accepted in `state`, in table order
+    /// and without duplicates.
+    pub fn allowed_transactions(state: AirplaneState) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        for &(source, _, name) in TRANSITIONS {
+            if source == state && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+}
+
+pub mod service {
+    use actix_web::{
+        error::ErrorInternalServerError, http::Method, FromRequest, HttpResponse, Query,
+    };
+    use bytes::Bytes;
+    use exonum::{
+        api::{
+            self,
+            backends::actix::{FutureResponse, HttpRequest, RawHandler, RequestHandler},
+            ServiceApiBackend, ServiceApiBuilder, ServiceApiState,
+        },
+        blockchain::{Schema as CoreSchema, Service, ServiceContext, Transaction},
+        crypto::{Hash, PublicKey, SecretKey, Signature},
+        encoding::{serialize::FromHex, Error as StreamStructError},
+        messages::RawTransaction,
+        node::TransactionSend,
+        storage::{Fork, ListProof, MapProof, Snapshot},
+    };
+
+    use chrono::{DateTime, Duration, Utc};
+    use exonum_time::schema::TimeSchema;
+
+    use futures::{
+        sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+        IntoFuture, Stream,
+    };
+
+    use std::collections::{BTreeMap, HashMap};
+    use std::error::Error as StdError;
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+
+    use serde::{
+        de::{self, value::MapDeserializer, DeserializeOwned, IntoDeserializer, Visitor},
+        Serialize,
+    };
+    use serde_json::{self, Value};
+
+    use schema::{
+        Airplane, AirplaneEvent, AirplaneServiceConfig, AirplaneState, AirplaneType, AuditEntry,
+        CertificationAuthority, CompletedRoute, FlightLogEntry, FlightPlan, Incident,
+        IntegrityViolation, MaintenanceRecord, OperatorQuota, RepairFlag, Runway, RunwaySchema,
+        ScheduledCheck, Schema, TimelineEntry, WeatherReport, DEFAULT_PRIORITY, EPOCH,
+        SCHEMA_VERSION,
+    };
+    use transactions::{
+        body_fields, check_body_field, AirplaneTransactions, TxExpireHeating, TxRegisterAirplane,
+        TxStartQueuedCheck, TxStartScheduledCheck, MAX_MESSAGE_ID,
+    };
+    use transitions::allowed_transactions;
+
+    /// Default ID and name of the service; see `AirplaneService::with_service_id`.
+    /// `transactions!` writes `SERVICE_ID` into the header of every message it creates.
+    pub const SERVICE_ID: u16 = 1;
+    pub const SERVICE_NAME: &str = "airplane";
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+    pub struct AirplaneQuery {
+        pub pub_key: PublicKey,
+    }
+
+    /// Body of `v1/airplanes/register`: the fields of `TxRegisterAirplane` and its signature,
+    /// without the message header used by the generic `v1/transactions` endpoint.
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    #[serde(deny_unknown_fields)]
+    pub struct TxRegisterAirplaneRequest {
+        pub pub_key: PublicKey,
+        pub name: String,
+        pub model: String,
+        pub serial_number: String,
+        pub airplane_type: u8,
+        pub max_takeoff_weight_kg: u32,
+        pub max_passenger_capacity: u16,
+        pub created_at_height: u64,
+        pub nonce: u64,
+        pub signature: Signature,
+    }
+
+    impl From<TxRegisterAirplaneRequest> for TxRegisterAirplane {
+        fn from(request: TxRegisterAirplaneRequest) -> Self {
+            TxRegisterAirplane::new_with_signature(
+                &request.pub_key,
+                &request.name,
+                &request.model,
+                &request.serial_number,
+                request.airplane_type,
+                request.max_takeoff_weight_kg,
+                request.max_passenger_capacity,
+                request.created_at_height,
+                request.nonce,
+                &request.signature,
+            )
+        }
+    }
+
+    /// Header of the messages posted to `v1/transactions` and the other transaction
+    /// endpoints. The body is checked by the `AirplaneTransactions` deserializer.
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct TransactionEnvelope {
+        #[allow(dead_code)]
+        protocol_version: u8,
+        service_id: u16,
+        message_id: u16,
+        #[allow(dead_code)]
+        signature: Signature,
+        body: Value,
+    }
+
+    /// A request body that does not match the expected JSON shape. Exonum rejects such
+    /// bodies with a bare `400 Bad Request`, so the transaction endpoints parse them
+    /// themselves to report the field at fault.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct TransactionDeserializationError {
+        /// Field at fault, as `body.<field>` for the fields of a message body; empty if
+        /// the request body as a whole is malformed.
+        pub field: String,
+
+        pub message: String,
+    }
+
+    impl TransactionDeserializationError {
+        fn new(field: &str, message: String) -> Self {
+            TransactionDeserializationError {
+                field: field.to_owned(),
+                message,
+            }
+        }
+    }
+
+    impl fmt::Display for TransactionDeserializationError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            if self.field.is_empty() {
+                write!(f, "{}", self.message)
+            } else {
+                write!(f, "{}: {}", self.field, self.message)
+            }
+        }
+    }
+
+    impl StdError for TransactionDeserializationError {}
+
+    impl de::Error for TransactionDeserializationError {
+        fn custom<T: fmt::Display>(message: T) -> Self {
+            TransactionDeserializationError::new("", message.to_string())
+        }
+
+        fn missing_field(field: &'static str) -> Self {
+            TransactionDeserializationError::new(field, "missing field".to_owned())
+        }
+
+        fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+            let message = format!("unknown field, expected one of {}", expected.join(", "));
+            TransactionDeserializationError::new(field, message)
+        }
+    }
+
+    impl From<TransactionDeserializationError> for api::Error {
+        fn from(error: TransactionDeserializationError) -> Self {
+            api::Error::BadRequest(
+                json!({
+                    "error": "malformed_json",
+                    "field": error.field,
+                    "detail": error.message,
+                })
+                .to_string(),
+            )
+        }
+    }
+
+    /// Value of a top-level field of a request body; errors raised while deserializing it
+    /// name the field.
+    struct FieldDeserializer {
+        field: String,
+        value: Value,
+    }
+
+    impl FieldDeserializer {
+        fn annotate(field: &str, error: serde_json::Error) -> TransactionDeserializationError {
+            TransactionDeserializationError::new(field, error.to_string())
+        }
+    }
+
+    impl<'de> de::Deserializer<'de> for FieldDeserializer {
+        type Error = TransactionDeserializationError;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            let field = self.field;
+            self.value
+                .deserialize_any(visitor)
+                .map_err(|e| Self::annotate(&field, e))
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            let field = self.field;
+            self.value
+                .deserialize_option(visitor)
+                .map_err(|e| Self::annotate(&field, e))
+        }
+
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            let field = self.field;
+            self.value
+                .deserialize_newtype_struct(name, visitor)
+                .map_err(|e| Self::annotate(&field, e))
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            name: &'static str,
+            variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            let field = self.field;
+            self.value
+                .deserialize_enum(name, variants, visitor)
+                .map_err(|e| Self::annotate(&field, e))
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf unit
+            unit_struct seq tuple tuple_struct map struct identifier ignored_any
+        }
+    }
+
+    impl<'de> IntoDeserializer<'de, TransactionDeserializationError> for FieldDeserializer {
+        type Deserializer = Self;
+
+        fn into_deserializer(self) -> Self {
+            self
+        }
+    }
+
+    /// Deserializes a request body, naming the top-level field at fault on errors.
+    pub fn from_request_body<T: DeserializeOwned>(
+        body: Value,
+    ) -> Result<T, TransactionDeserializationError> {
+        match body {
+            Value::Object(fields) => {
+                let fields = fields
+                    .into_iter()
+                    .map(|(field, value)| (field.clone(), FieldDeserializer { field, value }));
+                T::deserialize(MapDeserializer::new(fields))
+            }
+            body => serde_json::from_value(body)
+                .map_err(|e| TransactionDeserializationError::new("", e.to_string())),
+        }
+    }
+
+    /// Checks the body of a message field by field, since the Exonum deserializer ignores
+    /// unknown fields and does not name the field it fails on.
+    fn check_transaction_body(
+        message_id: u16,
+        body: &Value,
+    ) -> Result<(), TransactionDeserializationError> {
+        let body = body.as_object().ok_or_else(|| {
+            TransactionDeserializationError::new("body", "expected an object".to_owned())
+        })?;
+        let fields = body_fields(message_id);
+        if let Some(field) = body.keys().find(|field| !fields.contains(&field.as_str())) {
+            Err(TransactionDeserializationError::new(
+                &format!("body.{}", field),
+                format!("unknown field, expected one of {}", fields.join(", ")),
+            ))?
+        }
+        for field in fields {
+            let field_name = format!("body.{}", field);
+            let value = body.get(*field).ok_or_else(|| {
+                TransactionDeserializationError::new(&field_name, "missing field".to_owned())
+            })?;
+            check_body_field(message_id, field, value)
+                .map_err(|message| TransactionDeserializationError::new(&field_name, message))?;
+        }
+        Ok(())
+    }
+
+    /// Deserializes a message posted to the transaction endpoints of the service instance
+    /// with `service_id`. The Exonum deserializer does not say what is wrong with a
+    /// message, so the header and then the body are checked field by field first.
+    pub fn parse_transaction(
+        mut body: Value,
+        service_id: u16,
+    ) -> Result<AirplaneTransactions, TransactionDeserializationError> {
+        let envelope: TransactionEnvelope = from_request_body(body.clone())?;
+        if envelope.service_id != service_id {
+            Err(TransactionDeserializationError::new(
+                "service_id",
+                format!("expected {}", service_id),
+            ))?
+        }
+        if envelope.message_id > MAX_MESSAGE_ID {
+            Err(TransactionDeserializationError::new(
+                "message_id",
+                format!("unknown transaction type, expected 0 to {}", MAX_MESSAGE_ID),
+            ))?
+        }
+        check_transaction_body(envelope.message_id, &envelope.body)?;
+        // The Exonum deserializer only accepts `SERVICE_ID`; the signature still covers
+        // `service_id`, which is put back once the message is parsed.
+        body["service_id"] = json!(SERVICE_ID);
+        let tx: AirplaneTransactions = serde_json::from_value(body).map_err(|_| {
+            TransactionDeserializationError::new(
+                "body",
+                format!(
+                    "does not match the fields of transaction type {}",
+                    envelope.message_id
+                ),
+            )
+        })?;
+        Ok(tx.with_service_id(service_id))
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+    pub struct TransactionHashQuery {
+        pub hash: Hash,
+    }
+
+    pub const DEFAULT_PER_PAGE: u64 = 50;
+    pub const MAX_PER_PAGE: u64 = 200;
+
+    /// Maximum number of keys accepted by `v1/airplanes/batch-query`.
+    pub const MAX_BATCH_QUERY_SIZE: usize = 200;
+
+    /// Default `min_days` of `v1/airplanes/inactive`.
+    pub const DEFAULT_MIN_INACTIVE_DAYS: u32 = 90;
+
+    fn default_per_page() -> u64 {
+        DEFAULT_PER_PAGE
     }
 
-    impl<'a> Schema<&'a mut Fork> {
-        pub fn airplanes_mut(&mut self) -> MapIndex<&mut Fork, PublicKey, Airplane> {
-            MapIndex::new("airplanes", &mut self.view)
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+    pub struct PaginationParams {
+        #[serde(default)]
+        pub page: u64,
+
+        #[serde(default = "default_per_page")]
+        pub per_page: u64,
+    }
+
+    impl PaginationParams {
+        pub fn new(page: u64, per_page: u64) -> api::Result<Self> {
+            if per_page > MAX_PER_PAGE {
+                Err(api::Error::BadRequest(format!(
+                    "\"per_page must not exceed {}\"",
+                    MAX_PER_PAGE
+                )))
+            } else {
+                Ok(PaginationParams { page, per_page })
+            }
+        }
+
+        /// Index of the first item on the requested page.
+        pub fn offset(&self) -> u64 {
+            self.page.saturating_mul(self.per_page)
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct PaginatedResponse<T> {
+        pub items: Vec<T>,
+        pub total: u64,
+        pub page: u64,
+        pub per_page: u64,
+    }
+
+    impl<T> PaginatedResponse<T> {
+        /// Collects the requested page from `iter`, counting all of its items.
+        pub fn from_iter<I: Iterator<Item = T>>(iter: I, params: PaginationParams) -> Self {
+            let offset = params.offset();
+            let mut items = Vec::new();
+            let mut total = 0;
+            for item in iter {
+                if total >= offset && (items.len() as u64) < params.per_page {
+                    items.push(item);
+                }
+                total += 1;
+            }
+            PaginatedResponse {
+                items,
+                total,
+                page: params.page,
+                per_page: params.per_page,
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+    pub struct InternalStats {
+        /// Technical checks that ended with `is_airplane_ok == false`.
+        pub failed_technical_checks_total: u64,
+
+        pub total_decommissioned: u64,
+
+        /// Average heating time over passed technical checks.
+        pub avg_heating_seconds: f64,
+    }
+
+    /// Served by `v1/service/health`.
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+    pub struct HealthStatus {
+        /// Whether the stored data uses the current `SCHEMA_VERSION` layout.
+        pub storage_ok: bool,
+
+        /// Whether the time oracle has committed a time.
+        pub time_oracle_ok: bool,
+
+        pub current_time: Option<DateTime<Utc>>,
+
+        pub airplane_count: u64,
+    }
+
+    impl HealthStatus {
+        /// Reads the status from `snapshot`. Exonum panics on storage failures instead of
+        /// returning errors, so a reachable but outdated layout is the failure reported here.
+        pub fn check<T: AsRef<dyn Snapshot>>(snapshot: T, service_name: &str) -> Self {
+            let current_time = TimeSchema::new(&snapshot).time().get();
+            let schema = Schema::for_service(snapshot, service_name);
+            HealthStatus {
+                storage_ok: schema.schema_version() == SCHEMA_VERSION,
+                time_oracle_ok: current_time.is_some(),
+                current_time,
+                airplane_count: schema.airplane_count(),
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+    pub struct FleetStats {
+        /// Number of airplanes that are not decommissioned.
+        pub total: u64,
+
+        /// Configured `max_fleet_size`, `None` if the fleet size is unlimited.
+        pub max_fleet_size: Option<u64>,
+
+        /// Number of airplanes per state name; every known state is present.
+        pub by_state: HashMap<String, u64>,
+
+        pub total_flight_seconds: u64,
+
+        pub total_flights: u64,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+    pub struct AirplanePageQuery {
+        pub pub_key: PublicKey,
+
+        #[serde(default)]
+        pub page: u64,
+
+        #[serde(default = "default_per_page")]
+        pub per_page: u64,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct AirplanesQuery {
+        pub state: Option<String>,
+
+        /// Only airplanes whose registration expires within this many days, including
+        /// those that have already expired.
+        pub expiring_within_days: Option<u32>,
+
+        #[serde(default)]
+        pub page: u64,
+
+        #[serde(default = "default_per_page")]
+        pub per_page: u64,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct InactiveAirplanesQuery {
+        /// Only airplanes inactive for more than this many days; defaults to
+        /// `DEFAULT_MIN_INACTIVE_DAYS`.
+        pub min_days: Option<u32>,
+
+        #[serde(default)]
+        pub page: u64,
+
+        #[serde(default = "default_per_page")]
+        pub per_page: u64,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+    pub struct ReadyAirplanesQuery {
+        /// Also list airplanes waiting for flight that cannot be dispatched, with their
+        /// `blocking_reasons`.
+        #[serde(default)]
+        pub include_blocked: bool,
+    }
+
+    /// Selects the flight log entries with indexes from `from` up to, but not including, `to`.
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+    pub struct FlightLogQuery {
+        pub pub_key: PublicKey,
+
+        #[serde(default)]
+        pub from: u64,
+
+        /// Defaults to `from + DEFAULT_PER_PAGE`; at most `MAX_PER_PAGE` entries are returned.
+        pub to: Option<u64>,
+    }
+
+    /// Optional airplane filter of `v1/flight-plans`.
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+    pub struct FlightPlanQuery {
+        pub pub_key: Option<PublicKey>,
+    }
+
+    /// Time window of `v1/airplanes/timeline`, in RFC 3339 format.
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+    pub struct TimelineQuery {
+        pub from: DateTime<Utc>,
+        pub to: DateTime<Utc>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+    pub struct AuditEntryProofQuery {
+        pub pub_key: PublicKey,
+        pub index: u64,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct AirplaneNameQuery {
+        pub name: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct AirplanesByStateQuery {
+        pub state: String,
+
+        #[serde(default)]
+        pub page: u64,
+
+        #[serde(default = "default_per_page")]
+        pub per_page: u64,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct AirplanesByTypeQuery {
+        /// Type name, e.g. `Cargo`.
+        #[serde(rename = "type")]
+        pub airplane_type: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct TransactionResponse {
+        pub tx_hash: Hash,
+
+        /// Name of the submitted transaction struct, e.g. `"TxRegisterAirplane"`.
+        pub transaction_type: &'static str,
+    }
+
+    /// Response of `v1/airplane`: the stored airplane and values computed from it.
+    #[derive(Debug, Serialize)]
+    pub struct AirplaneResponse {
+        #[serde(flatten)]
+        pub airplane: Airplane,
+
+        /// See `Airplane::remaining_heating_seconds`; also `None` before the time oracle
+        /// has committed a time.
+        pub remaining_heating_seconds: Option<i64>,
+
+        /// `Airplane::time_in_state` of every state, keyed by state name.
+        pub time_in_state_seconds: BTreeMap<String, u64>,
+    }
+
+    /// `Airplane` as served by the `v2/` endpoints: camelCase field names, the key as a hex
+    /// string and RFC 3339 timestamps instead of the `{secs, nanos}` objects of `v1/`.
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct AirplaneDtoV2 {
+        pub pub_key: String,
+
+        pub name: String,
+
+        pub state_number: u8,
+
+        pub state_str: String,
+
+        pub engine_heating_start_time: DateTime<Utc>,
+
+        pub engine_heating_time_seconds: u32,
+
+        pub flight_count: u32,
+
+        pub accumulated_flight_seconds: u64,
+
+        pub flight_start_time: DateTime<Utc>,
+
+        pub last_emergency_reason: String,
+
+        pub emergency_count: u16,
+
+        pub airplane_model: String,
+
+        pub serial_number: String,
+
+        pub last_flight_end_time: DateTime<Utc>,
+
+        pub last_cancellation_reason: String,
+
+        pub cancellation_count: u16,
+
+        pub fuel_level_percent: u8,
+
+        pub current_departure_iata: String,
+
+        pub current_arrival_iata: String,
+
+        pub registered_at: DateTime<Utc>,
+
+        pub grounded_reason: String,
+
+        pub technical_check_deadline: DateTime<Utc>,
+
+        pub airplane_type: u8,
+
+        pub priority: u8,
+
+        pub cargo_weight_kg: u32,
+
+        pub max_takeoff_weight_kg: u32,
+
+        pub metadata: String,
+
+        pub passenger_count: u16,
+
+        pub max_passenger_capacity: u16,
+
+        pub airworthiness_cert_expiry: DateTime<Utc>,
+
+        pub required_repairs_bitmask: u8,
+
+        pub home_airport: String,
+
+        pub registration_expiry: DateTime<Utc>,
+
+        pub inactive_since: DateTime<Utc>,
+
+        pub last_state_change_time: DateTime<Utc>,
+
+        pub time_in_state_seconds: Vec<u64>,
+    }
+
+    impl<'a> From<&'a Airplane> for AirplaneDtoV2 {
+        fn from(airplane: &'a Airplane) -> Self {
+            AirplaneDtoV2 {
+                pub_key: airplane.pub_key().to_hex(),
+                name: airplane.name().to_owned(),
+                state_number: airplane.state_number(),
+                state_str: airplane.state_str().to_owned(),
+                engine_heating_start_time: airplane.engine_heating_start_time(),
+                engine_heating_time_seconds: airplane.engine_heating_time_seconds(),
+                flight_count: airplane.flight_count(),
+                accumulated_flight_seconds: airplane.accumulated_flight_seconds(),
+                flight_start_time: airplane.flight_start_time(),
+                last_emergency_reason: airplane.last_emergency_reason().to_owned(),
+                emergency_count: airplane.emergency_count(),
+                airplane_model: airplane.airplane_model().to_owned(),
+                serial_number: airplane.serial_number().to_owned(),
+                last_flight_end_time: airplane.last_flight_end_time(),
+                last_cancellation_reason: airplane.last_cancellation_reason().to_owned(),
+                cancellation_count: airplane.cancellation_count(),
+                fuel_level_percent: airplane.fuel_level_percent(),
+                current_departure_iata: airplane.current_departure_iata().to_owned(),
+                current_arrival_iata: airplane.current_arrival_iata().to_owned(),
+                registered_at: airplane.registered_at(),
+                grounded_reason: airplane.grounded_reason().to_owned(),
+                technical_check_deadline: airplane.technical_check_deadline(),
+                airplane_type: airplane.airplane_type(),
+                priority: airplane.priority(),
+                cargo_weight_kg: airplane.cargo_weight_kg(),
+                max_takeoff_weight_kg: airplane.max_takeoff_weight_kg(),
+                metadata: airplane.metadata().to_owned(),
+                passenger_count: airplane.passenger_count(),
+                max_passenger_capacity: airplane.max_passenger_capacity(),
+                airworthiness_cert_expiry: airplane.airworthiness_cert_expiry(),
+                required_repairs_bitmask: airplane.required_repairs_bitmask(),
+                home_airport: airplane.home_airport().to_owned(),
+                registration_expiry: airplane.registration_expiry(),
+                inactive_since: airplane.inactive_since(),
+                last_state_change_time: airplane.last_state_change_time(),
+                time_in_state_seconds: airplane.time_in_state_seconds(),
+            }
+        }
+    }
+
+    impl From<Airplane> for AirplaneDtoV2 {
+        fn from(airplane: Airplane) -> Self {
+            AirplaneDtoV2::from(&airplane)
+        }
+    }
+
+    /// Response of `v2/airplane`; `AirplaneResponse` with the airplane as `AirplaneDtoV2`.
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct AirplaneResponseV2 {
+        #[serde(flatten)]
+        pub airplane: AirplaneDtoV2,
+
+        pub remaining_heating_seconds: Option<i64>,
+
+        pub time_in_state_seconds: BTreeMap<String, u64>,
+    }
+
+    impl From<AirplaneResponse> for AirplaneResponseV2 {
+        fn from(response: AirplaneResponse) -> Self {
+            AirplaneResponseV2 {
+                airplane: response.airplane.into(),
+                remaining_heating_seconds: response.remaining_heating_seconds,
+                time_in_state_seconds: response.time_in_state_seconds,
+            }
+        }
+    }
+
+    /// Converts the `v1/` responses containing airplanes to their `v2/` form.
+    pub trait IntoV2 {
+        type Output: Serialize;
+
+        fn into_v2(self) -> Self::Output;
+    }
+
+    impl IntoV2 for Airplane {
+        type Output = AirplaneDtoV2;
+
+        fn into_v2(self) -> AirplaneDtoV2 {
+            self.into()
+        }
+    }
+
+    impl IntoV2 for AirplaneResponse {
+        type Output = AirplaneResponseV2;
+
+        fn into_v2(self) -> AirplaneResponseV2 {
+            self.into()
+        }
+    }
+
+    impl<T: IntoV2> IntoV2 for Option<T> {
+        type Output = Option<T::Output>;
+
+        fn into_v2(self) -> Self::Output {
+            self.map(IntoV2::into_v2)
+        }
+    }
+
+    impl<T: IntoV2> IntoV2 for Vec<T> {
+        type Output = Vec<T::Output>;
+
+        fn into_v2(self) -> Self::Output {
+            self.into_iter().map(IntoV2::into_v2).collect()
+        }
+    }
+
+    impl IntoV2 for AirplaneReadiness {
+        type Output = AirplaneReadiness<AirplaneDtoV2>;
+
+        fn into_v2(self) -> Self::Output {
+            AirplaneReadiness {
+                airplane: self.airplane.into(),
+                blocking_reasons: self.blocking_reasons,
+            }
+        }
+    }
+
+    impl<T: IntoV2> IntoV2 for PaginatedResponse<T> {
+        type Output = PaginatedResponse<T::Output>;
+
+        fn into_v2(self) -> Self::Output {
+            PaginatedResponse {
+                items: self.items.into_v2(),
+                total: self.total,
+                page: self.page,
+                per_page: self.per_page,
+            }
+        }
+    }
+
+    /// `snake_case` to `camelCase`; other keys, such as state names, are returned unchanged.
+    fn to_camel_case(key: &str) -> String {
+        let mut result = String::with_capacity(key.len());
+        let mut upper = false;
+        for c in key.chars() {
+            if c == '_' {
+                upper = !result.is_empty();
+            } else if upper {
+                result.extend(c.to_uppercase());
+                upper = false;
+            } else {
+                result.push(c);
+            }
+        }
+        result
+    }
+
+    /// `camelCase` to `snake_case`. Only applied to identifiers, which have no spaces.
+    fn to_snake_case(key: &str) -> String {
+        if key.contains(' ') {
+            return key.to_owned();
+        }
+        let mut result = String::with_capacity(key.len() + 4);
+        for c in key.chars() {
+            if c.is_uppercase() {
+                result.push('_');
+                result.extend(c.to_lowercase());
+            } else {
+                result.push(c);
+            }
+        }
+        result
+    }
+
+    fn map_keys(value: Value, f: &dyn Fn(&str) -> String) -> Value {
+        match value {
+            Value::Object(object) => Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (f(&key), map_keys(value, f)))
+                    .collect(),
+            ),
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|item| map_keys(item, f)).collect())
+            }
+            value => value,
+        }
+    }
+
+    /// Renames the `snake_case` keys of every object in `value` to `camelCase`.
+    pub fn camel_case_keys(value: Value) -> Value {
+        map_keys(value, &to_camel_case)
+    }
+
+    /// Serializes a response of the raw `v1/` handlers, with `camelCase` keys for `v2/`.
+    fn versioned_json<T: Serialize>(value: &T, v2: bool) -> Value {
+        let value = serde_json::to_value(value).expect("Unable to serialize response");
+        if v2 {
+            camel_case_keys(value)
+        } else {
+            value
+        }
+    }
+
+    /// Renames the `camelCase` keys of every object in `value` to `snake_case`.
+    pub fn snake_case_keys(value: Value) -> Value {
+        map_keys(value, &to_snake_case)
+    }
+
+    /// Entry of `v1/airplanes/ready`.
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct AirplaneReadiness<A = Airplane> {
+        pub airplane: A,
+
+        /// Why the airplane cannot be dispatched right now; empty if it is ready.
+        pub blocking_reasons: Vec<String>,
+    }
+
+    /// Response of `v1/airplane/allowed-actions`.
+    #[derive(Debug, Serialize)]
+    pub struct AllowedActions {
+        pub state: String,
+
+        /// Names of the transactions accepted in `state`, e.g. `"TxStartFlying"`.
+        pub actions: Vec<&'static str>,
+    }
+
+    /// Entry of `v1/airplanes/check-queue`.
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+    pub struct QueuedCheck {
+        pub pub_key: PublicKey,
+
+        pub priority: u8,
+    }
+
+    /// Subscribers of `v1/airplane/stream`. The service fills their streams in
+    /// `after_commit`, so only committed events are sent.
+    #[derive(Debug, Clone, Default)]
+    pub struct EventStreams {
+        subscribers: Arc<Mutex<Vec<EventSubscriber>>>,
+    }
+
+    #[derive(Debug)]
+    struct EventSubscriber {
+        pub_key: PublicKey,
+
+        /// Number of the airplane's events already sent.
+        sent: u64,
+
+        sender: UnboundedSender<AirplaneEvent>,
+    }
+
+    impl EventStreams {
+        /// Returns a stream of the airplane's events appended after `snapshot`.
+        pub fn subscribe<T: AsRef<dyn Snapshot>>(
+            &self,
+            pub_key: PublicKey,
+            snapshot: T,
+            service_name: &str,
+        ) -> UnboundedReceiver<AirplaneEvent> {
+            let (sender, receiver) = mpsc::unbounded();
+            let sent = Schema::for_service(snapshot, service_name)
+                .events(&pub_key)
+                .len();
+            self.subscribers
+                .lock()
+                .expect("Event stream subscribers lock is poisoned")
+                .push(EventSubscriber {
+                    pub_key,
+                    sent,
+                    sender,
+                });
+            receiver
+        }
+
+        /// Sends every event appended since the previous call and drops the subscribers
+        /// whose streams were closed.
+        pub fn publish<T: AsRef<dyn Snapshot>>(&self, snapshot: T, service_name: &str) {
+            let schema = Schema::for_service(snapshot, service_name);
+            let mut subscribers = self
+                .subscribers
+                .lock()
+                .expect("Event stream subscribers lock is poisoned");
+            subscribers.retain_mut(|subscriber| {
+                for event in schema
+                    .events(&subscriber.pub_key)
+                    .iter_from(subscriber.sent)
+                {
+                    if subscriber.sender.unbounded_send(event).is_err() {
+                        return false;
+                    }
+                    subscriber.sent += 1;
+                }
+                true
+            });
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct AirplaneApi;
+
+    fn parse_airplane_state(state_str: &str) -> api::Result<AirplaneState> {
+        state_str
+            .parse()
+            .map_err(|e| api::Error::BadRequest(format!("\"{}\"", e)))
+    }
+
+    impl AirplaneApi {
+        pub fn get_airplane(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplaneQuery,
+        ) -> api::Result<AirplaneResponse> {
+            let snapshot = state.snapshot();
+            let current_time = TimeSchema::new(&snapshot).time().get();
+            let schema = Schema::for_service(snapshot, service_name);
+            let airplane = schema
+                .airplane(&query.pub_key)
+                .ok_or_else(|| api::Error::NotFound("\"Airplane not found\"".to_owned()))?;
+            let time_in_state_seconds = AirplaneState::all()
+                .into_iter()
+                .map(|airplane_state| {
+                    let seconds =
+                        airplane.time_in_state(airplane_state, current_time.unwrap_or(EPOCH));
+                    (airplane_state.to_string(), seconds)
+                })
+                .collect();
+            Ok(AirplaneResponse {
+                remaining_heating_seconds: current_time
+                    .and_then(|time| airplane.remaining_heating_seconds(time)),
+                time_in_state_seconds,
+                airplane,
+            })
+        }
+
+        /// Streams the airplane's events as they are committed.
+        pub fn stream_events(
+            streams: &EventStreams,
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplaneQuery,
+        ) -> api::Result<impl Stream<Item = AirplaneEvent, Error = ()>> {
+            let snapshot = state.snapshot();
+            if Schema::for_service(&snapshot, service_name)
+                .airplane(&query.pub_key)
+                .is_none()
+            {
+                Err(api::Error::NotFound("\"Airplane not found\"".to_owned()))?
+            }
+            Ok(streams.subscribe(query.pub_key, snapshot, service_name))
+        }
+
+        /// Wires `v1/airplane/stream`, which responds with newline-delimited JSON events.
+        ///
+        /// The typed endpoints of `ServiceApiScope` return a single value, so this one is
+        /// registered on the underlying actix-web backend.
+        pub fn wire_event_stream(
+            builder: &mut ServiceApiBuilder,
+            streams: EventStreams,
+            service_name: &'static str,
+        ) {
+            for &(name, v2) in &[("v1/airplane/stream", false), ("v2/airplane/stream", true)] {
+                let streams = streams.clone();
+                let index = move |request: HttpRequest| -> FutureResponse {
+                    let response = Query::from_request(&request, &())
+                        .and_then(|query: Query<AirplaneQuery>| {
+                            Self::stream_events(
+                                &streams,
+                                request.state(),
+                                service_name,
+                                query.into_inner(),
+                            )
+                            .map_err(From::from)
+                        })
+                        .map(|events| {
+                            let lines = events
+                                .map(move |event| {
+                                    let mut line = serde_json::to_vec(&versioned_json(&event, v2))
+                                        .expect("Unable to serialize airplane event");
+                                    line.push(b'\n');
+                                    Bytes::from(line)
+                                })
+                                .map_err(|()| ErrorInternalServerError("Event stream failed"));
+                            HttpResponse::Ok()
+                                .content_type("application/x-ndjson")
+                                .streaming(lines)
+                        });
+                    Box::new(response.into_future())
+                };
+
+                builder
+                    .public_scope()
+                    .web_backend()
+                    .raw_handler(RequestHandler {
+                        name: name.to_owned(),
+                        method: Method::GET,
+                        inner: Arc::from(index) as Arc<RawHandler>,
+                    });
+            }
+        }
+
+        pub fn health_check(
+            state: &ServiceApiState,
+            service_name: &str,
+            _query: (),
+        ) -> api::Result<HealthStatus> {
+            Ok(HealthStatus::check(state.snapshot(), service_name))
+        }
+
+        /// Wires `v1/service/health` and `v2/service/health`, which respond with `503 Service Unavailable` unless
+        /// `storage_ok` is set.
+        ///
+        /// The typed endpoints of `ServiceApiScope` cannot set the status of a successful
+        /// response, so this one is registered on the underlying actix-web backend.
+        pub fn wire_health_check(builder: &mut ServiceApiBuilder, service_name: &'static str) {
+            for &(name, v2) in &[("v1/service/health", false), ("v2/service/health", true)] {
+                let index = move |request: HttpRequest| -> FutureResponse {
+                    let response = Self::health_check(request.state(), service_name, ())
+                        .map_err(From::from)
+                        .map(|status| {
+                            let body = versioned_json(&status, v2);
+                            if status.storage_ok {
+                                HttpResponse::Ok().json(body)
+                            } else {
+                                HttpResponse::ServiceUnavailable().json(body)
+                            }
+                        });
+                    Box::new(response.into_future())
+                };
+
+                builder
+                    .public_scope()
+                    .web_backend()
+                    .raw_handler(RequestHandler {
+                        name: name.to_owned(),
+                        method: Method::GET,
+                        inner: Arc::from(index) as Arc<RawHandler>,
+                    });
+            }
+        }
+
+        pub fn get_operator_quota(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplaneQuery,
+        ) -> api::Result<OperatorQuota> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            schema
+                .operator_quota(&query.pub_key)
+                .ok_or_else(|| api::Error::NotFound("\"Operator quota not found\"".to_owned()))
         }
-    }
-}
 
-pub mod service {
-    use exonum::{
-        api::{self, ServiceApiBuilder, ServiceApiState},
-        blockchain::{Service, Transaction, TransactionSet},
-        crypto::{Hash, PublicKey},
-        encoding::Error as StreamStructError,
-        messages::RawTransaction,
-        node::TransactionSend,
-        storage::Snapshot,
-    };
+        /// Wires `v1/operators/{pub_key}/quota` and its `v2/` counterpart.
+        ///
+        /// `ServiceApiScope` only passes query parameters, so the key in the path is read
+        /// on the underlying actix-web backend.
+        pub fn wire_operator_quota(builder: &mut ServiceApiBuilder, service_name: &'static str) {
+            for &(name, v2) in &[
+                ("v1/operators/{pub_key}/quota", false),
+                ("v2/operators/{pub_key}/quota", true),
+            ] {
+                let index = move |request: HttpRequest| -> FutureResponse {
+                    let pub_key = request.match_info().get("pub_key").unwrap_or_default();
+                    let response = PublicKey::from_hex(pub_key)
+                        .map_err(|e| api::Error::BadRequest(format!("\"{}\"", e)))
+                        .and_then(|pub_key| {
+                            Self::get_operator_quota(
+                                request.state(),
+                                service_name,
+                                AirplaneQuery { pub_key },
+                            )
+                        })
+                        .map_err(From::from)
+                        .map(|quota| HttpResponse::Ok().json(versioned_json(&quota, v2)));
+                    Box::new(response.into_future())
+                };
 
-    use schema::{Airplane, Schema};
-    use transactions::AirplaneTransactions;
+                builder
+                    .public_scope()
+                    .web_backend()
+                    .raw_handler(RequestHandler {
+                        name: name.to_owned(),
+                        method: Method::GET,
+                        inner: Arc::from(index) as Arc<RawHandler>,
+                    });
+            }
+        }
 
-    pub const SERVICE_ID: u16 = 1;
-    pub const SERVICE_NAME: &str = "airplane";
+        /// Returns the last nonce used for the key; the next transaction must use a greater one.
+        pub fn get_last_nonce(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplaneQuery,
+        ) -> api::Result<u64> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(schema.last_used_nonce(&query.pub_key))
+        }
 
-    #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
-    pub struct AirplaneQuery {
-        pub pub_key: PublicKey,
-    }
+        /// Lists the state-dependent transactions the airplane accepts in its current state.
+        pub fn get_allowed_actions(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplaneQuery,
+        ) -> api::Result<AllowedActions> {
+            let airplane = Self::get_airplane(state, service_name, query)?.airplane;
+            let airplane_state = airplane
+                .state()
+                .map_err(|e| api::Error::InternalError(e.to_string().into()))?;
+            Ok(AllowedActions {
+                state: airplane_state.to_string(),
+                actions: allowed_transactions(airplane_state),
+            })
+        }
 
-    #[derive(Debug, Serialize, Deserialize)]
-    pub struct TransactionResponse {
-        pub tx_hash: Hash,
-    }
+        /// Returns airplanes in the order of `query`, with `None` for unknown keys.
+        pub fn get_airplanes_batch(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: Vec<AirplaneQuery>,
+        ) -> api::Result<Vec<Option<Airplane>>> {
+            if query.len() > MAX_BATCH_QUERY_SIZE {
+                Err(api::Error::BadRequest(format!(
+                    "\"Batch query must not contain more than {} keys\"",
+                    MAX_BATCH_QUERY_SIZE
+                )))?
+            }
 
-    #[derive(Debug, Clone)]
-    pub struct AirplaneApi;
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(query
+                .iter()
+                .map(|item| schema.airplane(&item.pub_key))
+                .collect())
+        }
 
-    impl AirplaneApi {
-        pub fn get_airplane(
+        /// Returns every stored airplane, ordered by public key, for backups and audits.
+        pub fn export_snapshot(
             state: &ServiceApiState,
-            query: AirplaneQuery,
+            service_name: &str,
+            _query: (),
+        ) -> api::Result<Vec<Airplane>> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(schema.export_airplanes())
+        }
+
+        pub fn search_airplane_by_name(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplaneNameQuery,
         ) -> api::Result<Airplane> {
             let snapshot = state.snapshot();
-            let schema = Schema::new(snapshot);
+            let schema = Schema::for_service(snapshot, service_name);
             schema
-                .airplane(&query.pub_key)
+                .find_by_name(&query.name)
                 .ok_or_else(|| api::Error::NotFound("\"Airplane not found\"".to_owned()))
         }
 
+        /// Returns a Merkle proof of presence (or absence) of the airplane in the `airplanes` table.
+        ///
+        /// The proof is only valid against the `airplanes` root at the snapshot height
+        /// at which the request was served.
+        pub fn get_airplane_proof(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplaneQuery,
+        ) -> api::Result<MapProof<PublicKey, Airplane>> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(schema.airplane_proof(&query.pub_key))
+        }
+
+        pub fn get_airplane_events(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplanePageQuery,
+        ) -> api::Result<PaginatedResponse<AirplaneEvent>> {
+            let params = PaginationParams::new(query.page, query.per_page)?;
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            let events = schema.events(&query.pub_key);
+            Ok(PaginatedResponse {
+                items: events
+                    .iter_from(params.offset())
+                    .take(params.per_page as usize)
+                    .collect(),
+                total: events.len(),
+                page: params.page,
+                per_page: params.per_page,
+            })
+        }
+
+        pub fn get_maintenance_records(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplanePageQuery,
+        ) -> api::Result<PaginatedResponse<MaintenanceRecord>> {
+            let params = PaginationParams::new(query.page, query.per_page)?;
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            let records = schema.maintenance_records(&query.pub_key);
+            Ok(PaginatedResponse {
+                items: records
+                    .iter_from(params.offset())
+                    .take(params.per_page as usize)
+                    .collect(),
+                total: records.len(),
+                page: params.page,
+                per_page: params.per_page,
+            })
+        }
+
+        pub fn get_incidents(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplanePageQuery,
+        ) -> api::Result<PaginatedResponse<Incident>> {
+            let params = PaginationParams::new(query.page, query.per_page)?;
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            let incidents = schema.incidents(&query.pub_key);
+            Ok(PaginatedResponse {
+                items: incidents
+                    .iter_from(params.offset())
+                    .take(params.per_page as usize)
+                    .collect(),
+                total: incidents.len(),
+                page: params.page,
+                per_page: params.per_page,
+            })
+        }
+
+        pub fn get_all_airplanes(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplanesQuery,
+        ) -> api::Result<PaginatedResponse<Airplane>> {
+            let params = PaginationParams::new(query.page, query.per_page)?;
+            let snapshot = state.snapshot();
+            let current_time = TimeSchema::new(&snapshot).time().get().unwrap_or(EPOCH);
+            let schema = Schema::for_service(snapshot, service_name);
+            let airplane_state = match query.state {
+                Some(ref state_str) => Some(parse_airplane_state(state_str)?),
+                None => None,
+            };
+            let expiring_before = query
+                .expiring_within_days
+                .map(|days| current_time + Duration::days(i64::from(days)));
+            if airplane_state.is_none() && expiring_before.is_none() {
+                return Ok(schema.airplanes_page(params));
+            }
+
+            Ok(PaginatedResponse::from_iter(
+                schema.airplanes_in_order().filter(|airplane| {
+                    let state_matches = match airplane_state {
+                        Some(airplane_state) => airplane.state_number() == u8::from(airplane_state),
+                        None => airplane.state_number() != u8::from(AirplaneState::Decommissioned),
+                    };
+                    state_matches
+                        && expiring_before
+                            .is_none_or(|before| airplane.registration_expiry() <= before)
+                }),
+                params,
+            ))
+        }
+
+        pub fn get_decommissioned_airplanes(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: PaginationParams,
+        ) -> api::Result<PaginatedResponse<Airplane>> {
+            let params = PaginationParams::new(query.page, query.per_page)?;
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(PaginatedResponse::from_iter(
+                schema.all_decommissioned().into_iter(),
+                params,
+            ))
+        }
+
+        /// Airplanes deactivated with `TxDeactivateAirplane` more than `min_days` ago.
+        pub fn get_inactive_airplanes(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: InactiveAirplanesQuery,
+        ) -> api::Result<PaginatedResponse<Airplane>> {
+            let params = PaginationParams::new(query.page, query.per_page)?;
+            let snapshot = state.snapshot();
+            let current_time = TimeSchema::new(&snapshot).time().get().unwrap_or(EPOCH);
+            let schema = Schema::for_service(snapshot, service_name);
+            let min_days = query.min_days.unwrap_or(DEFAULT_MIN_INACTIVE_DAYS);
+            let inactive_before = current_time - Duration::days(i64::from(min_days));
+
+            Ok(PaginatedResponse::from_iter(
+                schema.inactive_airplanes(inactive_before).into_iter(),
+                params,
+            ))
+        }
+
+        /// Airplanes that can be dispatched right now; see `Schema::dispatch_readiness`.
+        pub fn get_ready_airplanes(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: ReadyAirplanesQuery,
+        ) -> api::Result<Vec<AirplaneReadiness>> {
+            let snapshot = state.snapshot();
+            let current_time = TimeSchema::new(&snapshot).time().get().unwrap_or(EPOCH);
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(schema
+                .dispatch_readiness(current_time)
+                .into_iter()
+                .filter(|readiness| query.include_blocked || readiness.blocking_reasons.is_empty())
+                .collect())
+        }
+
+        pub fn get_airplanes_by_state(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplanesByStateQuery,
+        ) -> api::Result<PaginatedResponse<Airplane>> {
+            let params = PaginationParams::new(query.page, query.per_page)?;
+            let airplane_state = parse_airplane_state(&query.state)?;
+
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(PaginatedResponse::from_iter(
+                schema
+                    .airplanes_in_state(airplane_state)
+                    .iter()
+                    .filter_map(|pub_key| schema.airplane(pub_key)),
+                params,
+            ))
+        }
+
+        pub fn get_airplanes_by_type(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplanesByTypeQuery,
+        ) -> api::Result<Vec<Airplane>> {
+            let airplane_type: AirplaneType = query
+                .airplane_type
+                .parse()
+                .map_err(|e| api::Error::BadRequest(format!("\"{}\"", e)))?;
+
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(schema.airplanes_by_type(airplane_type))
+        }
+
+        /// Queued technical checks in the order they will start.
+        pub fn get_check_queue(
+            state: &ServiceApiState,
+            service_name: &str,
+            _query: (),
+        ) -> api::Result<Vec<QueuedCheck>> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            let priorities = schema.queued_check_priorities();
+            Ok(schema
+                .queued_checks()
+                .into_iter()
+                .map(|pub_key| QueuedCheck {
+                    priority: priorities.get(&pub_key).unwrap_or(DEFAULT_PRIORITY),
+                    pub_key,
+                })
+                .collect())
+        }
+
+        pub fn get_stats(
+            state: &ServiceApiState,
+            service_name: &str,
+            _query: (),
+        ) -> api::Result<FleetStats> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+
+            let mut stats = FleetStats {
+                total: schema.airplane_count(),
+                max_fleet_size: schema.service_config().fleet_limit(),
+                by_state: AirplaneState::all()
+                    .into_iter()
+                    .map(|airplane_state| (airplane_state.to_string(), 0))
+                    .collect(),
+                total_flight_seconds: 0,
+                total_flights: 0,
+            };
+            for airplane in schema.airplanes().values() {
+                *stats
+                    .by_state
+                    .entry(airplane.state_str().to_owned())
+                    .or_insert(0) += 1;
+                stats.total_flight_seconds = stats
+                    .total_flight_seconds
+                    .saturating_add(airplane.accumulated_flight_seconds());
+                stats.total_flights += u64::from(airplane.flight_count());
+            }
+            Ok(stats)
+        }
+
+        pub fn get_scheduled_checks(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplaneQuery,
+        ) -> api::Result<Vec<ScheduledCheck>> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(schema.scheduled_checks(&query.pub_key).iter().collect())
+        }
+
+        /// Lists the systems the airplane must have repaired before its next technical check.
+        pub fn get_repairs(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplaneQuery,
+        ) -> api::Result<Vec<RepairFlag>> {
+            Ok(Self::get_airplane(state, service_name, query)?
+                .airplane
+                .required_repairs())
+        }
+
+        pub fn get_completed_routes(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplaneQuery,
+        ) -> api::Result<Vec<CompletedRoute>> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(schema.completed_routes(&query.pub_key).iter().collect())
+        }
+
+        pub fn get_flight_log(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: FlightLogQuery,
+        ) -> api::Result<Vec<FlightLogEntry>> {
+            let to = query
+                .to
+                .unwrap_or_else(|| query.from.saturating_add(DEFAULT_PER_PAGE));
+            if to < query.from {
+                Err(api::Error::BadRequest(
+                    "\"to must not be less than from\"".to_owned(),
+                ))?
+            }
+            if to - query.from > MAX_PER_PAGE {
+                Err(api::Error::BadRequest(format!(
+                    "\"at most {} entries can be requested\"",
+                    MAX_PER_PAGE
+                )))?
+            }
+
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            let flight_log = schema.flight_log(&query.pub_key);
+            Ok((query.from..to.min(flight_log.len()))
+                .filter_map(|index| flight_log.get(index))
+                .collect())
+        }
+
+        /// State changes of the whole fleet within the window, oldest first; see
+        /// `Schema::global_timeline`.
+        pub fn get_timeline(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: TimelineQuery,
+        ) -> api::Result<Vec<TimelineEntry>> {
+            if query.to < query.from {
+                Err(api::Error::BadRequest(
+                    "\"to must not be earlier than from\"".to_owned(),
+                ))?
+            }
+
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(schema.global_timeline(query.from, query.to))
+        }
+
+        pub fn get_audit_log(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplaneQuery,
+        ) -> api::Result<Vec<AuditEntry>> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(schema.audit_log(&query.pub_key).iter().collect())
+        }
+
+        /// Returns a proof of the audit entry at `index`, valid against the audit log root
+        /// stored in `audit_roots` at the snapshot height of the request.
+        pub fn get_audit_entry_proof(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AuditEntryProofQuery,
+        ) -> api::Result<ListProof<AuditEntry>> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            let audit_log = schema.audit_log(&query.pub_key);
+            if query.index >= audit_log.len() {
+                Err(api::Error::NotFound("\"Audit entry not found\"".to_owned()))?
+            }
+            Ok(audit_log.get_proof(query.index))
+        }
+
+        /// Lists certification authorities, including revoked ones.
+        pub fn get_authorities(
+            state: &ServiceApiState,
+            service_name: &str,
+            _query: (),
+        ) -> api::Result<Vec<CertificationAuthority>> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(schema.authorities().values().collect())
+        }
+
+        /// Lists the latest weather report of every airport, ordered by IATA code.
+        pub fn get_weather(
+            state: &ServiceApiState,
+            service_name: &str,
+            _query: (),
+        ) -> api::Result<Vec<WeatherReport>> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(schema.weather_reports().values().collect())
+        }
+
+        /// Lists flight plans by `plan_id`, only those of `pub_key` if it is given.
+        pub fn get_flight_plans(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: FlightPlanQuery,
+        ) -> api::Result<Vec<FlightPlan>> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            let flight_plans = schema.flight_plans();
+            Ok(match query.pub_key {
+                Some(pub_key) => schema
+                    .airplane_flight_plans(&pub_key)
+                    .iter()
+                    .filter_map(|plan_id| flight_plans.get(&plan_id))
+                    .collect(),
+                None => flight_plans.values().collect(),
+            })
+        }
+
+        pub fn get_runways(
+            state: &ServiceApiState,
+            service_name: &str,
+            _query: (),
+        ) -> api::Result<Vec<Runway>> {
+            let snapshot = state.snapshot();
+            let schema = RunwaySchema::for_service(snapshot, service_name);
+            Ok(schema.runways().values().collect())
+        }
+
+        /// Describes a committed or pooled transaction of the service instance with
+        /// `service_id` for block explorers.
+        pub fn get_transaction_description(
+            state: &ServiceApiState,
+            service_id: u16,
+            query: TransactionHashQuery,
+        ) -> api::Result<Value> {
+            let snapshot = state.snapshot();
+            let raw = CoreSchema::new(&snapshot)
+                .transactions()
+                .get(&query.hash)
+                .filter(|raw| raw.service_id() == service_id)
+                .ok_or_else(|| api::Error::NotFound("\"Transaction not found\"".to_owned()))?;
+            let tx = AirplaneTransactions::from_raw_for_service(raw, service_id)
+                .map_err(|e| api::Error::BadRequest(format!("\"{}\"", e)))?;
+
+            Ok(tx.description().unwrap_or_else(|| {
+                json!({
+                    "action": tx.type_name(),
+                })
+            }))
+        }
+
+        pub fn get_service_config(
+            state: &ServiceApiState,
+            service_name: &str,
+            _query: (),
+        ) -> api::Result<AirplaneServiceConfig> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+            Ok(schema.service_config())
+        }
+
         pub fn post_transaction(
             state: &ServiceApiState,
             query: AirplaneTransactions,
         ) -> api::Result<TransactionResponse> {
+            let transaction_type = query.type_name();
             let transaction: Box<dyn Transaction> = query.into();
             let hash = transaction.hash();
             state.sender().send(transaction.into())?;
-            Ok(TransactionResponse { tx_hash: hash })
+            Ok(TransactionResponse {
+                tx_hash: hash,
+                transaction_type,
+            })
+        }
+
+        /// `post_transaction` for a JSON body addressed to the service instance with
+        /// `service_id`, reporting malformed bodies with `TransactionDeserializationError`.
+        pub fn post_transaction_json(
+            state: &ServiceApiState,
+            service_id: u16,
+            body: Value,
+        ) -> api::Result<TransactionResponse> {
+            Self::post_transaction(state, parse_transaction(body, service_id)?)
+        }
+
+        /// Registers an airplane with the service instance with `service_id`; the request
+        /// is signed as a `TxRegisterAirplane` message carrying that ID.
+        pub fn register_airplane(
+            state: &ServiceApiState,
+            service_id: u16,
+            body: Value,
+        ) -> api::Result<TransactionResponse> {
+            let request: TxRegisterAirplaneRequest = from_request_body(body)?;
+            Self::post_transaction(
+                state,
+                AirplaneTransactions::TxRegisterAirplane(request.into())
+                    .with_service_id(service_id),
+            )
+        }
+
+        /// `v1/airplane` with the airplane mapped through `AirplaneDtoV2`.
+        pub fn get_airplane_v2(
+            state: &ServiceApiState,
+            service_name: &str,
+            query: AirplaneQuery,
+        ) -> api::Result<AirplaneResponseV2> {
+            Self::get_airplane(state, service_name, query).map(AirplaneResponseV2::from)
+        }
+
+        /// Binds `handler` to the service instance named `service_name`.
+        fn instance<Q, I>(
+            service_name: &'static str,
+            handler: fn(&ServiceApiState, &str, Q) -> api::Result<I>,
+        ) -> impl Fn(&ServiceApiState, Q) -> api::Result<I> + Clone {
+            move |state: &ServiceApiState, query: Q| handler(state, service_name, query)
+        }
+
+        /// Maps the airplanes in the response of `handler` through `AirplaneDtoV2`.
+        fn dto<Q, I, F>(
+            handler: F,
+        ) -> impl Fn(&ServiceApiState, Q) -> api::Result<I::Output> + Clone
+        where
+            F: Fn(&ServiceApiState, Q) -> api::Result<I> + Clone,
+            I: IntoV2,
+        {
+            move |state: &ServiceApiState, query: Q| handler(state, query).map(IntoV2::into_v2)
+        }
+
+        /// The `v2/` counterpart of a read endpoint: the response of `handler` with
+        /// `camelCase` keys. Query parameters keep their `v1/` names.
+        fn v2<Q, I, F>(handler: F) -> impl Fn(&ServiceApiState, Q) -> api::Result<Value> + Clone
+        where
+            F: Fn(&ServiceApiState, Q) -> api::Result<I> + Clone,
+            I: Serialize,
+        {
+            move |state: &ServiceApiState, query: Q| {
+                let response = handler(state, query)?;
+                serde_json::to_value(response)
+                    .map(camel_case_keys)
+                    .map_err(|e| api::Error::InternalError(e.into()))
+            }
         }
 
-        pub fn wire(builder: &mut ServiceApiBuilder) {
+        /// The `v2/` counterpart of a write endpoint: the body is accepted with `camelCase`
+        /// keys and the response is returned with them.
+        fn v2_mut<Q, I, F>(
+            handler: F,
+        ) -> impl Fn(&ServiceApiState, Value) -> api::Result<Value> + Clone
+        where
+            F: Fn(&ServiceApiState, Q) -> api::Result<I> + Clone,
+            Q: DeserializeOwned,
+            I: Serialize,
+        {
+            let handler = Self::v2(handler);
+            move |state: &ServiceApiState, body: Value| {
+                let query = serde_json::from_value(snake_case_keys(body))
+                    .map_err(|e| api::Error::BadRequest(format!("\"{}\"", e)))?;
+                handler(state, query)
+            }
+        }
+
+        /// Wires the endpoints of the service instance with `service_id`.
+        pub fn wire(builder: &mut ServiceApiBuilder, service_id: u16, service_name: &'static str) {
+            let post = move |state: &ServiceApiState, body: Value| {
+                Self::post_transaction_json(state, service_id, body)
+            };
+            let register = move |state: &ServiceApiState, body: Value| {
+                Self::register_airplane(state, service_id, body)
+            };
+            let describe = move |state: &ServiceApiState, query: TransactionHashQuery| {
+                Self::get_transaction_description(state, service_id, query)
+            };
+            builder
+                .public_scope()
+                .endpoint(
+                    "v1/airplane",
+                    Self::instance(service_name, Self::get_airplane),
+                )
+                .endpoint(
+                    "v1/airplane/proof",
+                    Self::instance(service_name, Self::get_airplane_proof),
+                )
+                .endpoint(
+                    "v1/airplane/allowed-actions",
+                    Self::instance(service_name, Self::get_allowed_actions),
+                )
+                .endpoint(
+                    "v1/airplane/nonce",
+                    Self::instance(service_name, Self::get_last_nonce),
+                )
+                .endpoint(
+                    "v1/airplane/audit-log",
+                    Self::instance(service_name, Self::get_audit_log),
+                )
+                .endpoint(
+                    "v1/airplane/audit-log/proof",
+                    Self::instance(service_name, Self::get_audit_entry_proof),
+                )
+                .endpoint(
+                    "v1/airplane/routes",
+                    Self::instance(service_name, Self::get_completed_routes),
+                )
+                .endpoint(
+                    "v1/airplane/repairs",
+                    Self::instance(service_name, Self::get_repairs),
+                )
+                .endpoint(
+                    "v1/airplane/flight-log",
+                    Self::instance(service_name, Self::get_flight_log),
+                )
+                .endpoint(
+                    "v1/airplane/scheduled-checks",
+                    Self::instance(service_name, Self::get_scheduled_checks),
+                )
+                .endpoint(
+                    "v1/airplane/events",
+                    Self::instance(service_name, Self::get_airplane_events),
+                )
+                .endpoint(
+                    "v1/airplane/maintenance-records",
+                    Self::instance(service_name, Self::get_maintenance_records),
+                )
+                .endpoint(
+                    "v1/airplane/incidents",
+                    Self::instance(service_name, Self::get_incidents),
+                )
+                .endpoint(
+                    "v1/airplanes",
+                    Self::instance(service_name, Self::get_all_airplanes),
+                )
+                .endpoint(
+                    "v1/airplanes/by-state",
+                    Self::instance(service_name, Self::get_airplanes_by_state),
+                )
+                .endpoint(
+                    "v1/airplanes/decommissioned",
+                    Self::instance(service_name, Self::get_decommissioned_airplanes),
+                )
+                .endpoint(
+                    "v1/airplanes/inactive",
+                    Self::instance(service_name, Self::get_inactive_airplanes),
+                )
+                .endpoint(
+                    "v1/airplanes/by-type",
+                    Self::instance(service_name, Self::get_airplanes_by_type),
+                )
+                .endpoint(
+                    "v1/airplanes/ready",
+                    Self::instance(service_name, Self::get_ready_airplanes),
+                )
+                .endpoint(
+                    "v1/airplanes/stats",
+                    Self::instance(service_name, Self::get_stats),
+                )
+                .endpoint(
+                    "v1/airplanes/check-queue",
+                    Self::instance(service_name, Self::get_check_queue),
+                )
+                .endpoint(
+                    "v1/airplanes/timeline",
+                    Self::instance(service_name, Self::get_timeline),
+                )
+                .endpoint(
+                    "v1/airplanes/search",
+                    Self::instance(service_name, Self::search_airplane_by_name),
+                )
+                .endpoint(
+                    "v1/airplanes/export",
+                    Self::instance(service_name, Self::export_snapshot),
+                )
+                .endpoint(
+                    "v1/service/config",
+                    Self::instance(service_name, Self::get_service_config),
+                )
+                .endpoint(
+                    "v1/runways",
+                    Self::instance(service_name, Self::get_runways),
+                )
+                .endpoint(
+                    "v1/authorities",
+                    Self::instance(service_name, Self::get_authorities),
+                )
+                .endpoint(
+                    "v1/weather",
+                    Self::instance(service_name, Self::get_weather),
+                )
+                .endpoint(
+                    "v1/flight-plans",
+                    Self::instance(service_name, Self::get_flight_plans),
+                )
+                .endpoint("v1/transactions/description", describe)
+                .endpoint_mut(
+                    "v1/airplanes/batch-query",
+                    Self::instance(service_name, Self::get_airplanes_batch),
+                )
+                .endpoint_mut("v1/transactions", post)
+                .endpoint_mut("v1/airplanes/register", register)
+                .endpoint_mut("v1/airplanes/batch-register", post)
+                .endpoint_mut("v1/airplanes/start-tech-check", post)
+                .endpoint_mut("v1/airplanes/end-tech-check", post)
+                .endpoint_mut("v1/airplanes/start-flying", post)
+                .endpoint_mut("v1/airplanes/end-flying", post)
+                .endpoint_mut("v1/airplanes/start-maintenance", post)
+                .endpoint_mut("v1/airplanes/end-maintenance", post)
+                .endpoint_mut("v1/airplanes/transfer", post)
+                .endpoint_mut("v1/airplanes/decommission", post)
+                .endpoint_mut("v1/airplanes/rename", post)
+                .endpoint_mut("v1/airplanes/emergency", post)
+                .endpoint_mut("v1/airplanes/clear-emergency", post)
+                .endpoint_mut("v1/airplanes/cancel-flight", post)
+                .endpoint_mut("v1/airplanes/refuel", post)
+                .endpoint_mut("v1/airplanes/update-heating-time", post)
+                .endpoint_mut("v1/airplanes/record-route", post)
+                .endpoint_mut("v1/airplanes/schedule-tech-check", post)
+                .endpoint_mut("v1/runways/assign", post)
+                .endpoint_mut("v1/runways/release", post)
+                .endpoint_mut("v1/airplanes/assign-operator", post)
+                .endpoint_mut("v1/airplanes/unassign-operator", post)
+                .endpoint_mut("v1/airplanes/ground", post)
+                .endpoint_mut("v1/airplanes/unground", post)
+                .endpoint_mut("v1/airplanes/extend-tech-check", post)
+                .endpoint_mut("v1/airplanes/report-incident", post)
+                .endpoint_mut("v1/airplanes/assign-priority", post)
+                .endpoint_mut("v1/airplanes/load-cargo", post)
+                .endpoint_mut("v1/airplanes/unload-cargo", post)
+                .endpoint_mut("v1/airplanes/set-metadata", post)
+                .endpoint_mut("v1/airplanes/remove-metadata", post)
+                .endpoint_mut("v1/airplanes/request-tech-check", post)
+                .endpoint_mut("v1/airplanes/approve-tech-check", post)
+                .endpoint_mut("v1/airplanes/deny-tech-check", post)
+                .endpoint_mut("v1/airplanes/set-passenger-count", post)
+                .endpoint_mut("v1/airplanes/renew-certificate", post)
+                .endpoint_mut("v1/authorities/register", post)
+                .endpoint_mut("v1/authorities/revoke", post)
+                .endpoint_mut("v1/airplanes/mark-repair", post)
+                .endpoint_mut("v1/airplanes/clear-repair", post)
+                .endpoint_mut("v1/airplanes/weather", post)
+                .endpoint_mut("v1/airplanes/set-home-airport", post)
+                .endpoint_mut("v1/flight-plans", post)
+                .endpoint_mut("v1/airplanes/renew-registration", post)
+                .endpoint_mut("v1/airplanes/deactivate", post)
+                .endpoint_mut("v1/airplanes/reactivate", post)
+                .endpoint_mut("v1/operators/quota", post);
             builder
                 .public_scope()
-                .endpoint("v1/airplane", Self::get_airplane)
-                .endpoint_mut("v1/airplanes/register", Self::post_transaction)
-                .endpoint_mut("v1/airplanes/start-tech-check", Self::post_transaction)
-                .endpoint_mut("v1/airplanes/end-tech-check", Self::post_transaction)
-                .endpoint_mut("v1/airplanes/start-flying", Self::post_transaction)
-                .endpoint_mut("v1/airplanes/end-flying", Self::post_transaction);
+                .endpoint(
+                    "v2/airplane",
+                    Self::v2(Self::instance(service_name, Self::get_airplane_v2)),
+                )
+                .endpoint(
+                    "v2/airplane/proof",
+                    Self::v2(Self::instance(service_name, Self::get_airplane_proof)),
+                )
+                .endpoint(
+                    "v2/airplane/allowed-actions",
+                    Self::v2(Self::instance(service_name, Self::get_allowed_actions)),
+                )
+                .endpoint(
+                    "v2/airplane/nonce",
+                    Self::v2(Self::instance(service_name, Self::get_last_nonce)),
+                )
+                .endpoint(
+                    "v2/airplane/audit-log",
+                    Self::v2(Self::instance(service_name, Self::get_audit_log)),
+                )
+                .endpoint(
+                    "v2/airplane/audit-log/proof",
+                    Self::v2(Self::instance(service_name, Self::get_audit_entry_proof)),
+                )
+                .endpoint(
+                    "v2/airplane/routes",
+                    Self::v2(Self::instance(service_name, Self::get_completed_routes)),
+                )
+                .endpoint(
+                    "v2/airplane/repairs",
+                    Self::v2(Self::instance(service_name, Self::get_repairs)),
+                )
+                .endpoint(
+                    "v2/airplane/flight-log",
+                    Self::v2(Self::instance(service_name, Self::get_flight_log)),
+                )
+                .endpoint(
+                    "v2/airplane/scheduled-checks",
+                    Self::v2(Self::instance(service_name, Self::get_scheduled_checks)),
+                )
+                .endpoint(
+                    "v2/airplane/events",
+                    Self::v2(Self::instance(service_name, Self::get_airplane_events)),
+                )
+                .endpoint(
+                    "v2/airplane/maintenance-records",
+                    Self::v2(Self::instance(service_name, Self::get_maintenance_records)),
+                )
+                .endpoint(
+                    "v2/airplane/incidents",
+                    Self::v2(Self::instance(service_name, Self::get_incidents)),
+                )
+                .endpoint(
+                    "v2/airplanes",
+                    Self::v2(Self::dto(Self::instance(
+                        service_name,
+                        Self::get_all_airplanes,
+                    ))),
+                )
+                .endpoint(
+                    "v2/airplanes/by-state",
+                    Self::v2(Self::dto(Self::instance(
+                        service_name,
+                        Self::get_airplanes_by_state,
+                    ))),
+                )
+                .endpoint(
+                    "v2/airplanes/decommissioned",
+                    Self::v2(Self::dto(Self::instance(
+                        service_name,
+                        Self::get_decommissioned_airplanes,
+                    ))),
+                )
+                .endpoint(
+                    "v2/airplanes/inactive",
+                    Self::v2(Self::dto(Self::instance(
+                        service_name,
+                        Self::get_inactive_airplanes,
+                    ))),
+                )
+                .endpoint(
+                    "v2/airplanes/by-type",
+                    Self::v2(Self::dto(Self::instance(
+                        service_name,
+                        Self::get_airplanes_by_type,
+                    ))),
+                )
+                .endpoint(
+                    "v2/airplanes/ready",
+                    Self::v2(Self::dto(Self::instance(
+                        service_name,
+                        Self::get_ready_airplanes,
+                    ))),
+                )
+                .endpoint(
+                    "v2/airplanes/stats",
+                    Self::v2(Self::instance(service_name, Self::get_stats)),
+                )
+                .endpoint(
+                    "v2/airplanes/check-queue",
+                    Self::v2(Self::instance(service_name, Self::get_check_queue)),
+                )
+                .endpoint(
+                    "v2/airplanes/timeline",
+                    Self::v2(Self::instance(service_name, Self::get_timeline)),
+                )
+                .endpoint(
+                    "v2/airplanes/search",
+                    Self::v2(Self::dto(Self::instance(
+                        service_name,
+                        Self::search_airplane_by_name,
+                    ))),
+                )
+                .endpoint(
+                    "v2/airplanes/export",
+                    Self::v2(Self::dto(Self::instance(
+                        service_name,
+                        Self::export_snapshot,
+                    ))),
+                )
+                .endpoint(
+                    "v2/service/config",
+                    Self::v2(Self::instance(service_name, Self::get_service_config)),
+                )
+                .endpoint(
+                    "v2/runways",
+                    Self::v2(Self::instance(service_name, Self::get_runways)),
+                )
+                .endpoint(
+                    "v2/authorities",
+                    Self::v2(Self::instance(service_name, Self::get_authorities)),
+                )
+                .endpoint(
+                    "v2/weather",
+                    Self::v2(Self::instance(service_name, Self::get_weather)),
+                )
+                .endpoint(
+                    "v2/flight-plans",
+                    Self::v2(Self::instance(service_name, Self::get_flight_plans)),
+                )
+                .endpoint("v2/transactions/description", Self::v2(describe))
+                .endpoint_mut(
+                    "v2/airplanes/batch-query",
+                    Self::v2_mut(Self::dto(Self::instance(
+                        service_name,
+                        Self::get_airplanes_batch,
+                    ))),
+                )
+                .endpoint_mut("v2/transactions", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/register", Self::v2_mut(register))
+                .endpoint_mut("v2/airplanes/batch-register", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/start-tech-check", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/end-tech-check", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/start-flying", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/end-flying", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/start-maintenance", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/end-maintenance", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/transfer", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/decommission", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/rename", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/emergency", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/clear-emergency", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/cancel-flight", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/refuel", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/update-heating-time", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/record-route", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/schedule-tech-check", Self::v2_mut(post))
+                .endpoint_mut("v2/runways/assign", Self::v2_mut(post))
+                .endpoint_mut("v2/runways/release", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/assign-operator", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/unassign-operator", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/ground", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/unground", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/extend-tech-check", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/report-incident", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/assign-priority", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/load-cargo", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/unload-cargo", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/set-metadata", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/remove-metadata", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/request-tech-check", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/approve-tech-check", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/deny-tech-check", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/set-passenger-count", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/renew-certificate", Self::v2_mut(post))
+                .endpoint_mut("v2/authorities/register", Self::v2_mut(post))
+                .endpoint_mut("v2/authorities/revoke", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/mark-repair", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/clear-repair", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/weather", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/set-home-airport", Self::v2_mut(post))
+                .endpoint_mut("v2/flight-plans", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/renew-registration", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/deactivate", Self::v2_mut(post))
+                .endpoint_mut("v2/airplanes/reactivate", Self::v2_mut(post))
+                .endpoint_mut("v2/operators/quota", Self::v2_mut(post));
+        }
+    }
+
+    /// Operator-only endpoints. Exonum serves the private scope on the private API address,
+    /// which should only be reachable from localhost.
+    #[derive(Debug, Clone)]
+    pub struct PrivateAirplaneApi;
+
+    impl PrivateAirplaneApi {
+        #[cfg(feature = "debug")]
+        pub fn get_debug_dump(
+            state: &ServiceApiState,
+            service_name: &str,
+            _query: (),
+        ) -> api::Result<Value> {
+            let snapshot = state.snapshot();
+            Ok(Schema::for_service(snapshot, service_name).dump_to_json())
+        }
+
+        /// Lists problems with stored airplanes; see `Schema::validate_integrity`.
+        pub fn validate_integrity(
+            state: &ServiceApiState,
+            service_name: &str,
+            _query: (),
+        ) -> api::Result<Vec<IntegrityViolation>> {
+            let snapshot = state.snapshot();
+            Ok(Schema::for_service(snapshot, service_name).validate_integrity())
+        }
+
+        pub fn get_internal_stats(
+            state: &ServiceApiState,
+            service_name: &str,
+            _query: (),
+        ) -> api::Result<InternalStats> {
+            let snapshot = state.snapshot();
+            let schema = Schema::for_service(snapshot, service_name);
+
+            let mut stats = InternalStats {
+                failed_technical_checks_total: 0,
+                total_decommissioned: 0,
+                avg_heating_seconds: 0.0,
+            };
+            let mut heating_seconds_total = 0u64;
+            let mut heating_records = 0u64;
+            for airplane in schema.airplanes().values() {
+                if airplane.state_number() == u8::from(AirplaneState::Decommissioned) {
+                    stats.total_decommissioned += 1;
+                }
+                for record in schema.maintenance_records(airplane.pub_key()).iter() {
+                    if record.passed() {
+                        heating_seconds_total += u64::from(record.engine_heating_time_seconds());
+                        heating_records += 1;
+                    } else {
+                        stats.failed_technical_checks_total += 1;
+                    }
+                }
+            }
+            if heating_records > 0 {
+                stats.avg_heating_seconds = heating_seconds_total as f64 / heating_records as f64;
+            }
+            Ok(stats)
+        }
+
+        pub fn update_config(
+            state: &ServiceApiState,
+            service_id: u16,
+            body: Value,
+        ) -> api::Result<TransactionResponse> {
+            match parse_transaction(body, service_id)? {
+                tx @ AirplaneTransactions::TxUpdateServiceConfig(_) => {
+                    AirplaneApi::post_transaction(state, tx)
+                }
+                tx => Err(api::Error::BadRequest(format!(
+                    "\"Expected TxUpdateServiceConfig, got {}\"",
+                    tx.type_name()
+                ))),
+            }
+        }
+
+        pub fn wire(builder: &mut ServiceApiBuilder, service_id: u16, service_name: &'static str) {
+            let update_config = move |state: &ServiceApiState, body: Value| {
+                Self::update_config(state, service_id, body)
+            };
+            builder
+                .private_scope()
+                .endpoint(
+                    "v1/admin/stats",
+                    AirplaneApi::instance(service_name, Self::get_internal_stats),
+                )
+                .endpoint(
+                    "v1/admin/validate",
+                    AirplaneApi::instance(service_name, Self::validate_integrity),
+                )
+                .endpoint_mut("v1/admin/update-config", update_config);
+            #[cfg(feature = "debug")]
+            builder.private_scope().endpoint(
+                "v1/admin/debug-dump",
+                AirplaneApi::instance(service_name, Self::get_debug_dump),
+            );
         }
     }
 
     #[derive(Debug)]
-    pub struct AirplaneService;
+    pub struct AirplaneService {
+        service_id: u16,
+
+        service_name: &'static str,
+
+        /// Keys used to sign transactions generated by the service itself; the service keys
+        /// of the node if `None`. They are deliberately not kept in the schema: every node
+        /// reads the blockchain state, so a secret key stored there could be used by anyone
+        /// to sign service transactions, and a key generated in `initialize` would differ
+        /// between nodes and break consensus on the genesis block.
+        service_keypair: Option<(PublicKey, SecretKey)>,
+
+        /// Safety authority written to the configuration at genesis.
+        admin_pub_key: Option<PublicKey>,
+
+        event_streams: EventStreams,
+    }
+
+    impl Default for AirplaneService {
+        fn default() -> Self {
+            AirplaneService {
+                service_id: SERVICE_ID,
+                service_name: SERVICE_NAME,
+                service_keypair: None,
+                admin_pub_key: None,
+                event_streams: EventStreams::default(),
+            }
+        }
+    }
+
+    impl AirplaneService {
+        /// Signs the transactions generated by the service with `service_keypair` instead of
+        /// the service keys of the node.
+        pub fn new(service_keypair: (PublicKey, SecretKey)) -> Self {
+            AirplaneService {
+                service_keypair: Some(service_keypair),
+                ..AirplaneService::default()
+            }
+        }
+
+        /// Runs the service under another ID and name, e.g. when `SERVICE_ID` is taken by
+        /// another service. Messages must then be signed with `service_id` in the header;
+        /// see `AirplaneTransactions::signed_for_service`. The schema indexes of the instance
+        /// are prefixed with `service_name`, so two instances in one blockchain keep separate
+        /// data.
+        ///
+        /// # Panics
+        ///
+        /// If `service_name` contains characters other than ASCII letters, digits and `_`,
+        /// which Exonum does not accept in index names.
+        pub fn with_service_id(mut self, service_id: u16, service_name: &'static str) -> Self {
+            assert!(
+                service_name
+                    .bytes()
+                    .all(|c| c.is_ascii_alphanumeric() || c == b'_'),
+                "Invalid service name {:?}; use ASCII letters, digits and `_`",
+                service_name
+            );
+            self.service_id = service_id;
+            self.service_name = service_name;
+            self
+        }
+
+        /// Sets the key allowed to ground and unground airplanes.
+        pub fn with_admin_pub_key(mut self, admin_pub_key: PublicKey) -> Self {
+            self.admin_pub_key = Some(admin_pub_key);
+            self
+        }
+    }
+
+    impl AirplaneService {
+        /// Transactions the service submits after a block is committed, signed with the
+        /// given service keys: `TxExpireHeating` for airplanes heating longer than
+        /// `max_heating_wait_seconds`, `TxStartScheduledCheck` for due scheduled checks
+        /// and, if a technical check slot is free, `TxStartQueuedCheck` for the next
+        /// queued airplane. Empty until the time oracle has committed a time.
+        pub fn service_transactions(
+            snapshot: &dyn Snapshot,
+            service_name: &str,
+            public_key: &PublicKey,
+            secret_key: &SecretKey,
+        ) -> Vec<AirplaneTransactions> {
+            let current_time = match TimeSchema::new(snapshot).time().get() {
+                Some(time) => time,
+                None => return Vec::new(),
+            };
+            let schema = Schema::for_service(snapshot, service_name);
+            let height = schema.height();
+            let max_wait =
+                Duration::seconds(schema.service_config().max_heating_wait_seconds() as i64);
+            let mut transactions = Vec::new();
+
+            for pub_key in schema.airplanes_in_state(AirplaneState::HeatingEngine) {
+                let is_overdue = schema.airplane(&pub_key).is_some_and(|airplane| {
+                    current_time - airplane.engine_heating_start_time() > max_wait
+                });
+                if is_overdue {
+                    let tx = TxExpireHeating::new(&pub_key, public_key, height, 0, secret_key);
+                    transactions.push(AirplaneTransactions::TxExpireHeating(tx));
+                }
+            }
+
+            for pub_key in schema.airplanes_in_state(AirplaneState::WaitingForFlight) {
+                let is_due = schema
+                    .next_scheduled_check(&pub_key)
+                    .is_some_and(|check| check.scheduled_at() <= current_time);
+                if is_due {
+                    let tx =
+                        TxStartScheduledCheck::new(&pub_key, public_key, height, 0, secret_key);
+                    transactions.push(AirplaneTransactions::TxStartScheduledCheck(tx));
+                }
+            }
+
+            if schema.service_config().technical_check_slots().is_some()
+                && schema.has_free_check_slot()
+            {
+                if let Some(pub_key) = schema.next_queued_check() {
+                    let tx = TxStartQueuedCheck::new(&pub_key, public_key, height, 0, secret_key);
+                    transactions.push(AirplaneTransactions::TxStartQueuedCheck(tx));
+                }
+            }
+            transactions
+        }
+    }
 
     impl Service for AirplaneService {
         fn service_id(&self) -> u16 {
-            SERVICE_ID
+            self.service_id
         }
 
         fn service_name(&self) -> &'static str {
-            SERVICE_NAME
+            self.service_name
+        }
+
+        fn state_hash(&self, view: &dyn Snapshot) -> Vec<Hash> {
+            let schema = Schema::for_service(view, self.service_name);
+            schema.state_hash()
         }
 
-        fn state_hash(&self, _view: &dyn Snapshot) -> Vec<Hash> {
-            vec![]
+        fn initialize(&self, fork: &mut Fork) -> Value {
+            let config = AirplaneServiceConfig::with_admin(self.admin_pub_key);
+            let mut schema = Schema::for_service(fork, self.service_name);
+            schema.register_service_id(self.service_id);
+            schema.schema_version_entry_mut().set(SCHEMA_VERSION);
+            schema.service_config_entry_mut().set(config.clone());
+            serde_json::to_value(config).expect("Unable to serialize service configuration")
         }
 
         fn tx_from_raw(
             &self,
             raw: RawTransaction,
         ) -> Result<Box<dyn Transaction>, StreamStructError> {
-            let tx = AirplaneTransactions::tx_from_raw(raw)?;
+            let tx = AirplaneTransactions::from_raw_for_service(raw, self.service_id).map_err(
+                |error| match error {
+                    StreamStructError::IncorrectMessageType { message_type } => {
+                        StreamStructError::Basic(
+                            format!(
+                            "Unknown transaction type {} for service {}; valid types are 0 to {}",
+                            message_type, self.service_id, MAX_MESSAGE_ID
+                        )
+                            .into(),
+                        )
+                    }
+                    error => error,
+                },
+            )?;
             Ok(tx.into())
         }
 
         fn wire_api(&self, builder: &mut ServiceApiBuilder) {
-            AirplaneApi::wire(builder);
+            AirplaneApi::wire(builder, self.service_id, self.service_name);
+            AirplaneApi::wire_event_stream(builder, self.event_streams.clone(), self.service_name);
+            AirplaneApi::wire_health_check(builder, self.service_name);
+            AirplaneApi::wire_operator_quota(builder, self.service_name);
+            PrivateAirplaneApi::wire(builder, self.service_id, self.service_name);
+        }
+
+        /// Sends committed events to `v1/airplane/stream` subscribers. On validators, also
+        /// resets airplanes that have been heating longer than `max_heating_wait_seconds`,
+        /// starts technical checks whose scheduled time has come and, if a technical check
+        /// slot is free, starts the check of the next queued airplane.
+        fn after_commit(&self, context: &ServiceContext) {
+            self.event_streams
+                .publish(context.snapshot(), self.service_name);
+
+            if context.validator_id().is_none() {
+                return;
+            }
+
+            let (public_key, secret_key) = match self.service_keypair {
+                Some((ref public_key, ref secret_key)) => (public_key, secret_key),
+                None => (context.public_key(), context.secret_key()),
+            };
+            for mut tx in Self::service_transactions(
+                context.snapshot(),
+                self.service_name,
+                public_key,
+                secret_key,
+            ) {
+                if self.service_id != SERVICE_ID {
+                    tx = tx.signed_for_service(self.service_id, secret_key);
+                }
+                let type_name = tx.type_name();
+                let tx: Box<dyn Transaction> = tx.into();
+                let hash = tx.hash();
+                if let Err(e) = context.transaction_sender().send(tx) {
+                    error!("Unable to submit {} {:?}: {}", type_name, hash, e);
+                }
+            }
         }
     }
 }
@@ -1,11 +1,11 @@
+extern crate airplane_service;
 extern crate exonum;
-extern crate test;
 
-use exonum::blockchain::{GenesisConfig, ValidatorKeys};
+use exonum::blockchain::{GenesisConfig, Service, ValidatorKeys};
 use exonum::node::{Node, NodeApiConfig, NodeConfig};
 use exonum::storage::MemoryDB;
 
-use test::service::AirplaneService;
+use airplane_service::service::AirplaneService;
 
 fn node_config() -> NodeConfig {
     let (consensus_public_key, consensus_secret_key) = exonum::crypto::gen_keypair();
@@ -18,8 +18,12 @@ fn node_config() -> NodeConfig {
     let genesis = GenesisConfig::new(vec![validator_keys].into_iter());
 
     let api_address = "0.0.0.0:8000".parse().unwrap();
+    // The private API serves internal statistics and configuration updates, so it
+    // only listens on the loopback interface.
+    let private_api_address = "127.0.0.1:8010".parse().unwrap();
     let api_cfg = NodeApiConfig {
         public_api_address: Some(api_address),
+        private_api_address: Some(private_api_address),
         ..Default::default()
     };
 
@@ -32,7 +36,7 @@ fn node_config() -> NodeConfig {
         consensus_public_key,
         consensus_secret_key,
         genesis,
-        external_address: Some(peer_address),
+        external_address: peer_address,
         network: Default::default(),
         connect_list: Default::default(),
         api: api_cfg,
@@ -43,12 +47,15 @@ fn node_config() -> NodeConfig {
 }
 
 fn main() {
-    let node = Node::new(
-        MemoryDB::new(),
-        vec![Box::new(AirplaneService)],
-        node_config(),
-        None,
-    );
+    let node_config = node_config();
+
+    // Two independent fleets; each instance keeps its own indexes and serves its API
+    // under `api/services/<service name>`.
+    let services: Vec<Box<dyn Service>> = vec![
+        Box::new(AirplaneService::default()),
+        Box::new(AirplaneService::default().with_service_id(2, "cargo_airplane")),
+    ];
+    let node = Node::new(MemoryDB::new(), services, node_config, None);
 
     println!("Airplane service started.");
     node.run().unwrap();
@@ -0,0 +1,42 @@
+//! `Schema::dump_to_json`, built only with `--features debug`.
+
+extern crate airplane_service;
+extern crate exonum;
+
+use exonum::crypto::gen_keypair;
+use exonum::storage::{Database, MemoryDB};
+
+use airplane_service::schema::{AirplaneServiceConfig, AirplaneState, Schema};
+
+#[test]
+fn dump_contains_airplanes_config_and_event_counts() {
+    let mut fork = MemoryDB::new().fork();
+    let (first_pk, _) = gen_keypair();
+    let (second_pk, _) = gen_keypair();
+    {
+        let mut schema = Schema::new(&mut fork);
+        schema
+            .service_config_entry_mut()
+            .set(AirplaneServiceConfig::default());
+        schema.seed_test_airplane(&first_pk, "Boeing", AirplaneState::WaitingForFlight);
+        schema.seed_test_airplane(&second_pk, "Airbus", AirplaneState::Flying);
+    }
+
+    let dump = Schema::new(&fork).dump_to_json();
+
+    assert_eq!(dump["airplane_count"], 2);
+    let airplanes = dump["airplanes"].as_array().unwrap();
+    assert_eq!(airplanes.len(), 2);
+    let mut names: Vec<&str> = airplanes
+        .iter()
+        .map(|airplane| airplane["name"].as_str().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["Airbus", "Boeing"]);
+    assert_eq!(
+        dump["config"]["min_heating_seconds"],
+        AirplaneServiceConfig::default().min_heating_seconds()
+    );
+    assert_eq!(dump["event_counts"][first_pk.to_hex()], 0);
+    assert_eq!(dump["event_counts"][second_pk.to_hex()], 0);
+}
@@ -0,0 +1,5419 @@
+//! End-to-end checks of the airplane service.
+//!
+//! `exonum-testkit` is not among the dependencies, so transactions are executed
+//! directly against a `MemoryDB` fork with the time oracle entry set by hand.
+
+extern crate airplane_service;
+extern crate chrono;
+extern crate exonum;
+extern crate exonum_time;
+extern crate futures;
+#[macro_use]
+extern crate serde_json;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use exonum::api::{self, ServiceApiState};
+use exonum::blockchain::{
+    Blockchain, ConsensusConfig, ExecutionError, ExecutionResult, Schema as CoreSchema, Service,
+    StoredConfiguration, Transaction, ValidatorKeys,
+};
+use exonum::crypto::{gen_keypair, CryptoHash, Hash, PublicKey, SecretKey};
+use exonum::helpers::Height;
+use exonum::messages::Message;
+use exonum::node::ApiSender;
+use exonum::storage::{Database, Fork, ListIndex, MapIndex, MapProof, MemoryDB};
+use exonum_time::schema::TimeSchema;
+use futures::sync::mpsc;
+use futures::Stream;
+use std::convert::TryFrom;
+
+use serde_json::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use airplane_service::schema::{Airplane, AirplaneBuilder, AirplaneEventType, AirplaneRegistration, AirplaneServiceConfig, AirplaneState, AirplaneType, AirplaneV1, DEFAULT_CARGO_MIN_HEATING_SECONDS, DEFAULT_COMMERCIAL_MIN_HEATING_SECONDS, DEFAULT_COOLING_PERIOD_SECONDS, DEFAULT_MAX_CONCURRENT_TECHNICAL_CHECKS, DEFAULT_MAX_HEATING_SECONDS, DEFAULT_MAX_HEATING_WAIT_SECONDS, DEFAULT_MAX_TX_PER_BLOCK_PER_KEY, DEFAULT_MILITARY_MIN_HEATING_SECONDS, DEFAULT_MIN_FUEL_PERCENT_FOR_FLIGHT, DEFAULT_MIN_HEATING_SECONDS, DEFAULT_MIN_VISIBILITY_METERS, DEFAULT_PRIVATE_MIN_HEATING_SECONDS, DEFAULT_REGISTRATION_VALIDITY_DAYS, DEFAULT_TX_EXPIRY_BLOCKS, EPOCH, MAX_AIRPLANE_NAME_LEN, MAX_EMERGENCY_REASON_LEN, MAX_INCIDENT_SEVERITY, MAX_METADATA_FIELDS, MAX_METADATA_KEY_LEN, MAX_PRIORITY, MigrationError, RepairFlag, RunwaySchema, SCHEMA_VERSION, Schema};
+use airplane_service::service::{AirplaneApi, AirplaneNameQuery, AirplaneQuery, AirplaneService, AirplanesQuery, AuditEntryProofQuery, DEFAULT_PER_PAGE, EventStreams, HealthStatus, MAX_BATCH_QUERY_SIZE, MAX_PER_PAGE, PaginationParams, PrivateAirplaneApi, SERVICE_ID, SERVICE_NAME, parse_transaction};
+use airplane_service::transactions::{AirplaneTransactions, Error, TxApproveCheckStart, TxAssignOperator, TxAssignPriority, TxAssignRunway, TxBatchRegisterAirplanes, TxCancelFlight, TxClearEmergency, TxClearRepair, TxDeactivateAirplane, TxDecommissionAirplane, TxDenyCheckRequest, TxEmergencyLanding, TxEndFlying, TxEndMaintenance, TxEndTechnicalCheck, TxExpireHeating, TxGroundAirplane, TxLoadCargo, TxMarkRepairRequired, TxPlanFlight, TxReactivateAirplane, TxRecordFlightRoute, TxRecordWeatherConditions, TxRefuelAirplane, TxRegisterAirplane, TxRegisterAuthority, TxReleaseRunway, TxRemoveMetadataField, TxRenewAirworthinessCertificate, TxRenewRegistration, TxReportIncident, TxRequestMaintenanceExtension, TxRequestTechnicalCheck, TxRevokeAuthority, TxScheduleTechnicalCheck, TxSetHomeAirport, TxSetMetadataField, TxSetOperatorQuota, TxSetPassengerCount, TxStartFlying, TxStartMaintenance, TxStartQueuedCheck, TxStartScheduledCheck, TxStartTechnicalCheck, TxTransferOwnership, TxUnassignOperator, TxUngroundAirplane, TxUnloadCargo, TxUpdateAirplaneName, TxUpdateEngineHeatingTime, TxUpdateServiceConfig};
+
+const HEATING_SECONDS: u32 = 60;
+
+/// `created_at_height` of every transaction; the test chain has no committed blocks.
+const CREATED_AT_HEIGHT: u64 = 0;
+
+static NONCE: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns a nonce greater than every nonce returned before.
+fn next_nonce() -> u64 {
+    NONCE.fetch_add(1, Ordering::SeqCst) as u64 + 1
+}
+
+struct TestChain {
+    fork: Fork,
+    now: DateTime<Utc>,
+}
+
+impl TestChain {
+    fn new() -> Self {
+        let mut chain = TestChain {
+            fork: MemoryDB::new().fork(),
+            now: Utc.timestamp_opt(1_500_000_000, 0).unwrap(),
+        };
+        chain.set_time(chain.now);
+        chain
+    }
+
+    /// Sets the time reported by the time oracle.
+    fn set_time(&mut self, time: DateTime<Utc>) {
+        self.now = time;
+        TimeSchema::new(&mut self.fork).time_mut().set(time);
+    }
+
+    fn set_config(&mut self, config: AirplaneServiceConfig) {
+        Schema::new(&mut self.fork)
+            .service_config_entry_mut()
+            .set(config);
+    }
+
+    /// Stores a configuration with `admin_pub_key` as the safety authority.
+    fn set_admin(&mut self, admin_pub_key: &PublicKey) {
+        self.set_config(AirplaneServiceConfig::with_admin(Some(*admin_pub_key)));
+    }
+
+    /// Stores the genesis configuration with a validator for each of `service_keys`.
+    fn set_validators(&mut self, service_keys: &[PublicKey]) {
+        let validator_keys = service_keys
+            .iter()
+            .map(|service_key| ValidatorKeys {
+                consensus_key: gen_keypair().0,
+                service_key: *service_key,
+            })
+            .collect::<Vec<_>>();
+        CoreSchema::new(&mut self.fork).commit_configuration(StoredConfiguration {
+            previous_cfg_hash: Hash::zero(),
+            actual_from: Height(0),
+            majority_count: None,
+            validator_keys,
+            consensus: ConsensusConfig::default(),
+            services: Default::default(),
+        });
+    }
+
+    /// Moves the oracle time forward by `seconds`.
+    fn advance_time(&mut self, seconds: i64) {
+        let time = self.now + Duration::seconds(seconds);
+        self.set_time(time);
+    }
+
+    /// Appends `count` placeholder blocks to the core schema, raising the height seen
+    /// by transactions.
+    fn advance_blocks(&mut self, count: u64) {
+        let mut block_hashes: ListIndex<&mut Fork, Hash> =
+            ListIndex::new("core.block_hashes_by_height", &mut self.fork);
+        for _ in 0..count {
+            block_hashes.push(Hash::zero());
+        }
+    }
+
+    /// Verifies and executes `tx`, keeping its changes only if it succeeds.
+    fn execute<T: Transaction>(&mut self, tx: T) -> ExecutionResult {
+        assert!(tx.verify());
+        self.fork.checkpoint();
+        let result = tx.execute(&mut self.fork);
+        match result {
+            Ok(()) => self.fork.commit(),
+            Err(_) => self.fork.rollback(),
+        }
+        result
+    }
+
+    /// Merges the chain into a blockchain and returns the state read by the
+    /// `AirplaneApi` handlers. Transactions submitted through it are not delivered.
+    fn into_api_state(self) -> ServiceApiState {
+        let (public_key, secret_key) = gen_keypair();
+        let mut blockchain = Blockchain::new(
+            MemoryDB::new(),
+            vec![Box::new(AirplaneService::default())],
+            public_key,
+            secret_key,
+            ApiSender::new(mpsc::channel(1).0),
+        );
+        blockchain.merge(self.fork.into_patch()).unwrap();
+        ServiceApiState::new(blockchain)
+    }
+
+    /// Stores an airworthiness certificate valid for a year, as a certification
+    /// authority would with `TxRenewAirworthinessCertificate`.
+    fn certify(&mut self, pub_key: &PublicKey) {
+        let expiry = self.now + Duration::days(365);
+        let mut schema = Schema::new(&mut self.fork);
+        let airplane = schema.airplane(pub_key).expect("airplane is registered");
+        schema.airplanes_mut().put(
+            pub_key,
+            Airplane::builder_from(&airplane)
+                .airworthiness_cert_expiry(expiry)
+                .build(),
+        );
+    }
+
+    /// Current state of a registered airplane.
+    fn state(&self, pub_key: &PublicKey) -> AirplaneState {
+        let airplane = Schema::new(&self.fork)
+            .airplane(pub_key)
+            .expect("airplane is registered");
+        AirplaneState::try_from(airplane.state_number()).unwrap()
+    }
+}
+
+/// Default configuration with the given fleet size limit and technical check window.
+fn config(max_fleet_size: u64, technical_check_window_seconds: u32) -> AirplaneServiceConfig {
+    AirplaneServiceConfig::new(
+        DEFAULT_MIN_HEATING_SECONDS,
+        DEFAULT_MAX_HEATING_SECONDS,
+        DEFAULT_COOLING_PERIOD_SECONDS,
+        DEFAULT_MAX_HEATING_WAIT_SECONDS,
+        DEFAULT_MIN_FUEL_PERCENT_FOR_FLIGHT,
+        &PublicKey::zero(),
+        max_fleet_size,
+        technical_check_window_seconds,
+        DEFAULT_COMMERCIAL_MIN_HEATING_SECONDS,
+        DEFAULT_CARGO_MIN_HEATING_SECONDS,
+        DEFAULT_MILITARY_MIN_HEATING_SECONDS,
+        DEFAULT_PRIVATE_MIN_HEATING_SECONDS,
+        DEFAULT_MAX_CONCURRENT_TECHNICAL_CHECKS,
+        DEFAULT_TX_EXPIRY_BLOCKS,
+        DEFAULT_MAX_TX_PER_BLOCK_PER_KEY,
+        DEFAULT_MIN_VISIBILITY_METERS,
+        DEFAULT_REGISTRATION_VALIDITY_DAYS,
+    )
+}
+
+/// Registers an airplane whose name and serial number are derived from its key.
+fn register_airplane(chain: &mut TestChain) -> (PublicKey, SecretKey) {
+    let (pk, sk) = gen_keypair();
+    let id = &pk.to_hex()[..8];
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            &format!("Airplane {}", id),
+            "Model",
+            &format!("SN{}", id),
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.certify(&pk);
+    (pk, sk)
+}
+
+/// Registers an airplane with a runway and takes it through a passed technical check.
+fn prepare_heated_airplane(chain: &mut TestChain) -> (PublicKey, SecretKey) {
+    let (pk, sk) = register_airplane(chain);
+    chain
+        .execute(TxAssignRunway::new(
+            &pk,
+            &pk,
+            "09L",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            &pk,
+            &pk,
+            true,
+            HEATING_SECONDS,
+            "All systems nominal",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::HeatingEngine);
+    (pk, sk)
+}
+
+/// Takes off with an airplane heated by `prepare_heated_airplane` and lands after
+/// `flight_seconds`.
+fn complete_flight(chain: &mut TestChain, pk: &PublicKey, sk: &SecretKey, flight_seconds: i64) {
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    chain
+        .execute(TxStartFlying::new(
+            pk,
+            pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            sk,
+        ))
+        .unwrap();
+    chain.advance_time(flight_seconds);
+    chain
+        .execute(TxEndFlying::new(
+            pk,
+            pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            sk,
+        ))
+        .unwrap();
+}
+
+/// Takes an airplane landed by `complete_flight` through runway assignment and a
+/// passed technical check back into `HeatingEngine`.
+fn prepare_next_flight(chain: &mut TestChain, pk: &PublicKey, sk: &SecretKey) {
+    chain
+        .execute(TxAssignRunway::new(
+            pk,
+            pk,
+            "09L",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            pk,
+            pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            pk,
+            pk,
+            true,
+            HEATING_SECONDS,
+            "All systems nominal",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            sk,
+        ))
+        .unwrap();
+}
+
+#[test]
+fn state_hash_covers_airplanes_and_audit_logs() {
+    let mut chain = TestChain::new();
+    let service = AirplaneService::new(gen_keypair());
+    let empty = service.state_hash(&chain.fork);
+
+    register_airplane(&mut chain);
+    let registered = service.state_hash(&chain.fork);
+    let schema = Schema::new(&chain.fork);
+    assert_eq!(
+        registered,
+        vec![
+            schema.airplanes().merkle_root(),
+            schema.audit_roots().merkle_root(),
+        ]
+    );
+    assert_ne!(registered[0], empty[0]);
+    assert_ne!(registered[1], empty[1]);
+}
+
+/// Flies a heated airplane and moves it into maintenance instead of ending the flight.
+fn fly_into_maintenance(chain: &mut TestChain) -> (PublicKey, SecretKey) {
+    let (pk, sk) = prepare_heated_airplane(chain);
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.advance_time(3_600);
+    chain
+        .execute(TxStartMaintenance::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::Maintenance);
+    (pk, sk)
+}
+
+#[test]
+fn passed_maintenance_returns_airplane_to_service() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = fly_into_maintenance(&mut chain);
+    chain
+        .execute(TxEndMaintenance::new(
+            &pk,
+            &pk,
+            true,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+
+    let result = chain.execute(TxStartMaintenance::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::InvalidStateTransition {
+            found: AirplaneState::WaitingForFlight,
+            required: &[AirplaneState::Flying],
+        }))
+    );
+}
+
+#[test]
+fn failed_maintenance_grounds_airplane() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = fly_into_maintenance(&mut chain);
+    chain
+        .execute(TxEndMaintenance::new(
+            &pk,
+            &pk,
+            false,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::Grounded);
+}
+
+#[test]
+fn completed_flights_are_counted_and_timed() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    let registered = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(registered.flight_count(), 0);
+    assert_eq!(registered.accumulated_flight_seconds(), 0);
+
+    complete_flight(&mut chain, &pk, &sk, 3_600);
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.flight_count(), 1);
+    assert_eq!(airplane.accumulated_flight_seconds(), 3_600);
+
+    let response = serde_json::to_value(&airplane).unwrap();
+    assert_eq!(response["flight_count"], 1);
+    assert_eq!(response["accumulated_flight_seconds"], "3600");
+}
+
+#[test]
+fn heating_time_above_u16_range_is_enforced() {
+    let heating_seconds = u32::from(u16::MAX) + 1;
+    let mut chain = TestChain::new();
+    let (pk, sk) = register_airplane(&mut chain);
+    chain
+        .execute(TxAssignRunway::new(
+            &pk,
+            &pk,
+            "09L",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            &pk,
+            &pk,
+            true,
+            heating_seconds,
+            "Arctic preheating",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(
+        Schema::new(&chain.fork)
+            .airplane(&pk)
+            .unwrap()
+            .engine_heating_time_seconds(),
+        heating_seconds
+    );
+
+    chain.advance_time(i64::from(u16::MAX));
+    let result = chain.execute(TxStartFlying::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::EngineIsNotHeated)));
+
+    chain.advance_time(1);
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::Flying);
+}
+
+#[test]
+fn state_index_follows_transitions() {
+    let counts = |chain: &TestChain| {
+        let schema = Schema::new(&chain.fork);
+        AirplaneState::all()
+            .iter()
+            .map(|&state| schema.airplanes_in_state(state).len())
+            .collect::<Vec<_>>()
+    };
+    let only_in = |state: AirplaneState| {
+        AirplaneState::all()
+            .iter()
+            .map(|&other| if other == state { 1 } else { 0 })
+            .collect::<Vec<_>>()
+    };
+
+    let mut chain = TestChain::new();
+    assert_eq!(counts(&chain), vec![0; AirplaneState::all().len()]);
+
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    assert_eq!(counts(&chain), only_in(AirplaneState::HeatingEngine));
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(counts(&chain), only_in(AirplaneState::Flying));
+    assert_eq!(
+        Schema::new(&chain.fork).airplanes_in_state(AirplaneState::Flying),
+        vec![pk]
+    );
+    chain
+        .execute(TxEndFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(counts(&chain), only_in(AirplaneState::WaitingForFlight));
+}
+
+fn rename(
+    chain: &mut TestChain,
+    pk: &PublicKey,
+    sk: &SecretKey,
+    new_name: &str,
+) -> ExecutionResult {
+    chain.execute(TxUpdateAirplaneName::new(
+        pk,
+        pk,
+        new_name,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        sk,
+    ))
+}
+
+#[test]
+fn renaming_keeps_names_unique() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = register_airplane(&mut chain);
+    let (other_pk, other_sk) = register_airplane(&mut chain);
+    let old_name = Schema::new(&chain.fork)
+        .airplane(&pk)
+        .unwrap()
+        .name()
+        .to_owned();
+
+    rename(&mut chain, &pk, &sk, "Spirit of Riga").unwrap();
+    {
+        let schema = Schema::new(&chain.fork);
+        assert_eq!(schema.airplane(&pk).unwrap().name(), "Spirit of Riga");
+        assert_eq!(
+            schema.airplane_names().get(&"spirit of riga".to_owned()),
+            Some(pk)
+        );
+        assert_eq!(schema.airplane_names().get(&old_name.to_lowercase()), None);
+    }
+
+    // Names are compared after trimming and lowercasing.
+    assert_eq!(
+        rename(&mut chain, &other_pk, &other_sk, "  SPIRIT OF RIGA "),
+        Err(ExecutionError::from(Error::NameAlreadyTaken))
+    );
+    // An airplane may change the case of its own name, and the freed name is available.
+    rename(&mut chain, &pk, &sk, "SPIRIT OF RIGA").unwrap();
+    rename(&mut chain, &other_pk, &other_sk, &old_name).unwrap();
+
+    let (new_pk, new_sk) = gen_keypair();
+    let result = chain.execute(TxRegisterAirplane::new(
+        &new_pk,
+        "spirit of riga",
+        "Model",
+        "SN777777",
+        AirplaneType::Commercial as u8,
+        0,
+        0,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &new_sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::NameAlreadyTaken)));
+}
+
+#[test]
+fn emergency_landing_checks_reason_and_counts_emergencies() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    let too_long = "x".repeat(MAX_EMERGENCY_REASON_LEN + 1);
+    for reason in &["", too_long.as_str()] {
+        let result = chain.execute(TxEmergencyLanding::new(
+            &pk,
+            &pk,
+            reason,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ));
+        assert_eq!(
+            result,
+            Err(ExecutionError::from(Error::InvalidEmergencyReason))
+        );
+        assert_eq!(chain.state(&pk), AirplaneState::Flying);
+    }
+
+    let reason = "y".repeat(MAX_EMERGENCY_REASON_LEN);
+    chain
+        .execute(TxEmergencyLanding::new(
+            &pk,
+            &pk,
+            &reason,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::Emergency);
+    assert_eq!(airplane.last_emergency_reason(), reason);
+    assert_eq!(airplane.emergency_count(), 0);
+
+    chain
+        .execute(TxClearEmergency::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+    assert_eq!(airplane.emergency_count(), 1);
+}
+
+#[test]
+fn serial_numbers_are_validated_and_unique() {
+    let mut chain = TestChain::new();
+    let register = |chain: &mut TestChain, name: &str, serial_number: &str| {
+        let (pk, sk) = gen_keypair();
+        chain.execute(TxRegisterAirplane::new(
+            &pk,
+            name,
+            "A320",
+            serial_number,
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+    };
+    register(&mut chain, "Boeing", "SN1234").unwrap();
+    let registered = Schema::new(&chain.fork)
+        .airplane_serials()
+        .get(&"SN1234".to_owned())
+        .unwrap();
+
+    for serial_number in &["SN1", "SN12345678901234567890", "SN-1234", "SN 1234"] {
+        assert_eq!(
+            register(&mut chain, "Airbus", serial_number),
+            Err(ExecutionError::from(Error::InvalidSerialNumber))
+        );
+    }
+    assert_eq!(
+        register(&mut chain, "Airbus", "SN1234"),
+        Err(ExecutionError::from(Error::SerialNumberAlreadyTaken))
+    );
+    assert_eq!(Schema::new(&chain.fork).airplanes().keys().count(), 1);
+
+    register(&mut chain, "Airbus", "SN5678").unwrap();
+    let schema = Schema::new(&chain.fork);
+    assert_eq!(schema.airplanes().keys().count(), 2);
+    let pk = schema.airplane_serials().get(&"SN5678".to_owned()).unwrap();
+    assert_ne!(pk, registered);
+    let airplane = schema.airplane(&pk).unwrap();
+    assert_eq!(airplane.airplane_model(), "A320");
+    assert_eq!(airplane.serial_number(), "SN5678");
+}
+
+#[test]
+fn technical_checks_are_recorded_in_maintenance_history() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = register_airplane(&mut chain);
+    assert_eq!(Schema::new(&chain.fork).maintenance_records(&pk).len(), 0);
+
+    for &(passed, notes) in &[(false, "Hydraulic leak"), (true, "Leak fixed")] {
+        chain.advance_time(60);
+        chain
+            .execute(TxStartTechnicalCheck::new(
+                &pk,
+                &pk,
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                &sk,
+            ))
+            .unwrap();
+        chain
+            .execute(TxEndTechnicalCheck::new(
+                &pk,
+                &pk,
+                passed,
+                HEATING_SECONDS,
+                notes,
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                &sk,
+            ))
+            .unwrap();
+    }
+    assert_eq!(chain.state(&pk), AirplaneState::HeatingEngine);
+
+    let records: Vec<_> = Schema::new(&chain.fork)
+        .maintenance_records(&pk)
+        .iter()
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].airplane_pub_key(), &pk);
+    assert!(!records[0].passed());
+    assert_eq!(records[0].technician_notes(), "Hydraulic leak");
+    assert_eq!(records[0].engine_heating_time_seconds(), 0);
+    assert!(records[1].passed());
+    assert_eq!(records[1].technician_notes(), "Leak fixed");
+    assert_eq!(records[1].engine_heating_time_seconds(), HEATING_SECONDS);
+    assert_eq!(records[1].timestamp(), chain.now);
+    assert!(records[0].timestamp() < records[1].timestamp());
+}
+
+#[test]
+fn airplane_pages_are_sliced_and_bounded() {
+    let mut chain = TestChain::new();
+    let keys: Vec<_> = (0..5).map(|_| register_airplane(&mut chain).0).collect();
+
+    let schema = Schema::new(&chain.fork);
+    let page_keys = |page, per_page| {
+        let page = schema.airplanes_page(PaginationParams::new(page, per_page).unwrap());
+        assert_eq!(page.total, 5);
+        page.items
+            .iter()
+            .map(|airplane| *airplane.pub_key())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(page_keys(0, 2), keys[..2].to_vec());
+    assert_eq!(page_keys(1, 2), keys[2..4].to_vec());
+    assert_eq!(page_keys(2, 2), keys[4..].to_vec());
+    assert!(page_keys(3, 2).is_empty());
+    assert!(page_keys(u64::MAX, 2).is_empty());
+    assert!(page_keys(0, 0).is_empty());
+
+    assert!(PaginationParams::new(0, MAX_PER_PAGE).is_ok());
+    assert!(PaginationParams::new(0, MAX_PER_PAGE + 1).is_err());
+
+    let query: AirplanesQuery = serde_json::from_str("{}").unwrap();
+    assert_eq!(query.page, 0);
+    assert_eq!(query.per_page, DEFAULT_PER_PAGE);
+}
+
+#[test]
+fn transition_airplane_validates_and_stores_the_update() {
+    let mut chain = TestChain::new();
+    let (pk, _) = register_airplane(&mut chain);
+    let (unknown_pk, _) = gen_keypair();
+    let tx_hash = Hash::zero();
+    let to_check = |airplane: Airplane| {
+        Ok(Airplane::builder_from(&airplane)
+            .state(AirplaneState::TechnicalCheck)
+            .build())
+    };
+
+    let mut schema = Schema::new(&mut chain.fork);
+    assert_eq!(
+        schema.transition_airplane(
+            &unknown_pk,
+            &tx_hash,
+            AirplaneState::WaitingForFlight,
+            to_check
+        ),
+        Err(ExecutionError::from(Error::AirplaneDoesNotExist(
+            unknown_pk
+        )))
+    );
+    assert_eq!(
+        schema.transition_airplane(&pk, &tx_hash, AirplaneState::Flying, to_check),
+        Err(ExecutionError::from(Error::InvalidStateTransition {
+            found: AirplaneState::WaitingForFlight,
+            required: AirplaneState::Flying.as_slice(),
+        }))
+    );
+    assert_eq!(
+        schema.transition_airplane(&pk, &tx_hash, AirplaneState::WaitingForFlight, |_| Err(
+            ExecutionError::from(Error::HeatingTimeOutOfRange)
+        )),
+        Err(ExecutionError::from(Error::HeatingTimeOutOfRange))
+    );
+    assert_eq!(schema.audit_log(&pk).len(), 1);
+
+    schema
+        .transition_airplane(&pk, &tx_hash, AirplaneState::WaitingForFlight, to_check)
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+    let schema = Schema::new(&chain.fork);
+    assert!(schema
+        .airplanes_by_state(AirplaneState::TechnicalCheck)
+        .contains(&pk));
+    assert!(!schema
+        .airplanes_by_state(AirplaneState::WaitingForFlight)
+        .contains(&pk));
+    let entry = schema.audit_log(&pk).last().unwrap();
+    assert_eq!(entry.tx_hash(), &tx_hash);
+    assert_eq!(schema.audit_log(&pk).len(), 2);
+}
+
+#[test]
+fn batch_registration_is_atomic_unless_skipping_existing() {
+    let mut chain = TestChain::new();
+    let (operator_pk, operator_sk) = gen_keypair();
+    let (existing_pk, _) = register_airplane(&mut chain);
+    let existing_name = Schema::new(&chain.fork)
+        .airplane(&existing_pk)
+        .unwrap()
+        .name()
+        .to_owned();
+    let new_keys: Vec<_> = (0..2).map(|_| gen_keypair().0).collect();
+    let registrations = |serials: &[&str]| {
+        let mut registrations = vec![AirplaneRegistration::new(
+            &existing_pk,
+            "Existing",
+            "A320",
+            "SN9000",
+            AirplaneType::Commercial as u8,
+        )];
+        for (i, (pk, serial)) in new_keys.iter().zip(serials).enumerate() {
+            registrations.push(AirplaneRegistration::new(
+                pk,
+                &format!("Batch {}", i),
+                "A320",
+                serial,
+                AirplaneType::Commercial as u8,
+            ));
+        }
+        registrations
+    };
+
+    let result = chain.execute(TxBatchRegisterAirplanes::new(
+        &operator_pk,
+        registrations(&["SN9001", "SN9002"]),
+        false,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &operator_sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::AirplaneAlreadyExists))
+    );
+
+    // A failing entry late in the batch discards the entries before it.
+    let result = chain.execute(TxBatchRegisterAirplanes::new(
+        &operator_pk,
+        registrations(&["SN9001", "SN9001"]),
+        true,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &operator_sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::SerialNumberAlreadyTaken))
+    );
+    let schema = Schema::new(&chain.fork);
+    assert_eq!(schema.airplanes().keys().count(), 1);
+    assert!(new_keys.iter().all(|pk| schema.airplane(pk).is_none()));
+
+    chain
+        .execute(TxBatchRegisterAirplanes::new(
+            &operator_pk,
+            registrations(&["SN9001", "SN9002"]),
+            true,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &operator_sk,
+        ))
+        .unwrap();
+    let schema = Schema::new(&chain.fork);
+    assert_eq!(schema.airplanes().keys().count(), 3);
+    assert_eq!(schema.airplane(&existing_pk).unwrap().name(), existing_name);
+    for pk in &new_keys {
+        assert_eq!(chain.state(pk), AirplaneState::WaitingForFlight);
+    }
+}
+
+#[test]
+fn airplane_proof_is_checked_against_the_state_hash() {
+    let mut chain = TestChain::new();
+    let (pk, _) = register_airplane(&mut chain);
+    register_airplane(&mut chain);
+    let (unknown_pk, _) = gen_keypair();
+
+    let schema = Schema::new(&chain.fork);
+    let airplane = schema.airplane(&pk).unwrap();
+    let state_hash = schema.state_hash();
+    // Clients receive the proof as JSON.
+    let proof = serde_json::to_value(schema.airplane_proof(&pk)).unwrap();
+    let proof: MapProof<PublicKey, Airplane> = serde_json::from_value(proof).unwrap();
+    let checked = proof.check().unwrap();
+    assert_eq!(checked.merkle_root(), state_hash[0]);
+    assert_eq!(checked.entries(), vec![(&pk, &airplane)]);
+
+    let checked = schema.airplane_proof(&unknown_pk).check().unwrap();
+    assert_eq!(checked.merkle_root(), state_hash[0]);
+    assert!(checked.entries().is_empty());
+    assert_eq!(checked.missing_keys(), vec![&unknown_pk]);
+}
+
+#[test]
+fn fleet_stats_count_airplanes_per_state() {
+    let mut chain = TestChain::new();
+    let (flying_pk, flying_sk) = prepare_heated_airplane(&mut chain);
+    complete_flight(&mut chain, &flying_pk, &flying_sk, 3_600);
+    prepare_next_flight(&mut chain, &flying_pk, &flying_sk);
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    chain
+        .execute(TxStartFlying::new(
+            &flying_pk,
+            &flying_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &flying_sk,
+        ))
+        .unwrap();
+
+    register_airplane(&mut chain);
+    let (retired_pk, retired_sk) = register_airplane(&mut chain);
+    chain
+        .execute(TxDecommissionAirplane::new(
+            &retired_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &retired_sk,
+        ))
+        .unwrap();
+
+    let state = chain.into_api_state();
+    let stats = AirplaneApi::get_stats(&state, SERVICE_NAME, ()).unwrap();
+    assert_eq!(stats.total, 2);
+    assert_eq!(stats.total_flights, 1);
+    assert_eq!(stats.total_flight_seconds, 3_600);
+    assert_eq!(stats.by_state.len(), AirplaneState::all().len());
+    for airplane_state in AirplaneState::all() {
+        let expected = match airplane_state {
+            AirplaneState::Flying
+            | AirplaneState::WaitingForFlight
+            | AirplaneState::Decommissioned => 1,
+            _ => 0,
+        };
+        assert_eq!(
+            stats.by_state[&airplane_state.to_string()],
+            expected,
+            "{}",
+            airplane_state
+        );
+    }
+}
+
+#[test]
+fn airplanes_are_found_by_name() {
+    let mut chain = TestChain::new();
+    let (first_pk, first_sk) = register_airplane(&mut chain);
+    let (second_pk, second_sk) = register_airplane(&mut chain);
+    rename(&mut chain, &first_pk, &first_sk, "Spirit of Riga").unwrap();
+    rename(&mut chain, &second_pk, &second_sk, "Baltic Star").unwrap();
+
+    let schema = Schema::new(&chain.fork);
+    assert_eq!(
+        schema.find_by_name("Spirit of Riga").unwrap().pub_key(),
+        &first_pk
+    );
+    assert_eq!(
+        schema.find_by_name("BALTIC STAR").unwrap().pub_key(),
+        &second_pk
+    );
+    assert_eq!(schema.find_by_name("Baltic"), None);
+
+    rename(&mut chain, &first_pk, &first_sk, "Spirit of Jurmala").unwrap();
+    chain
+        .execute(TxDecommissionAirplane::new(
+            &second_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &second_sk,
+        ))
+        .unwrap();
+    let schema = Schema::new(&chain.fork);
+    assert_eq!(schema.find_by_name("Spirit of Riga"), None);
+    assert_eq!(schema.find_by_name("Baltic Star"), None);
+
+    let state = chain.into_api_state();
+    let search = |name: &str| {
+        AirplaneApi::search_airplane_by_name(
+            &state,
+            SERVICE_NAME,
+            AirplaneNameQuery {
+                name: name.to_owned(),
+            },
+        )
+    };
+    assert_eq!(search("spirit of jurmala").unwrap().pub_key(), &first_pk);
+    match search("Spirit of Riga") {
+        Err(api::Error::NotFound(_)) => {}
+        other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn cancelled_flight_is_not_counted() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    let cancel = |chain: &mut TestChain| {
+        chain.execute(TxCancelFlight::new(
+            &pk,
+            &pk,
+            "Diverted by ATC",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+    };
+    assert_eq!(
+        cancel(&mut chain),
+        Err(ExecutionError::from(Error::InvalidStateTransition {
+            found: AirplaneState::HeatingEngine,
+            required: AirplaneState::Flying.as_slice(),
+        }))
+    );
+
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.advance_time(600);
+    cancel(&mut chain).unwrap();
+
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.flight_count(), 0);
+    assert_eq!(airplane.cancellation_count(), 1);
+    assert_eq!(airplane.last_cancellation_reason(), "Diverted by ATC");
+    assert_eq!(airplane.last_flight_end_time(), chain.now);
+    let runways = RunwaySchema::new(&chain.fork);
+    assert_eq!(runways.assigned_runway(&pk), None);
+    assert!(!runways.runway("09L").unwrap().is_occupied());
+}
+
+#[test]
+fn runways_are_assigned_to_one_airplane_at_a_time() {
+    let mut chain = TestChain::new();
+    let (heated_pk, heated_sk) = prepare_heated_airplane(&mut chain);
+    let (other_pk, other_sk) = register_airplane(&mut chain);
+    let assign = |chain: &mut TestChain, pk: &PublicKey, sk: &SecretKey, runway: &str| {
+        chain.execute(TxAssignRunway::new(
+            pk,
+            pk,
+            runway,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            sk,
+        ))
+    };
+    let release = |chain: &mut TestChain, pk: &PublicKey, sk: &SecretKey| {
+        chain.execute(TxReleaseRunway::new(
+            pk,
+            pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            sk,
+        ))
+    };
+
+    assert_eq!(
+        assign(&mut chain, &other_pk, &other_sk, "09L"),
+        Err(ExecutionError::from(Error::RunwayOccupied))
+    );
+    assign(&mut chain, &other_pk, &other_sk, "27R").unwrap();
+    assert_eq!(
+        assign(&mut chain, &other_pk, &other_sk, "27L"),
+        Err(ExecutionError::from(Error::RunwayAlreadyAssigned))
+    );
+    let runway = RunwaySchema::new(&chain.fork).runway("27R").unwrap();
+    assert!(runway.is_occupied());
+    assert_eq!(runway.assigned_airplane(), &other_pk);
+
+    // A heated airplane without a runway cannot take off.
+    release(&mut chain, &heated_pk, &heated_sk).unwrap();
+    assert_eq!(
+        release(&mut chain, &heated_pk, &heated_sk),
+        Err(ExecutionError::from(Error::NoRunwayAssigned))
+    );
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    let result = chain.execute(TxStartFlying::new(
+        &heated_pk,
+        &heated_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &heated_sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::NoRunwayAssigned)));
+
+    release(&mut chain, &other_pk, &other_sk).unwrap();
+    assign(&mut chain, &other_pk, &other_sk, "09L").unwrap();
+    let runways = RunwaySchema::new(&chain.fork);
+    assert!(!runways.runway("27R").unwrap().is_occupied());
+    assert_eq!(runways.assigned_runway(&other_pk).unwrap().name(), "09L");
+}
+
+#[test]
+fn takeoff_requires_minimum_fuel() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    assert_eq!(
+        Schema::new(&chain.fork)
+            .airplane(&pk)
+            .unwrap()
+            .fuel_level_percent(),
+        100
+    );
+    let refuel = |chain: &mut TestChain, new_level: u8| {
+        chain.execute(TxRefuelAirplane::new(
+            &pk,
+            &pk,
+            new_level,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+    };
+    assert_eq!(
+        refuel(&mut chain, 100),
+        Err(ExecutionError::from(Error::InvalidStateTransition {
+            found: AirplaneState::HeatingEngine,
+            required: AirplaneState::WaitingForFlight.as_slice(),
+        }))
+    );
+    complete_flight(&mut chain, &pk, &sk, 3_600);
+
+    assert_eq!(
+        refuel(&mut chain, 101),
+        Err(ExecutionError::from(Error::InvalidFuelLevel))
+    );
+    refuel(&mut chain, DEFAULT_MIN_FUEL_PERCENT_FOR_FLIGHT - 1).unwrap();
+    prepare_next_flight(&mut chain, &pk, &sk);
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    let result = chain.execute(TxStartFlying::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::InsufficientFuel)));
+    assert_eq!(chain.state(&pk), AirplaneState::HeatingEngine);
+}
+
+#[test]
+fn technical_checks_are_scheduled_ahead() {
+    let mut chain = TestChain::new();
+    let (service_pk, service_sk) = gen_keypair();
+    chain.set_validators(&[service_pk]);
+    let (pk, sk) = register_airplane(&mut chain);
+    let schedule = |chain: &mut TestChain, scheduled_at: DateTime<Utc>| {
+        chain.execute(TxScheduleTechnicalCheck::new(
+            &pk,
+            &pk,
+            scheduled_at,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+    };
+    let now = chain.now;
+    for &scheduled_at in &[now - Duration::hours(1), now] {
+        assert_eq!(
+            schedule(&mut chain, scheduled_at),
+            Err(ExecutionError::from(Error::InvalidScheduledTime))
+        );
+    }
+    schedule(&mut chain, now + Duration::hours(1)).unwrap();
+    // Checks are booked in chronological order.
+    assert_eq!(
+        schedule(&mut chain, now + Duration::minutes(30)),
+        Err(ExecutionError::from(Error::InvalidScheduledTime))
+    );
+    schedule(&mut chain, now + Duration::hours(2)).unwrap();
+
+    let start_due_check = |chain: &mut TestChain| {
+        chain.execute(TxStartScheduledCheck::new(
+            &pk,
+            &service_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &service_sk,
+        ))
+    };
+    assert_eq!(
+        start_due_check(&mut chain),
+        Err(ExecutionError::from(Error::ScheduledCheckNotDue))
+    );
+    chain.advance_time(3_600);
+    start_due_check(&mut chain).unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+
+    let schema = Schema::new(&chain.fork);
+    let checks: Vec<_> = schema.scheduled_checks(&pk).iter().collect();
+    assert_eq!(checks.len(), 2);
+    assert_eq!(checks[0].airplane_pub_key(), &pk);
+    assert_eq!(checks[0].scheduled_at(), now + Duration::hours(1));
+    assert_eq!(checks[0].scheduled_by(), &pk);
+    assert_eq!(checks[1].scheduled_at(), now + Duration::hours(2));
+    assert_eq!(schema.started_scheduled_checks(&pk), 1);
+}
+
+#[test]
+fn flight_routes_are_completed_on_landing() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    let record_route = |chain: &mut TestChain, departure: &str, arrival: &str| {
+        chain.execute(TxRecordFlightRoute::new(
+            &pk,
+            &pk,
+            departure,
+            arrival,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+    };
+    assert_eq!(
+        record_route(&mut chain, "RIX", "JFK"),
+        Err(ExecutionError::from(Error::InvalidStateTransition {
+            found: AirplaneState::HeatingEngine,
+            required: AirplaneState::Flying.as_slice(),
+        }))
+    );
+
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    for &(departure, arrival) in &[("rix", "JFK"), ("RIX", "JF")] {
+        assert_eq!(
+            record_route(&mut chain, departure, arrival),
+            Err(ExecutionError::from(Error::InvalidIataCode))
+        );
+    }
+    record_route(&mut chain, "RIX", "JFK").unwrap();
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.current_departure_iata(), "RIX");
+    assert_eq!(airplane.current_arrival_iata(), "JFK");
+
+    chain.advance_time(3_600);
+    chain
+        .execute(TxEndFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.current_departure_iata(), "");
+    assert_eq!(airplane.current_arrival_iata(), "");
+
+    // A flight without a recorded route adds no entry.
+    prepare_next_flight(&mut chain, &pk, &sk);
+    complete_flight(&mut chain, &pk, &sk, 1_800);
+    let routes: Vec<_> = Schema::new(&chain.fork)
+        .completed_routes(&pk)
+        .iter()
+        .collect();
+    assert_eq!(routes.len(), 1);
+    assert_eq!(routes[0].departure(), "RIX");
+    assert_eq!(routes[0].arrival(), "JFK");
+    assert_eq!(routes[0].duration_seconds(), 3_600);
+}
+
+#[test]
+fn audit_log_records_transitions_with_proofs() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = register_airplane(&mut chain);
+    chain.advance_blocks(2);
+    chain.advance_time(60);
+    let tx = TxStartTechnicalCheck::new(&pk, &pk, CREATED_AT_HEIGHT, next_nonce(), &sk);
+    let tx_hash = tx.hash();
+    chain.execute(tx).unwrap();
+
+    let schema = Schema::new(&chain.fork);
+    let entries: Vec<_> = schema.audit_log(&pk).iter().collect();
+    let audit_root = schema.audit_log(&pk).merkle_root();
+    assert_eq!(schema.audit_roots().get(&pk), Some(audit_root));
+    assert_eq!(entries.len(), 2);
+    assert_eq!(
+        entries[0].new_state(),
+        AirplaneState::WaitingForFlight as u8
+    );
+    assert_eq!(
+        entries[1].old_state(),
+        AirplaneState::WaitingForFlight as u8
+    );
+    assert_eq!(entries[1].new_state(), AirplaneState::TechnicalCheck as u8);
+    assert_eq!(entries[1].tx_hash(), &tx_hash);
+    assert_eq!(entries[1].block_height(), entries[0].block_height() + 2);
+    assert_eq!(entries[1].timestamp(), chain.now);
+
+    let state = chain.into_api_state();
+    let log = AirplaneApi::get_audit_log(&state, SERVICE_NAME, AirplaneQuery { pub_key: pk }).unwrap();
+    assert_eq!(log, entries);
+    let proof = AirplaneApi::get_audit_entry_proof(
+        &state,
+        SERVICE_NAME,
+        AuditEntryProofQuery {
+            pub_key: pk,
+            index: 1,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        proof.validate(audit_root, 2).unwrap(),
+        vec![(1, &entries[1])]
+    );
+    match AirplaneApi::get_audit_entry_proof(
+        &state,
+        SERVICE_NAME,
+        AuditEntryProofQuery {
+            pub_key: pk,
+            index: 2,
+        },
+    ) {
+        Err(api::Error::NotFound(_)) => {}
+        other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn transactions_fail_without_oracle_time() {
+    let mut chain = TestChain::new();
+    let (heated_pk, heated_sk) = prepare_heated_airplane(&mut chain);
+    let (checked_pk, checked_sk) = register_airplane(&mut chain);
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &checked_pk,
+            &checked_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &checked_sk,
+        ))
+        .unwrap();
+    TimeSchema::new(&mut chain.fork).time_mut().remove();
+
+    let time_not_available = Err(ExecutionError::from(Error::TimeNotAvailable));
+    let (pk, sk) = gen_keypair();
+    let result = chain.execute(TxRegisterAirplane::new(
+        &pk,
+        "Tupolev",
+        "Tu-154",
+        "SN7777",
+        AirplaneType::Commercial as u8,
+        0,
+        0,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(result, time_not_available);
+    let result = chain.execute(TxEndTechnicalCheck::new(
+        &checked_pk,
+        &checked_pk,
+        true,
+        HEATING_SECONDS,
+        "All systems nominal",
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &checked_sk,
+    ));
+    assert_eq!(result, time_not_available);
+    let result = chain.execute(TxStartFlying::new(
+        &heated_pk,
+        &heated_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &heated_sk,
+    ));
+    assert_eq!(result, time_not_available);
+
+    let schema = Schema::new(&chain.fork);
+    assert_eq!(schema.airplane(&pk), None);
+    assert_eq!(chain.state(&checked_pk), AirplaneState::TechnicalCheck);
+    assert_eq!(chain.state(&heated_pk), AirplaneState::HeatingEngine);
+}
+
+/// Runs a technical check on an airplane waiting for flight and ends it with `passed`.
+fn run_technical_check(
+    chain: &mut TestChain,
+    pk: &PublicKey,
+    sk: &SecretKey,
+    passed: bool,
+    heating_seconds: u32,
+) {
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            pk,
+            pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            pk,
+            pk,
+            passed,
+            heating_seconds,
+            "",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            sk,
+        ))
+        .unwrap();
+}
+
+#[test]
+fn internal_stats_summarize_maintenance_records() {
+    let empty = TestChain::new().into_api_state();
+    let stats = PrivateAirplaneApi::get_internal_stats(&empty, SERVICE_NAME, ()).unwrap();
+    assert_eq!(stats.failed_technical_checks_total, 0);
+    assert_eq!(stats.total_decommissioned, 0);
+    assert_eq!(stats.avg_heating_seconds, 0.0);
+
+    let mut chain = TestChain::new();
+    let (first_pk, first_sk) = register_airplane(&mut chain);
+    run_technical_check(&mut chain, &first_pk, &first_sk, false, 0);
+    run_technical_check(&mut chain, &first_pk, &first_sk, true, 60);
+    let (second_pk, second_sk) = register_airplane(&mut chain);
+    run_technical_check(&mut chain, &second_pk, &second_sk, true, 90);
+    let (retired_pk, retired_sk) = register_airplane(&mut chain);
+    chain
+        .execute(TxDecommissionAirplane::new(
+            &retired_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &retired_sk,
+        ))
+        .unwrap();
+
+    let state = chain.into_api_state();
+    let stats = PrivateAirplaneApi::get_internal_stats(&state, SERVICE_NAME, ()).unwrap();
+    assert_eq!(stats.failed_technical_checks_total, 1);
+    assert_eq!(stats.total_decommissioned, 1);
+    assert_eq!(stats.avg_heating_seconds, 75.0);
+}
+
+#[test]
+fn airplanes_record_their_registration_time() {
+    let mut chain = TestChain::new();
+    let (first_pk, first_sk) = register_airplane(&mut chain);
+    let first_registered = chain.now;
+    chain.advance_time(3_600);
+    let (second_pk, _) = register_airplane(&mut chain);
+    chain.advance_time(60);
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &first_pk,
+            &first_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &first_sk,
+        ))
+        .unwrap();
+
+    let schema = Schema::new(&chain.fork);
+    let first = schema.airplane(&first_pk).unwrap();
+    let second = schema.airplane(&second_pk).unwrap();
+    assert_eq!(first.registered_at(), first_registered);
+    assert_eq!(
+        second.registered_at(),
+        first_registered + Duration::hours(1)
+    );
+
+    let state = chain.into_api_state();
+    let response = AirplaneApi::get_airplane(&state, SERVICE_NAME, AirplaneQuery { pub_key: second_pk }).unwrap();
+    assert_eq!(
+        serde_json::to_value(&response).unwrap()["registered_at"]["secs"],
+        json!(second.registered_at().timestamp().to_string())
+    );
+}
+
+#[test]
+fn heating_time_is_updated_while_heating() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    let heating_start = Schema::new(&chain.fork)
+        .airplane(&pk)
+        .unwrap()
+        .engine_heating_start_time();
+    let update = |chain: &mut TestChain, seconds: u32| {
+        chain.execute(TxUpdateEngineHeatingTime::new(
+            &pk,
+            &pk,
+            seconds,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+    };
+    let start_flying = |chain: &mut TestChain| {
+        chain.execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+    };
+
+    for &seconds in &[0, DEFAULT_MAX_HEATING_SECONDS + 1] {
+        assert_eq!(
+            update(&mut chain, seconds),
+            Err(ExecutionError::from(Error::HeatingTimeOutOfRange))
+        );
+    }
+    chain.advance_time(30);
+    update(&mut chain, 2 * HEATING_SECONDS).unwrap();
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.engine_heating_time_seconds(), 2 * HEATING_SECONDS);
+    assert_eq!(airplane.engine_heating_start_time(), heating_start);
+
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    assert_eq!(
+        start_flying(&mut chain),
+        Err(ExecutionError::from(Error::EngineIsNotHeated))
+    );
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    start_flying(&mut chain).unwrap();
+
+    assert_eq!(
+        update(&mut chain, HEATING_SECONDS),
+        Err(ExecutionError::from(Error::InvalidStateTransition {
+            found: AirplaneState::Flying,
+            required: AirplaneState::HeatingEngine.as_slice(),
+        }))
+    );
+}
+
+#[test]
+fn batch_query_returns_airplanes_in_request_order() {
+    let mut chain = TestChain::new();
+    let (first_pk, _) = register_airplane(&mut chain);
+    let (second_pk, _) = register_airplane(&mut chain);
+    let (unknown_pk, _) = gen_keypair();
+    let schema = Schema::new(&chain.fork);
+    let first = schema.airplane(&first_pk);
+    let second = schema.airplane(&second_pk);
+
+    let state = chain.into_api_state();
+    let batch = |keys: &[PublicKey]| {
+        AirplaneApi::get_airplanes_batch(
+            &state,
+            SERVICE_NAME,
+            keys.iter()
+                .map(|pub_key| AirplaneQuery { pub_key: *pub_key })
+                .collect(),
+        )
+    };
+    assert_eq!(batch(&[]).unwrap(), vec![]);
+    assert_eq!(
+        batch(&[second_pk, first_pk]).unwrap(),
+        vec![second.clone(), first.clone()]
+    );
+    assert_eq!(
+        batch(&[first_pk, unknown_pk, second_pk]).unwrap(),
+        vec![first.clone(), None, second]
+    );
+    assert_eq!(
+        batch(&vec![first_pk; MAX_BATCH_QUERY_SIZE]).unwrap().len(),
+        MAX_BATCH_QUERY_SIZE
+    );
+    match batch(&vec![first_pk; MAX_BATCH_QUERY_SIZE + 1]) {
+        Err(api::Error::BadRequest(message)) => assert!(message.contains("200")),
+        other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn state_transitions_keep_counters_and_reset_times() {
+    let (pk, _) = gen_keypair();
+    let epoch = Utc.timestamp_opt(0, 0).unwrap();
+    let time = Utc.timestamp_opt(1_500_000_000, 0).unwrap();
+    let airplane = AirplaneBuilder::new(&pk, "Boeing", "737", "SN1234")
+        .state(AirplaneState::HeatingEngine)
+        .heating_start_time(time)
+        .heating_time_seconds(HEATING_SECONDS)
+        .flight_start_time(time)
+        .flight_count(3)
+        .accumulated_flight_seconds(10_800)
+        .emergency_count(1)
+        .fuel_level_percent(42)
+        .build();
+
+    let waiting = airplane.transition_state(AirplaneState::WaitingForFlight);
+    assert_eq!(waiting.state().unwrap(), AirplaneState::WaitingForFlight);
+    assert_eq!(waiting.state_str(), "Waiting for flight");
+    assert_eq!(waiting.engine_heating_start_time(), epoch);
+    assert_eq!(waiting.engine_heating_time_seconds(), 0);
+    assert_eq!(waiting.flight_start_time(), epoch);
+    assert_eq!(
+        waiting,
+        Airplane::builder_from(&airplane)
+            .state(AirplaneState::WaitingForFlight)
+            .heating_start_time(epoch)
+            .heating_time_seconds(0)
+            .flight_start_time(epoch)
+            .build()
+    );
+
+    let later = time + Duration::hours(1);
+    let heating = waiting.transition_state_with_time(AirplaneState::HeatingEngine, later, 120);
+    assert_eq!(heating.state().unwrap(), AirplaneState::HeatingEngine);
+    assert_eq!(heating.engine_heating_start_time(), later);
+    assert_eq!(heating.engine_heating_time_seconds(), 120);
+    assert_eq!(heating.flight_start_time(), epoch);
+    assert_eq!(heating.pub_key(), &pk);
+    assert_eq!(heating.name(), "Boeing");
+    assert_eq!(heating.flight_count(), 3);
+    assert_eq!(heating.accumulated_flight_seconds(), 10_800);
+    assert_eq!(heating.emergency_count(), 1);
+    assert_eq!(heating.fuel_level_percent(), 42);
+}
+
+#[test]
+fn airplane_count_follows_registrations_and_decommissioning() {
+    let mut chain = TestChain::new();
+    assert_eq!(Schema::new(&chain.fork).airplane_count(), 0);
+
+    let keys: Vec<_> = (0..3).map(|_| register_airplane(&mut chain)).collect();
+    assert_eq!(Schema::new(&chain.fork).airplane_count(), 3);
+
+    let (ref pk, ref sk) = keys[1];
+    chain
+        .execute(TxDecommissionAirplane::new(
+            pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            sk,
+        ))
+        .unwrap();
+    let schema = Schema::new(&chain.fork);
+    assert_eq!(schema.airplane_count(), 2);
+    assert_eq!(schema.airplanes().values().count(), 3);
+
+    let state = chain.into_api_state();
+    assert_eq!(AirplaneApi::get_stats(&state, SERVICE_NAME, ()).unwrap().total, 2);
+}
+
+#[test]
+fn start_flying_after_heating() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    assert_eq!(chain.state(&pk), AirplaneState::Flying);
+}
+
+#[test]
+fn failed_technical_check_resets_to_waiting_for_flight() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Airbus",
+            "A320",
+            "SN5678",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            &pk,
+            &pk,
+            false,
+            0,
+            "Hydraulic leak",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.engine_heating_time_seconds(), 0);
+}
+
+#[test]
+fn start_flying_before_heating_elapsed() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+
+    chain.advance_time(i64::from(HEATING_SECONDS) - 1);
+    let result = chain.execute(TxStartFlying::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+
+    assert_eq!(result, Err(ExecutionError::from(Error::EngineIsNotHeated)));
+    assert_eq!(chain.state(&pk), AirplaneState::HeatingEngine);
+}
+
+#[test]
+fn admin_grounds_flying_airplane() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    chain
+        .execute(TxGroundAirplane::new(
+            &pk,
+            "Engine inspection",
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+
+    assert_eq!(chain.state(&pk), AirplaneState::Grounded);
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.grounded_reason(), "Engine inspection");
+
+    chain
+        .execute(TxUngroundAirplane::new(
+            &pk,
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+}
+
+#[test]
+fn ground_airplane_requires_configured_admin() {
+    let mut chain = TestChain::new();
+    let (admin_pk, _) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Boeing",
+            "737",
+            "SN1234",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    let result = chain.execute(TxGroundAirplane::new(
+        &pk,
+        "No authority",
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+
+    assert_eq!(result, Err(ExecutionError::from(Error::NotAdmin)));
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+}
+
+#[test]
+fn registration_fails_when_fleet_is_full() {
+    let mut chain = TestChain::new();
+    chain.set_config(config(2, 0));
+
+    for i in 0..2 {
+        let (pk, sk) = gen_keypair();
+        let name = format!("Airplane {}", i);
+        let serial = format!("SN000{}", i);
+        chain
+            .execute(TxRegisterAirplane::new(
+                &pk,
+                &name,
+                "737",
+                &serial,
+                AirplaneType::Commercial as u8,
+                0,
+                0,
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                &sk,
+            ))
+            .unwrap();
+    }
+
+    let (pk, sk) = gen_keypair();
+    let result = chain.execute(TxRegisterAirplane::new(
+        &pk,
+        "Airplane 2",
+        "737",
+        "SN0002",
+        AirplaneType::Commercial as u8,
+        0,
+        0,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::FleetCapacityReached))
+    );
+    assert_eq!(Schema::new(&chain.fork).airplane_count(), 2);
+}
+
+#[test]
+fn extended_technical_check_ends_after_original_window() {
+    let mut chain = TestChain::new();
+    chain.set_config(config(0, 600));
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Boeing",
+            "737",
+            "SN1234",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    chain
+        .execute(TxRequestMaintenanceExtension::new(
+            &pk,
+            &pk,
+            300,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheckExtended);
+
+    // A check can only be extended once.
+    let result = chain.execute(TxRequestMaintenanceExtension::new(
+        &pk,
+        &pk,
+        300,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert!(result.is_err());
+
+    chain.advance_time(800);
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            &pk,
+            &pk,
+            true,
+            HEATING_SECONDS,
+            "",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::HeatingEngine);
+}
+
+#[test]
+fn technical_check_fails_after_deadline() {
+    let mut chain = TestChain::new();
+    chain.set_config(config(0, 600));
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Boeing",
+            "737",
+            "SN1234",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    chain.advance_time(601);
+    let result = chain.execute(TxEndTechnicalCheck::new(
+        &pk,
+        &pk,
+        true,
+        HEATING_SECONDS,
+        "",
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::MaintenanceDeadlineExceeded))
+    );
+}
+
+#[test]
+fn airplane_name_length_is_validated() {
+    let mut chain = TestChain::new();
+    let cases = vec![
+        (String::new(), false),
+        ("A".repeat(MAX_AIRPLANE_NAME_LEN), true),
+        ("A".repeat(MAX_AIRPLANE_NAME_LEN + 1), false),
+        // Non-ASCII names are accepted; the limit counts UTF-8 bytes.
+        ("Туполев".to_owned(), true),
+    ];
+
+    for (i, (name, is_valid)) in cases.into_iter().enumerate() {
+        let (pk, sk) = gen_keypair();
+        let serial = format!("SN000{}", i);
+        let tx = TxRegisterAirplane::new(
+            &pk,
+            &name,
+            "737",
+            &serial,
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        );
+        assert_eq!(tx.verify(), is_valid, "{:?}", name);
+
+        // Execution checks the name too, for transactions that skip the mempool.
+        chain.fork.checkpoint();
+        let result = tx.execute(&mut chain.fork);
+        chain.fork.rollback();
+        if is_valid {
+            assert_eq!(result, Ok(()));
+        } else {
+            assert_eq!(
+                result,
+                Err(ExecutionError::from(Error::InvalidAirplaneName))
+            );
+        }
+    }
+}
+
+#[test]
+fn stored_pub_key_mismatch_is_rejected() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+
+    // Store the airplane under `pk` with the key of another airplane.
+    let (other_pk, _) = gen_keypair();
+    {
+        let mut schema = Schema::new(&mut chain.fork);
+        let airplane = schema.seed_test_airplane(&pk, "Boeing", AirplaneState::WaitingForFlight);
+        let forged = Airplane::builder_from(&airplane).pub_key(&other_pk).build();
+        schema.airplanes_mut().put(&pk, forged);
+    }
+
+    let result = chain.execute(TxStartTechnicalCheck::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+
+    assert_eq!(result, Err(ExecutionError::from(Error::PubKeyMismatch)));
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+}
+
+#[test]
+fn migration_rejects_unknown_state_numbers() {
+    let mut chain = TestChain::new();
+    let (pk, _) = gen_keypair();
+    let old = AirplaneV1::new(&pk, "Tupolev", 42, "Unknown", EPOCH, 0);
+    MapIndex::new("airplanes", &mut chain.fork).put(&pk, old.clone());
+
+    match Schema::new(&mut chain.fork).migrate() {
+        Err(MigrationError::InvalidState {
+            pub_key,
+            state_number,
+        }) => {
+            assert_eq!(pub_key, pk);
+            assert_eq!(state_number, 42);
+        }
+        other => panic!("Unexpected migration result: {:?}", other),
+    }
+    let index: MapIndex<&Fork, PublicKey, AirplaneV1> = MapIndex::new("airplanes", &chain.fork);
+    assert_eq!(index.get(&pk), Some(old));
+}
+
+#[test]
+fn export_import_round_trip() {
+    let mut chain = TestChain::new();
+    let (decommissioned_pk, decommissioned_sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &decommissioned_pk,
+            "Tupolev",
+            "Tu-154",
+            "SN0000",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &decommissioned_sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxDecommissionAirplane::new(
+            &decommissioned_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &decommissioned_sk,
+        ))
+        .unwrap();
+    let (pk, _) = prepare_heated_airplane(&mut chain);
+    let name = Schema::new(&chain.fork)
+        .airplane(&pk)
+        .unwrap()
+        .name()
+        .to_string();
+
+    let exported = Schema::new(&chain.fork).export_airplanes();
+    assert_eq!(exported.len(), 2);
+    assert!(exported[0].pub_key() < exported[1].pub_key());
+
+    let mut restored = MemoryDB::new().fork();
+    let imported = Schema::new(&mut restored)
+        .import_airplanes(&exported)
+        .unwrap();
+
+    assert_eq!(imported, 2);
+    let schema = Schema::new(&restored);
+    assert_eq!(schema.export_airplanes(), exported);
+    assert_eq!(schema.airplane_count(), 1);
+    assert_eq!(schema.find_by_name(&name).unwrap().pub_key(), &pk);
+    assert_eq!(schema.find_by_name("Tupolev"), None);
+}
+
+#[test]
+fn critical_incidents_block_technical_check() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+    let (reporter_pk, reporter_sk) = gen_keypair();
+    chain.set_admin(&reporter_pk);
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Boeing",
+            "737",
+            "SN1234",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    let invalid = TxReportIncident::new(
+        &pk,
+        6,
+        "Unknown severity",
+        &reporter_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &reporter_sk,
+    );
+    assert!(!invalid.verify());
+
+    for _ in 0..4 {
+        chain
+            .execute(TxReportIncident::new(
+                &pk,
+                5,
+                "Engine fire",
+                &reporter_pk,
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                &reporter_sk,
+            ))
+            .unwrap();
+    }
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+    assert_eq!(Schema::new(&chain.fork).incidents(&pk).len(), 4);
+
+    let result = chain.execute(TxStartTechnicalCheck::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::TooManyCriticalIncidents))
+    );
+
+    // Incidents older than the window no longer count.
+    chain.advance_time(31 * 24 * 60 * 60);
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+}
+
+#[test]
+fn event_stream_yields_committed_events_in_order() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Boeing",
+            "737",
+            "SN1234",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let streams = EventStreams::default();
+    let events = streams.subscribe(pk, &chain.fork, SERVICE_NAME);
+
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    streams.publish(&chain.fork, SERVICE_NAME);
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            &pk,
+            &pk,
+            true,
+            HEATING_SECONDS,
+            "",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    streams.publish(&chain.fork, SERVICE_NAME);
+    // Publishing again must not repeat events.
+    streams.publish(&chain.fork, SERVICE_NAME);
+    drop(streams);
+
+    let event_types: Vec<u8> = events
+        .wait()
+        .map(|event| event.unwrap().event_type())
+        .collect();
+    assert_eq!(
+        event_types,
+        vec![
+            AirplaneEventType::TechnicalCheckStarted as u8,
+            AirplaneEventType::TechnicalCheckEnded as u8,
+            AirplaneEventType::HeatingStarted as u8,
+        ]
+    );
+}
+
+#[test]
+fn start_flying_requires_type_minimum_heating() {
+    let cases = vec![
+        (AirplaneType::Commercial, 60),
+        (AirplaneType::Cargo, 45),
+        (AirplaneType::Military, 30),
+        (AirplaneType::Private, 15),
+    ];
+
+    for (airplane_type, min_heating_seconds) in cases {
+        let mut chain = TestChain::new();
+        let (pk, sk) = gen_keypair();
+        chain
+            .execute(TxRegisterAirplane::new(
+                &pk,
+                "Boeing",
+                "737",
+                "SN1234",
+                airplane_type as u8,
+                0,
+                0,
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                &sk,
+            ))
+            .unwrap();
+        chain.certify(&pk);
+        chain
+            .execute(TxAssignRunway::new(
+                &pk,
+                &pk,
+                "09L",
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                &sk,
+            ))
+            .unwrap();
+        chain
+            .execute(TxStartTechnicalCheck::new(
+                &pk,
+                &pk,
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                &sk,
+            ))
+            .unwrap();
+        // The technical check asks for less heating than the type requires.
+        chain
+            .execute(TxEndTechnicalCheck::new(
+                &pk,
+                &pk,
+                true,
+                DEFAULT_MIN_HEATING_SECONDS,
+                "",
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                &sk,
+            ))
+            .unwrap();
+
+        chain.advance_time(min_heating_seconds - 1);
+        let result = chain.execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ));
+        assert_eq!(
+            result,
+            Err(ExecutionError::from(Error::EngineIsNotHeated)),
+            "{}",
+            airplane_type
+        );
+
+        chain.advance_time(1);
+        chain
+            .execute(TxStartFlying::new(
+                &pk,
+                &pk,
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                &sk,
+            ))
+            .unwrap();
+        let schema = Schema::new(&chain.fork);
+        assert_eq!(schema.airplanes_by_type(airplane_type).len(), 1);
+    }
+}
+
+#[test]
+fn assigned_operator_can_start_technical_check() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+    let (operator_pk, operator_sk) = gen_keypair();
+    let (stranger_pk, stranger_sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Boeing",
+            "737",
+            "SN1234",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxAssignOperator::new(
+            &pk,
+            &operator_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    let result = chain.execute(TxStartTechnicalCheck::new(
+        &pk,
+        &stranger_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &stranger_sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::NotOwnerOrOperator)));
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &operator_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &operator_sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+}
+
+#[test]
+fn replayed_transaction_is_rejected_by_nonce() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    let start_flying = TxStartFlying::new(&pk, &pk, CREATED_AT_HEIGHT, next_nonce(), &sk);
+    chain.execute(start_flying.clone()).unwrap();
+    chain
+        .execute(TxEndFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    // Bring the airplane back to `HeatingEngine`, where the captured message is valid again.
+    chain
+        .execute(TxAssignRunway::new(
+            &pk,
+            &pk,
+            "09L",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            &pk,
+            &pk,
+            true,
+            HEATING_SECONDS,
+            "",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.advance_time(i64::from(HEATING_SECONDS));
+
+    let result = chain.execute(start_flying);
+    assert_eq!(result, Err(ExecutionError::from(Error::InvalidNonce)));
+    assert_eq!(chain.state(&pk), AirplaneState::HeatingEngine);
+
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::Flying);
+}
+
+#[test]
+fn updated_config_applies_to_later_transactions() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Boeing",
+            "737",
+            "SN1234",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    let mut config =
+        serde_json::to_value(AirplaneServiceConfig::with_admin(Some(admin_pk))).unwrap();
+    config["min_heating_seconds"] = json!(HEATING_SECONDS + 1);
+    chain
+        .execute(TxUpdateServiceConfig::new(
+            &admin_pk,
+            &config.to_string(),
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+    assert_eq!(
+        Schema::new(&chain.fork)
+            .service_config()
+            .min_heating_seconds(),
+        HEATING_SECONDS + 1
+    );
+
+    let result = chain.execute(TxEndTechnicalCheck::new(
+        &pk,
+        &pk,
+        true,
+        HEATING_SECONDS,
+        "All systems nominal",
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::HeatingTimeOutOfRange))
+    );
+
+    config["max_heating_seconds"] = json!(HEATING_SECONDS);
+    let result = chain.execute(TxUpdateServiceConfig::new(
+        &admin_pk,
+        &config.to_string(),
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &admin_sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::InvalidConfig)));
+
+    let result = chain.execute(TxUpdateServiceConfig::new(
+        &admin_pk,
+        "{\"min_heating_seconds\": 1}",
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &admin_sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::InvalidConfig)));
+}
+
+#[test]
+fn clear_test_data_removes_airplanes_and_indexes() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    let (seeded_pk, _) = gen_keypair();
+    Schema::new(&mut chain.fork).seed_test_airplane(&seeded_pk, "Cessna", AirplaneState::Grounded);
+    assert_eq!(
+        Schema::new(&chain.fork).airplanes_in_state(AirplaneState::Grounded),
+        vec![seeded_pk]
+    );
+
+    Schema::new(&mut chain.fork).clear_test_data();
+
+    let schema = Schema::new(&chain.fork);
+    assert_eq!(schema.airplanes().keys().count(), 0);
+    assert_eq!(schema.airplane_count(), 0);
+    assert_eq!(schema.find_by_name("Boeing"), None);
+    assert!(schema
+        .airplanes_in_state(AirplaneState::HeatingEngine)
+        .is_empty());
+    assert!(schema.audit_log(&pk).is_empty());
+    assert_eq!(schema.last_used_nonce(&pk), 0);
+    assert_eq!(RunwaySchema::new(&chain.fork).assigned_runway(&pk), None);
+
+    // The same keys can be registered again from scratch.
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Boeing",
+            "737",
+            "SN1234",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+}
+
+#[test]
+fn queued_checks_start_by_priority() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    let (service_pk, service_sk) = gen_keypair();
+    chain.set_validators(&[service_pk]);
+    let mut config =
+        serde_json::to_value(AirplaneServiceConfig::with_admin(Some(admin_pk))).unwrap();
+    config["max_concurrent_technical_checks"] = json!(1);
+    chain.set_config(serde_json::from_value(config).unwrap());
+
+    let mut airplanes = Vec::new();
+    for (name, serial) in &[
+        ("Boeing", "SN0001"),
+        ("Airbus", "SN0002"),
+        ("Cessna", "SN0003"),
+    ] {
+        let (pk, sk) = gen_keypair();
+        chain
+            .execute(TxRegisterAirplane::new(
+                &pk,
+                name,
+                "Model",
+                serial,
+                AirplaneType::Commercial as u8,
+                0,
+                0,
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                &sk,
+            ))
+            .unwrap();
+        airplanes.push((pk, sk));
+    }
+    let (first_pk, ref first_sk) = airplanes[0];
+    let (second_pk, _) = airplanes[1];
+    let (urgent_pk, ref urgent_sk) = airplanes[2];
+    chain
+        .execute(TxAssignPriority::new(
+            &urgent_pk,
+            MAX_PRIORITY,
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+
+    for (pk, sk) in &airplanes {
+        chain
+            .execute(TxStartTechnicalCheck::new(
+                pk,
+                pk,
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                sk,
+            ))
+            .unwrap();
+        assert_eq!(chain.state(pk), AirplaneState::WaitingForFlight);
+    }
+    let result = chain.execute(TxStartTechnicalCheck::new(
+        &first_pk,
+        &first_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        first_sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::AlreadyInCheckQueue))
+    );
+    assert_eq!(
+        Schema::new(&chain.fork).queued_checks(),
+        vec![urgent_pk, first_pk, second_pk]
+    );
+
+    let result = chain.execute(TxStartQueuedCheck::new(
+        &first_pk,
+        &service_pk,
+        CREATED_AT_HEIGHT,
+        0,
+        &service_sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::QueuedCheckNotReady))
+    );
+    let (other_pk, other_sk) = gen_keypair();
+    let result = chain.execute(TxStartQueuedCheck::new(
+        &urgent_pk,
+        &other_pk,
+        CREATED_AT_HEIGHT,
+        0,
+        &other_sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::NotValidator)));
+    chain
+        .execute(TxStartQueuedCheck::new(
+            &urgent_pk,
+            &service_pk,
+            CREATED_AT_HEIGHT,
+            0,
+            &service_sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&urgent_pk), AirplaneState::TechnicalCheck);
+
+    // The only slot is taken until the urgent airplane finishes its check.
+    let result = chain.execute(TxStartQueuedCheck::new(
+        &first_pk,
+        &service_pk,
+        CREATED_AT_HEIGHT,
+        0,
+        &service_sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::QueuedCheckNotReady))
+    );
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            &urgent_pk,
+            &urgent_pk,
+            false,
+            0,
+            "Hydraulic leak",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            urgent_sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartQueuedCheck::new(
+            &first_pk,
+            &service_pk,
+            CREATED_AT_HEIGHT,
+            0,
+            &service_sk,
+        ))
+        .unwrap();
+
+    assert_eq!(chain.state(&first_pk), AirplaneState::TechnicalCheck);
+    assert_eq!(Schema::new(&chain.fork).queued_checks(), vec![second_pk]);
+}
+
+#[test]
+fn transaction_expires_after_configured_blocks() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Boeing",
+            "737",
+            "SN1234",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let stale = TxStartTechnicalCheck::new(&pk, &pk, CREATED_AT_HEIGHT, next_nonce(), &sk);
+
+    chain.advance_blocks(u64::from(DEFAULT_TX_EXPIRY_BLOCKS) + 1);
+    let result = chain.execute(stale);
+
+    assert_eq!(result, Err(ExecutionError::from(Error::TransactionExpired)));
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+
+    let height = Schema::new(&chain.fork).height();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            height,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+}
+
+#[test]
+fn decommissioned_airplanes_are_listed_separately() {
+    let mut chain = TestChain::new();
+    let (active_pk, active_sk) = gen_keypair();
+    let (retired_pk, retired_sk) = gen_keypair();
+    for &(ref pk, sk, name, serial) in &[
+        (active_pk, &active_sk, "Boeing", "SN0001"),
+        (retired_pk, &retired_sk, "Tupolev", "SN0002"),
+    ] {
+        chain
+            .execute(TxRegisterAirplane::new(
+                pk,
+                name,
+                "Model",
+                serial,
+                AirplaneType::Commercial as u8,
+                0,
+                0,
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                sk,
+            ))
+            .unwrap();
+    }
+    chain
+        .execute(TxDecommissionAirplane::new(
+            &retired_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &retired_sk,
+        ))
+        .unwrap();
+
+    let schema = Schema::new(&chain.fork);
+    let page = schema.airplanes_page(PaginationParams::new(0, 50).unwrap());
+    assert_eq!(page.total, 1);
+    assert_eq!(page.items[0].pub_key(), &active_pk);
+    let decommissioned = schema.all_decommissioned();
+    assert_eq!(decommissioned.len(), 1);
+    assert_eq!(decommissioned[0].pub_key(), &retired_pk);
+    assert!(schema.decommissioned_airplane(&retired_pk).is_some());
+    assert_eq!(schema.decommissioned_airplane(&active_pk), None);
+
+    let result = chain.execute(TxStartTechnicalCheck::new(
+        &retired_pk,
+        &retired_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &retired_sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::AirplaneIsDecommissioned))
+    );
+}
+
+#[test]
+fn decommissioned_airplanes_reject_incident_reports() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = register_airplane(&mut chain);
+    chain
+        .execute(TxDecommissionAirplane::new(
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    let (reporter_pk, reporter_sk) = gen_keypair();
+    let result = chain.execute(TxReportIncident::new(
+        &pk,
+        2,
+        "Hangar door scratch",
+        &reporter_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &reporter_sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::AirplaneIsDecommissioned))
+    );
+    assert_eq!(Schema::new(&chain.fork).incidents(&pk).len(), 0);
+}
+
+#[test]
+fn decommissioned_airplanes_reject_operator_unassignment() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = register_airplane(&mut chain);
+    let (operator_pk, _) = gen_keypair();
+    chain
+        .execute(TxAssignOperator::new(
+            &pk,
+            &operator_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxDecommissionAirplane::new(
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    let result = chain.execute(TxUnassignOperator::new(
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::AirplaneIsDecommissioned))
+    );
+    assert_eq!(
+        Schema::new(&chain.fork).airplane_operator(&pk),
+        Some(operator_pk)
+    );
+}
+
+#[test]
+fn unloading_more_than_loaded_empties_cargo() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Freighter",
+            "747-8F",
+            "SN7478",
+            AirplaneType::Cargo as u8,
+            10_000,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxLoadCargo::new(
+            &pk,
+            &pk,
+            4_000,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    chain
+        .execute(TxUnloadCargo::new(
+            &pk,
+            &pk,
+            5_000,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.cargo_weight_kg(), 0);
+    assert_eq!(airplane.max_takeoff_weight_kg(), 10_000);
+}
+
+#[test]
+fn overweight_airplane_cannot_take_off() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Freighter",
+            "747-8F",
+            "SN7478",
+            AirplaneType::Commercial as u8,
+            10_000,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxLoadCargo::new(
+            &pk,
+            &pk,
+            10_001,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxAssignRunway::new(
+            &pk,
+            &pk,
+            "09L",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            &pk,
+            &pk,
+            true,
+            HEATING_SECONDS,
+            "All systems nominal",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.advance_time(i64::from(HEATING_SECONDS));
+
+    let result = chain.execute(TxStartFlying::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::OverMaxTakeoffWeight))
+    );
+    assert_eq!(chain.state(&pk), AirplaneState::HeatingEngine);
+}
+
+#[test]
+fn flight_log_records_completed_flights() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    complete_flight(&mut chain, &pk, &sk, 3_600);
+    let first_landing = chain.now;
+
+    chain.advance_time(i64::from(DEFAULT_COOLING_PERIOD_SECONDS));
+    chain
+        .execute(TxAssignRunway::new(
+            &pk,
+            &pk,
+            "09L",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            &pk,
+            &pk,
+            true,
+            HEATING_SECONDS,
+            "All systems nominal",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    complete_flight(&mut chain, &pk, &sk, 7_200);
+
+    let schema = Schema::new(&chain.fork);
+    let log: Vec<_> = schema.flight_log(&pk).iter().collect();
+    assert_eq!(log.len(), 2);
+    assert_eq!(log[0].flight_number(), 1);
+    assert_eq!(log[0].end_time(), first_landing);
+    assert_eq!(
+        log[0].start_time(),
+        first_landing - Duration::seconds(3_600)
+    );
+    assert_eq!(log[0].duration_seconds(), 3_600);
+    assert_eq!(log[1].flight_number(), 2);
+    assert_eq!(log[1].end_time(), chain.now);
+    assert_eq!(log[1].start_time(), chain.now - Duration::seconds(7_200));
+    assert_eq!(log[1].duration_seconds(), 7_200);
+}
+
+#[test]
+fn metadata_fields_are_set_updated_and_removed() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Tagged",
+            "A320",
+            "SN3200",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let metadata = |chain: &TestChain| {
+        Schema::new(&chain.fork)
+            .airplane(&pk)
+            .unwrap()
+            .metadata_fields()
+    };
+    let set = |chain: &mut TestChain, key: &str, value: &str| {
+        chain.execute(TxSetMetadataField::new(
+            &pk,
+            &pk,
+            key,
+            value,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+    };
+
+    set(&mut chain, "insurance_policy", "POL-1").unwrap();
+    set(&mut chain, "paint_scheme", "CLASSIC").unwrap();
+    set(&mut chain, "insurance_policy", "POL-2").unwrap();
+    let fields = metadata(&chain);
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields["insurance_policy"], "POL-2");
+
+    chain
+        .execute(TxRemoveMetadataField::new(
+            &pk,
+            &pk,
+            "paint_scheme",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(
+        metadata(&chain).keys().collect::<Vec<_>>(),
+        ["insurance_policy"]
+    );
+
+    let long_key = "k".repeat(MAX_METADATA_KEY_LEN + 1);
+    assert_eq!(
+        set(&mut chain, &long_key, "value"),
+        Err(ExecutionError::from(Error::InvalidMetadataField))
+    );
+}
+
+#[test]
+fn metadata_field_count_is_limited() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Tagged",
+            "A320",
+            "SN3200",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    for i in 0..MAX_METADATA_FIELDS {
+        chain
+            .execute(TxSetMetadataField::new(
+                &pk,
+                &pk,
+                &format!("tag{}", i),
+                "value",
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                &sk,
+            ))
+            .unwrap();
+    }
+
+    let result = chain.execute(TxSetMetadataField::new(
+        &pk,
+        &pk,
+        "one_too_many",
+        "value",
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::TooManyMetadataFields))
+    );
+
+    // Existing fields can still be updated at the limit.
+    chain
+        .execute(TxSetMetadataField::new(
+            &pk,
+            &pk,
+            "tag0",
+            "updated",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.metadata_fields()["tag0"], "updated");
+}
+
+/// Registers an airplane and requests a technical check that needs admin approval.
+fn request_technical_check(chain: &mut TestChain) -> (PublicKey, SecretKey) {
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Requester",
+            "A320",
+            "SN3201",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxRequestTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::AwaitingCheckApproval);
+    (pk, sk)
+}
+
+#[test]
+fn approved_check_request_starts_technical_check() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (pk, sk) = request_technical_check(&mut chain);
+
+    // The airplane cannot approve its own request.
+    let result = chain.execute(TxApproveCheckStart::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::NotAdmin)));
+
+    chain
+        .execute(TxApproveCheckStart::new(
+            &pk,
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+
+    let events: Vec<_> = Schema::new(&chain.fork).events(&pk).iter().collect();
+    assert_eq!(
+        events.last().unwrap().event_type(),
+        AirplaneEventType::TechnicalCheckStarted as u8
+    );
+}
+
+#[test]
+fn denied_check_request_returns_to_waiting_for_flight() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (pk, sk) = request_technical_check(&mut chain);
+
+    chain
+        .execute(TxDenyCheckRequest::new(
+            &pk,
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+
+    // The direct way still starts the check without approval.
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+}
+
+/// Registers an airplane of `airplane_type` seating 180, boards `passengers` and heats
+/// the engine for `HEATING_SECONDS`.
+fn board_and_heat(
+    chain: &mut TestChain,
+    airplane_type: AirplaneType,
+    passengers: u16,
+) -> (PublicKey, SecretKey) {
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Airliner",
+            "A320",
+            "SN3202",
+            airplane_type as u8,
+            0,
+            180,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxSetPassengerCount::new(
+            &pk,
+            &pk,
+            passengers,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.certify(&pk);
+    chain
+        .execute(TxAssignRunway::new(
+            &pk,
+            &pk,
+            "09L",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            &pk,
+            &pk,
+            true,
+            HEATING_SECONDS,
+            "All systems nominal",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    (pk, sk)
+}
+
+#[test]
+fn passenger_count_is_limited_by_capacity() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = board_and_heat(&mut chain, AirplaneType::Commercial, 0);
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxEndFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    let result = chain.execute(TxSetPassengerCount::new(
+        &pk,
+        &pk,
+        181,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::ExceedsPassengerCapacity))
+    );
+    chain
+        .execute(TxSetPassengerCount::new(
+            &pk,
+            &pk,
+            180,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+}
+
+#[test]
+fn only_commercial_airplanes_fly_with_passengers() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = board_and_heat(&mut chain, AirplaneType::Cargo, 2);
+
+    let result = chain.execute(TxStartFlying::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::PassengersNotAllowed))
+    );
+    assert_eq!(chain.state(&pk), AirplaneState::HeatingEngine);
+}
+
+#[test]
+fn passengers_disembark_on_landing() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = board_and_heat(&mut chain, AirplaneType::Commercial, 150);
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.passenger_count(), 150);
+
+    chain
+        .execute(TxEndFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.passenger_count(), 0);
+}
+
+#[test]
+fn health_status_reports_time_and_airplane_count() {
+    let mut chain = TestChain::new();
+    let (pk, _) = gen_keypair();
+    {
+        let mut schema = Schema::new(&mut chain.fork);
+        schema.schema_version_entry_mut().set(SCHEMA_VERSION);
+        schema.seed_test_airplane(&pk, "Healthy", AirplaneState::WaitingForFlight);
+    }
+
+    let status = HealthStatus::check(&chain.fork, SERVICE_NAME);
+
+    assert!(status.storage_ok);
+    assert!(status.time_oracle_ok);
+    assert_eq!(status.current_time, Some(chain.now));
+    assert_eq!(status.airplane_count, 1);
+}
+
+#[test]
+fn health_status_reports_outdated_storage() {
+    let chain = TestChain::new();
+
+    // Nothing initialized the service, so the data counts as the version 1 layout.
+    let status = HealthStatus::check(&chain.fork, SERVICE_NAME);
+
+    assert!(!status.storage_ok);
+    assert!(status.time_oracle_ok);
+}
+
+#[test]
+fn transactions_per_key_are_limited_per_block() {
+    let mut chain = TestChain::new();
+    let mut config = serde_json::to_value(AirplaneServiceConfig::default()).unwrap();
+    config["max_tx_per_block_per_key"] = json!(3);
+    chain.set_config(serde_json::from_value(config).unwrap());
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Spammer",
+            "A320",
+            "SN3203",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let set_metadata = |chain: &mut TestChain, value: &str| {
+        chain.execute(TxSetMetadataField::new(
+            &pk,
+            &pk,
+            "tag",
+            value,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+    };
+
+    set_metadata(&mut chain, "2").unwrap();
+    set_metadata(&mut chain, "3").unwrap();
+    for value in &["4", "5"] {
+        assert_eq!(
+            set_metadata(&mut chain, value),
+            Err(ExecutionError::from(Error::RateLimitExceeded))
+        );
+    }
+
+    chain.advance_blocks(1);
+    set_metadata(&mut chain, "6").unwrap();
+}
+
+#[test]
+fn global_timeline_is_chronological_across_airplanes() {
+    let mut chain = TestChain::new();
+    let start = chain.now;
+    let (first_pk, first_sk) = prepare_heated_airplane(&mut chain);
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    let (second_pk, second_sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &second_pk,
+            "Second",
+            "A320",
+            "SN3204",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &second_sk,
+        ))
+        .unwrap();
+    chain.advance_time(10);
+    chain
+        .execute(TxStartFlying::new(
+            &first_pk,
+            &first_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &first_sk,
+        ))
+        .unwrap();
+    chain.advance_time(10);
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &second_pk,
+            &second_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &second_sk,
+        ))
+        .unwrap();
+
+    let schema = Schema::new(&chain.fork);
+    let timeline = schema.global_timeline(start, chain.now);
+    let timestamps: Vec<_> = timeline
+        .iter()
+        .map(|entry| entry.audit_entry().timestamp())
+        .collect();
+    let mut sorted = timestamps.clone();
+    sorted.sort();
+    assert_eq!(timestamps, sorted);
+    let keys: Vec<_> = timeline
+        .iter()
+        .rev()
+        .take(3)
+        .map(|entry| *entry.airplane_pub_key())
+        .collect();
+    assert_eq!(keys, vec![second_pk, first_pk, second_pk]);
+
+    // Only the takeoff and the technical check of the second airplane are in the window.
+    let recent = schema.global_timeline(chain.now - Duration::seconds(10), chain.now);
+    assert_eq!(recent.len(), 2);
+    assert_eq!(
+        recent[0].audit_entry().new_state(),
+        u8::from(AirplaneState::Flying)
+    );
+    assert_eq!(
+        recent[1].audit_entry().new_state(),
+        u8::from(AirplaneState::TechnicalCheck)
+    );
+}
+
+#[test]
+fn integrity_check_reports_corrupt_airplanes() {
+    let mut chain = TestChain::new();
+    let (healthy_pk, healthy_sk) = prepare_heated_airplane(&mut chain);
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    chain
+        .execute(TxStartFlying::new(
+            &healthy_pk,
+            &healthy_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &healthy_sk,
+        ))
+        .unwrap();
+    let (unknown_pk, _) = gen_keypair();
+    let (heating_pk, _) = gen_keypair();
+    let (mislabeled_pk, _) = gen_keypair();
+    {
+        let mut schema = Schema::new(&mut chain.fork);
+        let seeded =
+            schema.seed_test_airplane(&unknown_pk, "Unknown", AirplaneState::WaitingForFlight);
+        let mut corrupt = serde_json::to_value(seeded).unwrap();
+        corrupt["state_number"] = json!(255);
+        let corrupt: Airplane = serde_json::from_value(corrupt).unwrap();
+        schema.airplanes_mut().put(&unknown_pk, corrupt);
+
+        let seeded = schema.seed_test_airplane(&heating_pk, "Heating", AirplaneState::Flying);
+        let corrupt = Airplane::builder_from(&seeded)
+            .heating_time_seconds(HEATING_SECONDS)
+            .build();
+        schema.airplanes_mut().put(&heating_pk, corrupt);
+
+        let seeded = schema.seed_test_airplane(
+            &mislabeled_pk,
+            "Mislabeled",
+            AirplaneState::WaitingForFlight,
+        );
+        let mut corrupt = serde_json::to_value(&seeded).unwrap();
+        corrupt["state_str"] = json!("Flying");
+        let corrupt: Airplane = serde_json::from_value(corrupt).unwrap();
+        schema.airplanes_mut().put(&mislabeled_pk, corrupt.clone());
+        // Rebuilding derives `state_str` from `state_number` again.
+        assert_eq!(Airplane::builder_from(&corrupt).build(), seeded);
+    }
+
+    let violations = Schema::new(&chain.fork).validate_integrity();
+
+    assert_eq!(violations.len(), 3, "{:?}", violations);
+    let violation = |pub_key| {
+        violations
+            .iter()
+            .find(|violation| violation.pub_key == pub_key)
+            .map(|violation| violation.violation.as_str())
+    };
+    assert_eq!(
+        violation(unknown_pk),
+        Some("state_number 255 is not a known state")
+    );
+    assert_eq!(
+        violation(heating_pk),
+        Some("engine_heating_time_seconds is 60 outside HeatingEngine")
+    );
+    assert_eq!(
+        violation(mislabeled_pk),
+        Some("state_str 'Flying' does not match state_number 0 (Waiting for flight)")
+    );
+    assert_eq!(violation(healthy_pk), None);
+}
+
+#[test]
+fn remaining_heating_seconds_counts_down_to_zero() {
+    let mut chain = TestChain::new();
+    let (pk, _) = prepare_heated_airplane(&mut chain);
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+
+    assert_eq!(
+        airplane.remaining_heating_seconds(chain.now),
+        Some(i64::from(HEATING_SECONDS))
+    );
+    assert_eq!(
+        airplane.remaining_heating_seconds(chain.now + Duration::seconds(20)),
+        Some(i64::from(HEATING_SECONDS) - 20)
+    );
+    assert_eq!(
+        airplane.remaining_heating_seconds(chain.now + Duration::hours(1)),
+        Some(0)
+    );
+
+    let (waiting_pk, _) = gen_keypair();
+    let waiting = Schema::new(&mut chain.fork).seed_test_airplane(
+        &waiting_pk,
+        "Waiting",
+        AirplaneState::WaitingForFlight,
+    );
+    assert_eq!(waiting.remaining_heating_seconds(chain.now), None);
+}
+
+#[test]
+fn uncertified_airplane_cannot_take_off() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (authority_pk, authority_sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAuthority::new(
+            &authority_pk,
+            "Civil Aviation Authority",
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Boeing",
+            "737",
+            "SN1235",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxAssignRunway::new(
+            &pk,
+            &pk,
+            "09L",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxEndTechnicalCheck::new(
+            &pk,
+            &pk,
+            true,
+            HEATING_SECONDS,
+            "",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.advance_time(i64::from(HEATING_SECONDS));
+
+    let result = chain.execute(TxStartFlying::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::AirworthinessCertificateExpired))
+    );
+
+    // Only registered authorities renew certificates.
+    let expiry = chain.now + Duration::days(365);
+    let result = chain.execute(TxRenewAirworthinessCertificate::new(
+        &pk,
+        expiry,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::UnknownAuthority)));
+
+    chain
+        .execute(TxRenewAirworthinessCertificate::new(
+            &pk,
+            expiry,
+            &authority_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &authority_sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::Flying);
+}
+
+#[test]
+fn revoked_authority_cannot_renew_certificates() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (authority_pk, authority_sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAuthority::new(
+            &authority_pk,
+            "Civil Aviation Authority",
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+    let (pk, _) = gen_keypair();
+    Schema::new(&mut chain.fork).seed_test_airplane(
+        &pk,
+        "Certified",
+        AirplaneState::WaitingForFlight,
+    );
+
+    let renew = |chain: &mut TestChain, days: i64| {
+        let expiry = chain.now + Duration::days(days);
+        chain.execute(TxRenewAirworthinessCertificate::new(
+            &pk,
+            expiry,
+            &authority_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &authority_sk,
+        ))
+    };
+    renew(&mut chain, 30).unwrap();
+
+    chain
+        .execute(TxRevokeAuthority::new(
+            &authority_pk,
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+    assert_eq!(
+        renew(&mut chain, 365),
+        Err(ExecutionError::from(Error::UnknownAuthority))
+    );
+    let schema = Schema::new(&chain.fork);
+    assert_eq!(
+        schema.airplane(&pk).unwrap().airworthiness_cert_expiry(),
+        chain.now + Duration::days(30)
+    );
+    let authority = schema.authority(&authority_pk).unwrap();
+    assert_eq!(authority.name(), "Civil Aviation Authority");
+    assert!(!authority.is_active());
+}
+
+#[test]
+fn pending_repairs_block_technical_check() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (pk, sk) = gen_keypair();
+    Schema::new(&mut chain.fork).seed_test_airplane(
+        &pk,
+        "Repaired",
+        AirplaneState::WaitingForFlight,
+    );
+
+    chain
+        .execute(TxMarkRepairRequired::new(
+            &pk,
+            RepairFlag::Engine as u8,
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.required_repairs(), vec![RepairFlag::Engine]);
+
+    let result = chain.execute(TxStartTechnicalCheck::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::RepairsRequired)));
+
+    chain
+        .execute(TxClearRepair::new(
+            &pk,
+            RepairFlag::Engine as u8,
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(airplane.required_repairs_bitmask(), 0);
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+}
+
+#[test]
+fn low_visibility_at_home_airport_prevents_takeoff() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    chain
+        .execute(TxSetHomeAirport::new(
+            &pk,
+            &pk,
+            "JFK",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    let record_weather = |chain: &mut TestChain, visibility_meters: u32| {
+        chain
+            .execute(TxRecordWeatherConditions::new(
+                "JFK",
+                visibility_meters,
+                25,
+                &admin_pk,
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                &admin_sk,
+            ))
+            .unwrap();
+    };
+    record_weather(&mut chain, DEFAULT_MIN_VISIBILITY_METERS - 1);
+    let result = chain.execute(TxStartFlying::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::WeatherBelowMinimums))
+    );
+    assert_eq!(chain.state(&pk), AirplaneState::HeatingEngine);
+
+    record_weather(&mut chain, DEFAULT_MIN_VISIBILITY_METERS);
+    let report = Schema::new(&chain.fork).weather_report("JFK").unwrap();
+    assert_eq!(report.visibility_meters(), DEFAULT_MIN_VISIBILITY_METERS);
+    assert_eq!(report.recorded_at(), chain.now);
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+}
+
+#[test]
+fn overlapping_flight_plans_are_rejected() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+    Schema::new(&mut chain.fork).seed_test_airplane(
+        &pk,
+        "Planned",
+        AirplaneState::WaitingForFlight,
+    );
+    let now = chain.now;
+    let hours = |hours: i64| now + Duration::hours(hours);
+    let plan = |departure: DateTime<Utc>, arrival: DateTime<Utc>| {
+        TxPlanFlight::new(
+            &pk,
+            &pk,
+            "JFK",
+            "LHR",
+            departure,
+            arrival,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        )
+    };
+    let (first, second, overlapping, reversed) = (
+        plan(hours(1), hours(8)),
+        plan(hours(8), hours(15)),
+        plan(hours(7), hours(9)),
+        plan(hours(20), hours(20)),
+    );
+
+    chain.execute(first).unwrap();
+    chain.execute(second).unwrap();
+    assert_eq!(
+        chain.execute(overlapping),
+        Err(ExecutionError::from(Error::FlightPlanConflict))
+    );
+    assert_eq!(
+        chain.execute(reversed),
+        Err(ExecutionError::from(Error::InvalidFlightPlan))
+    );
+
+    let schema = Schema::new(&chain.fork);
+    let plan_ids: Vec<_> = schema.airplane_flight_plans(&pk).iter().collect();
+    assert_eq!(plan_ids, vec![0, 1]);
+    let second = schema.flight_plans().get(&1).unwrap();
+    assert_eq!(second.planned_departure(), hours(8));
+    assert_eq!(second.arrival_iata(), "LHR");
+}
+
+#[test]
+fn expired_registration_blocks_technical_check() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (authority_pk, authority_sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAuthority::new(
+            &authority_pk,
+            "Civil Aviation Authority",
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Boeing",
+            "737",
+            "SN1236",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(
+        airplane.registration_expiry(),
+        chain.now + Duration::days(i64::from(DEFAULT_REGISTRATION_VALIDITY_DAYS))
+    );
+
+    chain.advance_time(Duration::days(i64::from(DEFAULT_REGISTRATION_VALIDITY_DAYS)).num_seconds());
+    let result = chain.execute(TxStartTechnicalCheck::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::RegistrationExpired))
+    );
+
+    let new_expiry = chain.now + Duration::days(365);
+    chain
+        .execute(TxRenewRegistration::new(
+            &pk,
+            new_expiry,
+            &authority_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &authority_sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+}
+
+fn assert_inactive(result: ExecutionResult) {
+    assert_eq!(result, Err(ExecutionError::from(Error::AirplaneIsInactive)));
+}
+
+#[test]
+fn inactive_airplane_rejects_transactions_until_reactivated() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (authority_pk, authority_sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAuthority::new(
+            &authority_pk,
+            "Civil Aviation Authority",
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Boeing",
+            "737",
+            "SN1237",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxAssignRunway::new(
+            &pk,
+            &pk,
+            "09L",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    chain
+        .execute(TxDeactivateAirplane::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::Inactive);
+    let deactivated_at = chain.now;
+    {
+        let schema = Schema::new(&chain.fork);
+        assert_eq!(
+            schema.airplane(&pk).unwrap().inactive_since(),
+            deactivated_at
+        );
+        assert_eq!(RunwaySchema::new(&chain.fork).assigned_runway(&pk), None);
+    }
+
+    let (other_pk, _) = gen_keypair();
+    assert_inactive(chain.execute(TxDeactivateAirplane::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxStartTechnicalCheck::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxRequestTechnicalCheck::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxScheduleTechnicalCheck::new(
+        &pk,
+        &pk,
+        chain.now + Duration::days(1),
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxAssignRunway::new(
+        &pk,
+        &pk,
+        "09L",
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxReleaseRunway::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxRefuelAirplane::new(
+        &pk,
+        &pk,
+        100,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxLoadCargo::new(
+        &pk,
+        &pk,
+        1000,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxSetPassengerCount::new(
+        &pk,
+        &pk,
+        10,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxUpdateAirplaneName::new(
+        &pk,
+        &pk,
+        "Stored Boeing",
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxTransferOwnership::new(
+        &pk,
+        &other_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxAssignOperator::new(
+        &pk,
+        &other_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxUnassignOperator::new(
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxSetMetadataField::new(
+        &pk,
+        &pk,
+        "hangar",
+        "H2",
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxRemoveMetadataField::new(
+        &pk,
+        &pk,
+        "hangar",
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxSetHomeAirport::new(
+        &pk,
+        &pk,
+        "JFK",
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxPlanFlight::new(
+        &pk,
+        &pk,
+        "JFK",
+        "LHR",
+        chain.now + Duration::days(1),
+        chain.now + Duration::days(1) + Duration::hours(7),
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxReportIncident::new(
+        &pk,
+        2,
+        "Hangar door scratch",
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    )));
+    assert_inactive(chain.execute(TxGroundAirplane::new(
+        &pk,
+        "Inspection",
+        &admin_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &admin_sk,
+    )));
+    assert_inactive(chain.execute(TxAssignPriority::new(
+        &pk,
+        MAX_PRIORITY,
+        &admin_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &admin_sk,
+    )));
+    assert_inactive(chain.execute(TxMarkRepairRequired::new(
+        &pk,
+        RepairFlag::Engine as u8,
+        &admin_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &admin_sk,
+    )));
+    assert_inactive(chain.execute(TxClearRepair::new(
+        &pk,
+        RepairFlag::Engine as u8,
+        &admin_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &admin_sk,
+    )));
+    assert_inactive(chain.execute(TxRenewAirworthinessCertificate::new(
+        &pk,
+        chain.now + Duration::days(365),
+        &authority_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &authority_sk,
+    )));
+    assert_inactive(chain.execute(TxRenewRegistration::new(
+        &pk,
+        chain.now + Duration::days(365),
+        &authority_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &authority_sk,
+    )));
+    assert_eq!(chain.state(&pk), AirplaneState::Inactive);
+
+    chain.advance_time(Duration::days(91).num_seconds());
+    let schema = Schema::new(&chain.fork);
+    let long_inactive = schema.inactive_airplanes(chain.now - Duration::days(90));
+    assert_eq!(long_inactive.len(), 1);
+    assert_eq!(long_inactive[0].pub_key(), &pk);
+    assert!(schema
+        .inactive_airplanes(chain.now - Duration::days(92))
+        .is_empty());
+
+    chain
+        .execute(TxReactivateAirplane::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+    assert_eq!(
+        Schema::new(&chain.fork)
+            .airplane(&pk)
+            .unwrap()
+            .inactive_since(),
+        EPOCH
+    );
+    let result = chain.execute(TxReactivateAirplane::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::InvalidStateTransition {
+            found: AirplaneState::WaitingForFlight,
+            required: &[AirplaneState::Inactive],
+        }))
+    );
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+}
+
+#[test]
+fn inactive_airplane_can_be_decommissioned() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Tupolev",
+            "154",
+            "SN1238",
+            AirplaneType::Commercial as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxDeactivateAirplane::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxDecommissionAirplane::new(
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::Decommissioned);
+    assert_eq!(Schema::new(&chain.fork).airplane_count(), 0);
+}
+
+fn batch_register(
+    chain: &mut TestChain,
+    operator: &(PublicKey, SecretKey),
+    pub_key: &PublicKey,
+    serial: &str,
+) -> ExecutionResult {
+    chain.execute(TxBatchRegisterAirplanes::new(
+        &operator.0,
+        vec![AirplaneRegistration::new(
+            pub_key,
+            &format!("Airplane {}", serial),
+            "A320",
+            serial,
+            AirplaneType::Commercial as u8,
+        )],
+        false,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &operator.1,
+    ))
+}
+
+#[test]
+fn operator_quota_limits_registrations() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let operator = gen_keypair();
+    chain
+        .execute(TxSetOperatorQuota::new(
+            &operator.0,
+            1,
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+
+    let (first_pk, first_sk) = gen_keypair();
+    batch_register(&mut chain, &operator, &first_pk, "SN2001").unwrap();
+    let quota = Schema::new(&chain.fork)
+        .operator_quota(&operator.0)
+        .unwrap();
+    assert_eq!(quota.max_airplanes(), 1);
+    assert_eq!(quota.current_count(), 1);
+
+    let (second_pk, _) = gen_keypair();
+    assert_eq!(
+        batch_register(&mut chain, &operator, &second_pk, "SN2002"),
+        Err(ExecutionError::from(Error::OperatorQuotaExceeded))
+    );
+    assert_eq!(Schema::new(&chain.fork).airplane(&second_pk), None);
+
+    // Decommissioning frees the slot.
+    chain
+        .execute(TxDecommissionAirplane::new(
+            &first_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &first_sk,
+        ))
+        .unwrap();
+    let schema = Schema::new(&chain.fork);
+    assert_eq!(
+        schema.operator_quota(&operator.0).unwrap().current_count(),
+        0
+    );
+    batch_register(&mut chain, &operator, &second_pk, "SN2002").unwrap();
+}
+
+#[test]
+fn time_in_each_state_is_accumulated() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    let heated_at = chain.now;
+
+    chain.advance_time(i64::from(HEATING_SECONDS) + 30);
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.advance_time(3600);
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(
+        airplane.last_state_change_time(),
+        heated_at + Duration::seconds(i64::from(HEATING_SECONDS) + 30)
+    );
+    // The current state includes the time since the last transition.
+    assert_eq!(
+        airplane.time_in_state(AirplaneState::Flying, chain.now),
+        3600
+    );
+
+    chain
+        .execute(TxEndFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.advance_time(10);
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(
+        airplane.last_state_change_time(),
+        chain.now - Duration::seconds(10)
+    );
+    let seconds_in = |state| airplane.time_in_state(state, chain.now);
+    assert_eq!(seconds_in(AirplaneState::TechnicalCheck), 0);
+    assert_eq!(
+        seconds_in(AirplaneState::HeatingEngine),
+        u64::from(HEATING_SECONDS) + 30
+    );
+    assert_eq!(seconds_in(AirplaneState::Flying), 3600);
+    assert_eq!(seconds_in(AirplaneState::WaitingForFlight), 10);
+    assert_eq!(seconds_in(AirplaneState::Maintenance), 0);
+    assert!(Schema::new(&chain.fork).validate_integrity().is_empty());
+}
+
+#[test]
+fn dispatch_readiness_lists_blocking_reasons() {
+    let mut chain = TestChain::new();
+    let keys: Vec<_> = (0..4).map(|_| gen_keypair()).collect();
+    for (i, (pk, sk)) in keys.iter().enumerate() {
+        chain
+            .execute(TxRegisterAirplane::new(
+                pk,
+                &format!("Airplane {}", i),
+                "Model",
+                &format!("SN000{}", i),
+                AirplaneType::Commercial as u8,
+                0,
+                0,
+                CREATED_AT_HEIGHT,
+                next_nonce(),
+                sk,
+            ))
+            .unwrap();
+    }
+    let (ready_pk, _) = keys[0];
+    let (empty_pk, _) = keys[1];
+    let (uncertified_pk, _) = keys[2];
+    let (checked_pk, ref checked_sk) = keys[3];
+    chain.certify(&ready_pk);
+    chain.certify(&empty_pk);
+    chain.certify(&checked_pk);
+    {
+        let mut schema = Schema::new(&mut chain.fork);
+        let empty = schema.airplane(&empty_pk).unwrap();
+        schema.airplanes_mut().put(
+            &empty_pk,
+            Airplane::builder_from(&empty).fuel_level_percent(0).build(),
+        );
+        let uncertified = schema.airplane(&uncertified_pk).unwrap();
+        schema.airplanes_mut().put(
+            &uncertified_pk,
+            Airplane::builder_from(&uncertified)
+                .required_repairs_bitmask(1 << RepairFlag::Engine as u8)
+                .build(),
+        );
+    }
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &checked_pk,
+            &checked_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            checked_sk,
+        ))
+        .unwrap();
+
+    let readiness = Schema::new(&chain.fork).dispatch_readiness(chain.now);
+    let reasons_of = |pk: &PublicKey| {
+        readiness
+            .iter()
+            .find(|readiness| readiness.airplane.pub_key() == pk)
+            .map(|readiness| readiness.blocking_reasons.clone())
+    };
+
+    assert_eq!(readiness.len(), 3);
+    assert_eq!(reasons_of(&ready_pk), Some(vec![]));
+    assert_eq!(
+        reasons_of(&empty_pk),
+        Some(vec![Error::InsufficientFuel.to_string()])
+    );
+    assert_eq!(
+        reasons_of(&uncertified_pk),
+        Some(vec![
+            Error::AirworthinessCertificateExpired.to_string(),
+            Error::RepairsRequired.to_string(),
+        ])
+    );
+    assert_eq!(reasons_of(&checked_pk), None);
+
+    chain.advance_time(Duration::days(i64::from(DEFAULT_REGISTRATION_VALIDITY_DAYS)).num_seconds());
+    let readiness = Schema::new(&chain.fork).dispatch_readiness(chain.now);
+    assert!(readiness.iter().all(|readiness| readiness
+        .blocking_reasons
+        .contains(&Error::RegistrationExpired.to_string())));
+}
+
+#[test]
+fn service_transactions_are_signed_with_service_key() {
+    let mut chain = TestChain::new();
+    let (pk, _) = prepare_heated_airplane(&mut chain);
+    let (service_pk, service_sk) = gen_keypair();
+    chain.set_validators(&[service_pk]);
+    assert!(
+        AirplaneService::service_transactions(&chain.fork, SERVICE_NAME, &service_pk, &service_sk).is_empty()
+    );
+
+    chain.advance_time(i64::from(DEFAULT_MAX_HEATING_WAIT_SECONDS) + 1);
+    let mut transactions =
+        AirplaneService::service_transactions(&chain.fork, SERVICE_NAME, &service_pk, &service_sk);
+
+    assert_eq!(transactions.len(), 1);
+    let tx = match transactions.remove(0) {
+        AirplaneTransactions::TxExpireHeating(tx) => tx,
+        tx => panic!("unexpected transaction {}", tx.type_name()),
+    };
+    assert_eq!(tx.pub_key(), &pk);
+    assert_eq!(tx.service_key(), &service_pk);
+    assert!(tx.verify_signature(&service_pk));
+    assert!(!tx.verify_signature(&pk));
+    chain.execute(tx).unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+}
+
+#[test]
+fn error_codes_are_stable() {
+    let (pk, _) = gen_keypair();
+    let cases = vec![
+        (Error::AirplaneAlreadyExists, 0),
+        (Error::AirplaneDoesNotExist(pk), 1),
+        (
+            Error::InvalidStateTransition {
+                found: AirplaneState::Flying,
+                required: &[AirplaneState::WaitingForFlight],
+            },
+            2,
+        ),
+        (Error::InvalidNonce, 16),
+        (
+            Error::TransitionNotAllowed {
+                from: AirplaneState::Flying,
+                to: AirplaneState::TechnicalCheck,
+            },
+            44,
+        ),
+        (Error::AirplaneIsInactive, 56),
+    ];
+    for (error, code) in cases {
+        assert_eq!(error.code(), code, "{}", error);
+        let description = format!("{}", error);
+        assert_eq!(
+            ExecutionError::from(error),
+            ExecutionError::with_description(code, description)
+        );
+    }
+}
+
+#[test]
+fn missing_airplane_error_names_the_key() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = gen_keypair();
+
+    let result = chain.execute(TxStartTechnicalCheck::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+
+    // `PublicKey` displays the first bytes of its hex representation.
+    let description = format!("{:?}", result.unwrap_err());
+    assert!(description.contains(&pk.to_hex()[..8]), "{}", description);
+}
+
+#[test]
+fn third_party_nonces_do_not_affect_the_airplane() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    let (reporter_pk, reporter_sk) = gen_keypair();
+
+    chain
+        .execute(TxReportIncident::new(
+            &pk,
+            1,
+            "Bird strike",
+            &reporter_pk,
+            CREATED_AT_HEIGHT,
+            u64::MAX,
+            &reporter_sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxGroundAirplane::new(
+            &pk,
+            "Engine inspection",
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            u64::MAX - 1,
+            &admin_sk,
+        ))
+        .unwrap();
+    chain
+        .execute(TxUngroundAirplane::new(
+            &pk,
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            u64::MAX,
+            &admin_sk,
+        ))
+        .unwrap();
+
+    // The reporter's own nonce is used up.
+    let result = chain.execute(TxReportIncident::new(
+        &pk,
+        1,
+        "Bird strike",
+        &reporter_pk,
+        CREATED_AT_HEIGHT,
+        u64::MAX,
+        &reporter_sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::InvalidNonce)));
+
+    // The owner can still transact with its own nonces.
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+}
+
+#[test]
+fn expire_heating_requires_validator_service_key() {
+    let mut chain = TestChain::new();
+    let (service_pk, _) = gen_keypair();
+    chain.set_validators(&[service_pk]);
+    let (pk, _) = prepare_heated_airplane(&mut chain);
+    let last_nonce = Schema::new(&chain.fork).last_used_nonce(&pk);
+    chain.advance_time(i64::from(DEFAULT_MAX_HEATING_WAIT_SECONDS) + 1);
+
+    let (other_pk, other_sk) = gen_keypair();
+    let tx = TxExpireHeating::new(&pk, &other_pk, CREATED_AT_HEIGHT, 0, &other_sk);
+    let result = chain.execute(tx);
+
+    assert_eq!(result, Err(ExecutionError::from(Error::NotValidator)));
+    assert_eq!(chain.state(&pk), AirplaneState::HeatingEngine);
+    assert_eq!(Schema::new(&chain.fork).last_used_nonce(&pk), last_nonce);
+}
+
+#[test]
+fn scheduled_check_requires_validator_service_key() {
+    let mut chain = TestChain::new();
+    let (service_pk, service_sk) = gen_keypair();
+    chain.set_validators(&[service_pk]);
+    let (pk, sk) = register_airplane(&mut chain);
+    chain
+        .execute(TxScheduleTechnicalCheck::new(
+            &pk,
+            &pk,
+            chain.now + Duration::hours(1),
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let last_nonce = Schema::new(&chain.fork).last_used_nonce(&pk);
+    chain.advance_time(3600);
+
+    let (other_pk, other_sk) = gen_keypair();
+    let tx = TxStartScheduledCheck::new(&pk, &other_pk, CREATED_AT_HEIGHT, 0, &other_sk);
+    let result = chain.execute(tx);
+    assert_eq!(result, Err(ExecutionError::from(Error::NotValidator)));
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+
+    let mut transactions =
+        AirplaneService::service_transactions(&chain.fork, SERVICE_NAME, &service_pk, &service_sk);
+    assert_eq!(transactions.len(), 1);
+    match transactions.remove(0) {
+        AirplaneTransactions::TxStartScheduledCheck(tx) => chain.execute(tx).unwrap(),
+        tx => panic!("unexpected transaction {}", tx.type_name()),
+    }
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+    assert_eq!(Schema::new(&chain.fork).last_used_nonce(&pk), last_nonce);
+}
+
+#[test]
+fn technical_check_signatures_are_checked() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = register_airplane(&mut chain);
+    let (operator_pk, operator_sk) = gen_keypair();
+    let (stranger_pk, stranger_sk) = gen_keypair();
+    chain
+        .execute(TxAssignOperator::new(
+            &pk,
+            &operator_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    // A key that is neither the airplane nor the named signer fails `verify`.
+    let forged = TxStartTechnicalCheck::new(
+        &pk,
+        &operator_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &stranger_sk,
+    );
+    assert!(!forged.verify());
+
+    // A stranger naming itself passes `verify` but is rejected in `execute`.
+    let result = chain.execute(TxStartTechnicalCheck::new(
+        &pk,
+        &stranger_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &stranger_sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::NotOwnerOrOperator)));
+
+    // So is an operator once it is unassigned.
+    chain
+        .execute(TxUnassignOperator::new(
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let result = chain.execute(TxStartTechnicalCheck::new(
+        &pk,
+        &operator_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &operator_sk,
+    ));
+    assert_eq!(result, Err(ExecutionError::from(Error::NotOwnerOrOperator)));
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+
+    let operator_signed = TxStartTechnicalCheck::new(
+        &pk,
+        &operator_pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &operator_sk,
+    );
+    assert!(operator_signed.verify());
+    chain
+        .execute(TxAssignOperator::new(
+            &pk,
+            &operator_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.execute(operator_signed).unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+}
+
+#[test]
+fn critical_incidents_need_a_trusted_reporter() {
+    let mut chain = TestChain::new();
+    let (admin_pk, admin_sk) = gen_keypair();
+    chain.set_admin(&admin_pk);
+    let (pk, sk) = register_airplane(&mut chain);
+    let report = |severity: u8, reporter_pk: &PublicKey, reporter_sk: &SecretKey| {
+        TxReportIncident::new(
+            &pk,
+            severity,
+            "Engine fire",
+            reporter_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            reporter_sk,
+        )
+    };
+
+    // Anyone can report a minor incident, but not a critical one.
+    let (stranger_pk, stranger_sk) = gen_keypair();
+    chain
+        .execute(report(MAX_INCIDENT_SEVERITY - 1, &stranger_pk, &stranger_sk))
+        .unwrap();
+    let result = chain.execute(report(MAX_INCIDENT_SEVERITY, &stranger_pk, &stranger_sk));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::UntrustedIncidentReporter))
+    );
+    assert_eq!(Schema::new(&chain.fork).incidents(&pk).len(), 1);
+
+    let (operator_pk, operator_sk) = gen_keypair();
+    chain
+        .execute(TxAssignOperator::new(
+            &pk,
+            &operator_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let (authority_pk, authority_sk) = gen_keypair();
+    chain
+        .execute(TxRegisterAuthority::new(
+            &authority_pk,
+            "Civil Aviation Authority",
+            &admin_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &admin_sk,
+        ))
+        .unwrap();
+    for (reporter_pk, reporter_sk) in &[
+        (admin_pk, admin_sk),
+        (operator_pk, operator_sk),
+        (authority_pk, authority_sk),
+    ] {
+        chain
+            .execute(report(MAX_INCIDENT_SEVERITY, reporter_pk, reporter_sk))
+            .unwrap();
+    }
+    assert_eq!(Schema::new(&chain.fork).incidents(&pk).len(), 4);
+}
+
+#[test]
+fn transfer_moves_the_airplane_and_its_history() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    complete_flight(&mut chain, &pk, &sk, 3_600);
+    let (new_pk, new_sk) = gen_keypair();
+    chain.set_admin(&new_pk);
+    for _ in 0..4 {
+        chain
+            .execute(TxReportIncident::new(
+                &pk,
+                5,
+                "Engine fire",
+                &new_pk,
+                CREATED_AT_HEIGHT,
+                // Ahead of the airplane's nonces, which the new key must not reuse.
+                next_nonce() + 1_000_000,
+                &new_sk,
+            ))
+            .unwrap();
+    }
+    let new_key_nonce = Schema::new(&chain.fork).last_used_nonce(&new_pk);
+    let old = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    let event_count = Schema::new(&chain.fork).events(&pk).len();
+    let audit_root = Schema::new(&chain.fork).audit_log(&pk).merkle_root();
+
+    let transfer_nonce = next_nonce();
+    chain
+        .execute(TxTransferOwnership::new(
+            &pk,
+            &new_pk,
+            CREATED_AT_HEIGHT,
+            transfer_nonce,
+            &sk,
+        ))
+        .unwrap();
+
+    {
+        let schema = Schema::new(&chain.fork);
+        assert!(schema.airplane(&pk).is_none());
+        let airplane = schema.airplane(&new_pk).unwrap();
+        assert_eq!(airplane.pub_key(), &new_pk);
+        assert_eq!(airplane.name(), old.name());
+        assert_eq!(airplane.serial_number(), old.serial_number());
+        assert_eq!(airplane.flight_count(), old.flight_count());
+        assert_eq!(schema.find_by_name(old.name()), Some(airplane.clone()));
+
+        assert!(new_key_nonce > transfer_nonce);
+        assert_eq!(schema.last_used_nonce(&new_pk), new_key_nonce);
+
+        assert_eq!(schema.flight_log(&new_pk).len(), 1);
+        assert_eq!(schema.incidents(&new_pk).len(), 4);
+        assert_eq!(schema.events(&new_pk).len(), event_count);
+        assert_eq!(schema.audit_log(&new_pk).merkle_root(), audit_root);
+        assert_eq!(schema.audit_roots().get(&new_pk), Some(audit_root));
+
+        assert_eq!(schema.flight_log(&pk).len(), 0);
+        assert_eq!(schema.incidents(&pk).len(), 0);
+        assert_eq!(schema.events(&pk).len(), 0);
+        assert_eq!(schema.audit_log(&pk).len(), 0);
+        assert_eq!(schema.audit_roots().get(&pk), None);
+    }
+
+    // The critical incidents still block the airplane under its new key.
+    let result = chain.execute(TxStartTechnicalCheck::new(
+        &new_pk,
+        &new_pk,
+        CREATED_AT_HEIGHT,
+        new_key_nonce + 1,
+        &new_sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::TooManyCriticalIncidents))
+    );
+
+    // The old key can register another airplane without inheriting the history.
+    chain
+        .execute(TxRegisterAirplane::new(
+            &pk,
+            "Cessna",
+            "172",
+            "SN9876",
+            AirplaneType::Private as u8,
+            0,
+            0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    let schema = Schema::new(&chain.fork);
+    assert_eq!(schema.incidents(&pk).len(), 0);
+    assert_eq!(schema.flight_log(&pk).len(), 0);
+}
+
+#[test]
+fn cleared_emergency_starts_the_cooling_period() {
+    let mut chain = TestChain::new();
+    let mut config = serde_json::to_value(AirplaneServiceConfig::with_admin(None)).unwrap();
+    config["cooling_period_seconds"] = json!(3_600);
+    chain.set_config(serde_json::from_value(config).unwrap());
+    let (pk, sk) = prepare_heated_airplane(&mut chain);
+    chain.advance_time(i64::from(HEATING_SECONDS));
+    chain
+        .execute(TxStartFlying::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.advance_time(600);
+    chain
+        .execute(TxEmergencyLanding::new(
+            &pk,
+            &pk,
+            "Cabin depressurization",
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    chain.advance_time(600);
+    chain
+        .execute(TxClearEmergency::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+
+    let airplane = Schema::new(&chain.fork).airplane(&pk).unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::WaitingForFlight);
+    assert_eq!(airplane.last_flight_end_time(), chain.now);
+
+    let result = chain.execute(TxStartTechnicalCheck::new(
+        &pk,
+        &pk,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(
+        result,
+        Err(ExecutionError::from(Error::CoolingPeriodNotElapsed))
+    );
+
+    chain.advance_time(3_600);
+    chain
+        .execute(TxStartTechnicalCheck::new(
+            &pk,
+            &pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &sk,
+        ))
+        .unwrap();
+    assert_eq!(chain.state(&pk), AirplaneState::TechnicalCheck);
+}
+
+#[test]
+fn airplanes_with_equal_fields_are_equal() {
+    let (pk, _) = gen_keypair();
+    let build = |state| {
+        AirplaneBuilder::new(&pk, "Boeing", "737", "SN1234")
+            .state(state)
+            .flight_start_time(Utc.timestamp_opt(1_500_000_000, 7).unwrap())
+            .build()
+    };
+
+    let airplane = build(AirplaneState::WaitingForFlight);
+    assert_eq!(airplane, build(AirplaneState::WaitingForFlight));
+    assert_eq!(airplane, airplane.clone());
+    assert_eq!(airplane, Airplane::builder_from(&airplane).build());
+
+    let flying = build(AirplaneState::Flying);
+    assert_ne!(airplane.state_number(), flying.state_number());
+    assert_ne!(airplane, flying);
+}
+
+fn airplanes_query(state: Option<&str>) -> AirplanesQuery {
+    AirplanesQuery {
+        state: state.map(str::to_owned),
+        expiring_within_days: None,
+        page: 0,
+        per_page: 50,
+    }
+}
+
+fn listed_keys(state: &ServiceApiState, airplane_state: Option<&str>) -> Vec<PublicKey> {
+    AirplaneApi::get_all_airplanes(state, SERVICE_NAME, airplanes_query(airplane_state))
+        .unwrap()
+        .items
+        .iter()
+        .map(|airplane| *airplane.pub_key())
+        .collect()
+}
+
+#[test]
+fn airplanes_are_listed_in_registration_order() {
+    let empty = TestChain::new().into_api_state();
+    let page = AirplaneApi::get_all_airplanes(&empty, SERVICE_NAME, airplanes_query(None)).unwrap();
+    assert_eq!(page.total, 0);
+    assert!(page.items.is_empty());
+
+    let mut chain = TestChain::new();
+    let mut keys = (0..5)
+        .map(|_| register_airplane(&mut chain))
+        .collect::<Vec<_>>();
+
+    // The transferred airplane keeps its place even though its key changes.
+    let (new_pk, _) = gen_keypair();
+    chain
+        .execute(TxTransferOwnership::new(
+            &keys[1].0,
+            &new_pk,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &keys[1].1,
+        ))
+        .unwrap();
+    keys[1].0 = new_pk;
+
+    chain
+        .execute(TxDecommissionAirplane::new(
+            &keys[3].0,
+            CREATED_AT_HEIGHT,
+            next_nonce(),
+            &keys[3].1,
+        ))
+        .unwrap();
+
+    let registered = keys.iter().map(|&(pk, _)| pk).collect::<Vec<_>>();
+    let active = vec![registered[0], registered[1], registered[2], registered[4]];
+    {
+        let schema = Schema::new(&chain.fork);
+        let page = schema.airplanes_page(PaginationParams::new(0, 50).unwrap());
+        let listed = page
+            .items
+            .iter()
+            .map(|airplane| *airplane.pub_key())
+            .collect::<Vec<_>>();
+        assert_eq!(listed, active);
+        assert_eq!(page.total, 4);
+    }
+
+    let state = chain.into_api_state();
+    assert_eq!(listed_keys(&state, None), active);
+    assert_eq!(listed_keys(&state, Some("Waiting for flight")), active);
+    assert_eq!(
+        listed_keys(&state, Some("Decommissioned")),
+        vec![registered[3]]
+    );
+}
+
+/// Posts `message` through the transaction endpoint handler and returns the field and
+/// detail of the `malformed_json` error it is rejected with.
+fn malformed_json_error(state: &ServiceApiState, message: Value) -> (String, String) {
+    match AirplaneApi::post_transaction_json(state, SERVICE_ID, message) {
+        Err(api::Error::BadRequest(body)) => {
+            let body: Value = serde_json::from_str(&body).unwrap();
+            assert_eq!(body["error"], "malformed_json");
+            (
+                body["field"].as_str().unwrap().to_owned(),
+                body["detail"].as_str().unwrap().to_owned(),
+            )
+        }
+        result => panic!("unexpected result {:?}", result.map(|_| ())),
+    }
+}
+
+#[test]
+fn transaction_endpoint_names_malformed_body_fields() {
+    let mut chain = TestChain::new();
+    let (pk, sk) = register_airplane(&mut chain);
+    let state = chain.into_api_state();
+    let message = serde_json::to_value(TxUpdateAirplaneName::new(
+        &pk,
+        &pk,
+        "Renamed",
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ))
+    .unwrap();
+
+    let mut missing = message.clone();
+    missing["body"].as_object_mut().unwrap().remove("new_name");
+    let (field, missing_detail) = malformed_json_error(&state, missing);
+    assert_eq!(field, "body.new_name");
+
+    let mut wrong_type = message.clone();
+    wrong_type["body"]["new_name"] = json!(42);
+    let (field, wrong_type_detail) = malformed_json_error(&state, wrong_type);
+    assert_eq!(field, "body.new_name");
+
+    let mut extra = message.clone();
+    extra["body"]["callsign"] = json!("ABC");
+    let (field, extra_detail) = malformed_json_error(&state, extra);
+    assert_eq!(field, "body.callsign");
+
+    assert_ne!(missing_detail, wrong_type_detail);
+    assert_ne!(wrong_type_detail, extra_detail);
+    assert_ne!(missing_detail, extra_detail);
+}
+
+#[test]
+fn service_instance_uses_its_service_id() {
+    let service = AirplaneService::default().with_service_id(99, "airplane_99");
+    assert_eq!(service.service_id(), 99);
+    assert_eq!(service.service_name(), "airplane_99");
+
+    let (pk, sk) = gen_keypair();
+    let register = AirplaneTransactions::TxRegisterAirplane(TxRegisterAirplane::new(
+        &pk,
+        "Instance Airplane",
+        "Model",
+        "SN990001",
+        AirplaneType::Commercial as u8,
+        0,
+        0,
+        CREATED_AT_HEIGHT,
+        next_nonce(),
+        &sk,
+    ));
+    assert_eq!(register.raw().service_id(), SERVICE_ID);
+    assert!(service.tx_from_raw(register.raw().clone()).is_err());
+
+    // Changing the ID without signing again invalidates the signature.
+    let unsigned = service
+        .tx_from_raw(register.with_service_id(99).raw().clone())
+        .unwrap();
+    assert!(!unsigned.verify());
+
+    let signed = register.signed_for_service(99, &sk);
+    assert_eq!(signed.raw().service_id(), 99);
+    let tx = service.tx_from_raw(signed.raw().clone()).unwrap();
+    assert_eq!(tx.raw().service_id(), 99);
+    assert!(tx.verify());
+
+    let mut chain = TestChain::new();
+    service.initialize(&mut chain.fork);
+    tx.execute(&mut chain.fork).unwrap();
+    // Each instance keeps its own indexes.
+    assert!(Schema::for_service(&chain.fork, "airplane_99").airplane(&pk).is_some());
+    assert!(Schema::new(&chain.fork).airplane(&pk).is_none());
+
+    // Messages posted as JSON carry the ID of the instance they are addressed to.
+    let message = serde_json::to_value(&signed).unwrap();
+    assert_eq!(message["service_id"], 99);
+    assert_eq!(
+        parse_transaction(message.clone(), 99).unwrap().raw(),
+        signed.raw()
+    );
+    assert_eq!(
+        parse_transaction(message, SERVICE_ID).unwrap_err().field,
+        "service_id"
+    );
+}
@@ -0,0 +1,97 @@
+//! Log records emitted while transactions execute.
+//!
+//! The logger is process-wide, so these checks live in their own test binary.
+
+extern crate airplane_service;
+extern crate chrono;
+extern crate exonum;
+extern crate exonum_time;
+extern crate log;
+
+use chrono::{TimeZone, Utc};
+use exonum::blockchain::Transaction;
+use exonum::crypto::gen_keypair;
+use exonum::storage::{Database, MemoryDB};
+use exonum_time::schema::TimeSchema;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use std::sync::Mutex;
+
+use airplane_service::schema::AirplaneType;
+use airplane_service::transactions::{TxRegisterAirplane, TxStartTechnicalCheck};
+
+/// Keeps every record from this crate as `"LEVEL message"`.
+struct CapturingLogger {
+    records: Mutex<Vec<String>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target().starts_with("airplane_service")
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.records
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", record.level(), record.args()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger {
+    records: Mutex::new(Vec::new()),
+};
+
+#[test]
+fn execute_logs_state_transitions_and_rejections() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let mut fork = MemoryDB::new().fork();
+    TimeSchema::new(&mut fork)
+        .time_mut()
+        .set(Utc.timestamp_opt(1_500_000_000, 0).unwrap());
+    let (pk, sk) = gen_keypair();
+    TxRegisterAirplane::new(
+        &pk,
+        "Boeing",
+        "737",
+        "SN1234",
+        AirplaneType::Commercial as u8,
+        0,
+        0,
+        0,
+        1,
+        &sk,
+    )
+    .execute(&mut fork)
+    .unwrap();
+    TxStartTechnicalCheck::new(&pk, &pk, 0, 2, &sk)
+        .execute(&mut fork)
+        .unwrap();
+    assert!(TxStartTechnicalCheck::new(&pk, &pk, 0, 3, &sk)
+        .execute(&mut fork)
+        .is_err());
+
+    let records = LOGGER.records.lock().unwrap();
+    let contains = |expected: &str| records.iter().any(|record| record == expected);
+    assert!(contains(&format!(
+        "INFO [TxRegisterAirplane] airplane={:?} created in WaitingForFlight",
+        pk
+    )));
+    assert!(contains(&format!(
+        "INFO [TxStartTechnicalCheck] airplane={:?} transitioned WaitingForFlight -> TechnicalCheck",
+        pk
+    )));
+    assert!(records.iter().any(|record| record.starts_with(&format!(
+        "WARN [TxStartTechnicalCheck] airplane={:?} rejected",
+        pk
+    ))));
+    assert!(records
+        .iter()
+        .any(|record| record.starts_with(&format!("{} Reading airplane", Level::Debug))));
+}
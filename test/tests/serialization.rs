@@ -0,0 +1,511 @@
+//! JSON round trips of the transactions accepted by the REST API.
+
+extern crate airplane_service;
+extern crate exonum;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use exonum::api;
+use exonum::blockchain::Service;
+use exonum::crypto::gen_keypair;
+use exonum::messages::{MessageWriter, RawTransaction};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use airplane_service::schema::{AirplaneBuilder, AirplaneType};
+use airplane_service::service::{
+    camel_case_keys, from_request_body, parse_transaction, snake_case_keys, AirplaneDtoV2,
+    AirplaneResponse, AirplaneResponseV2, AirplaneService, TransactionDeserializationError,
+    TxRegisterAirplaneRequest, SERVICE_ID,
+};
+use airplane_service::transactions::{
+    AirplaneTransactions, TxEndFlying, TxEndTechnicalCheck, TxRegisterAirplane, TxStartFlying,
+    TxStartTechnicalCheck, MAX_MESSAGE_ID,
+};
+
+/// Serializes `tx`, checks that it deserializes back to the same value and
+/// returns the JSON of its body.
+fn round_trip<T>(tx: &T) -> Value
+where
+    T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    let value = serde_json::to_value(tx).unwrap();
+    let decoded: T = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(&decoded, tx);
+
+    assert!(value["signature"].is_string());
+    value["body"].clone()
+}
+
+#[test]
+fn register_airplane() {
+    let (pk, sk) = gen_keypair();
+    let tx = TxRegisterAirplane::new(
+        &pk,
+        "Boeing",
+        "737-800",
+        "SN1234",
+        AirplaneType::Commercial as u8,
+        0,
+        0,
+        0,
+        1,
+        &sk,
+    );
+
+    assert_eq!(
+        round_trip(&tx),
+        json!({
+            "pub_key": pk.to_hex(),
+            "name": "Boeing",
+            "model": "737-800",
+            "serial_number": "SN1234",
+            "airplane_type": 0,
+            "max_takeoff_weight_kg": 0,
+            "max_passenger_capacity": 0,
+            "created_at_height": "0",
+            "nonce": "1",
+        })
+    );
+}
+
+#[test]
+fn start_technical_check() {
+    let (pk, sk) = gen_keypair();
+    let tx = TxStartTechnicalCheck::new(&pk, &pk, 0, 1, &sk);
+
+    assert_eq!(
+        round_trip(&tx),
+        json!({
+            "pub_key": pk.to_hex(),
+            "signer": pk.to_hex(),
+            "created_at_height": "0",
+            "nonce": "1",
+        })
+    );
+}
+
+#[test]
+fn end_technical_check_passed() {
+    let (pk, sk) = gen_keypair();
+    let tx = TxEndTechnicalCheck::new(&pk, &pk, true, 120, "All systems nominal", 0, 1, &sk);
+
+    assert_eq!(
+        round_trip(&tx),
+        json!({
+            "pub_key": pk.to_hex(),
+            "signer": pk.to_hex(),
+            "is_airplane_ok": true,
+            "engine_heating_time_seconds": 120,
+            "technician_notes": "All systems nominal",
+            "created_at_height": "0",
+            "nonce": "1",
+        })
+    );
+}
+
+#[test]
+fn end_technical_check_failed() {
+    let (pk, sk) = gen_keypair();
+    let tx = TxEndTechnicalCheck::new(&pk, &pk, false, 0, "Hydraulic leak", 0, 1, &sk);
+
+    assert_eq!(
+        round_trip(&tx),
+        json!({
+            "pub_key": pk.to_hex(),
+            "signer": pk.to_hex(),
+            "is_airplane_ok": false,
+            "engine_heating_time_seconds": 0,
+            "technician_notes": "Hydraulic leak",
+            "created_at_height": "0",
+            "nonce": "1",
+        })
+    );
+}
+
+#[test]
+fn start_flying() {
+    let (pk, sk) = gen_keypair();
+    let tx = TxStartFlying::new(&pk, &pk, 0, 1, &sk);
+
+    assert_eq!(
+        round_trip(&tx),
+        json!({
+            "pub_key": pk.to_hex(),
+            "signer": pk.to_hex(),
+            "created_at_height": "0",
+            "nonce": "1",
+        })
+    );
+}
+
+#[test]
+fn end_flying() {
+    let (pk, sk) = gen_keypair();
+    let tx = TxEndFlying::new(&pk, &pk, 0, 1, &sk);
+
+    assert_eq!(
+        round_trip(&tx),
+        json!({
+            "pub_key": pk.to_hex(),
+            "signer": pk.to_hex(),
+            "created_at_height": "0",
+            "nonce": "1",
+        })
+    );
+}
+
+/// The POST endpoints report the type of the transaction parsed from the body.
+#[test]
+fn transaction_type_names() {
+    let (pk, sk) = gen_keypair();
+    let bodies = vec![
+        (
+            serde_json::to_value(TxRegisterAirplane::new(
+                &pk,
+                "Boeing",
+                "737",
+                "SN1234",
+                AirplaneType::Commercial as u8,
+                0,
+                0,
+                0,
+                1,
+                &sk,
+            )),
+            "TxRegisterAirplane",
+        ),
+        (
+            serde_json::to_value(TxStartTechnicalCheck::new(&pk, &pk, 0, 1, &sk)),
+            "TxStartTechnicalCheck",
+        ),
+        (
+            serde_json::to_value(TxEndTechnicalCheck::new(&pk, &pk, true, 60, "", 0, 1, &sk)),
+            "TxEndTechnicalCheck",
+        ),
+        (
+            serde_json::to_value(TxStartFlying::new(&pk, &pk, 0, 1, &sk)),
+            "TxStartFlying",
+        ),
+        (
+            serde_json::to_value(TxEndFlying::new(&pk, &pk, 0, 1, &sk)),
+            "TxEndFlying",
+        ),
+    ];
+
+    for (body, expected) in bodies {
+        let tx: AirplaneTransactions = serde_json::from_value(body.unwrap()).unwrap();
+        assert_eq!(tx.type_name(), expected);
+    }
+}
+
+#[test]
+fn unknown_transaction_type() {
+    let (pk, sk) = gen_keypair();
+    let service = AirplaneService::new((pk, sk.clone()));
+    let raw =
+        RawTransaction::new(MessageWriter::new(0, SERVICE_ID, MAX_MESSAGE_ID + 1, 0).sign(&sk));
+
+    let error = service.tx_from_raw(raw).unwrap_err().to_string();
+
+    assert!(
+        error.contains(&format!("service {}", SERVICE_ID)),
+        "{}",
+        error
+    );
+    assert!(
+        error.contains(&format!("0 to {}", MAX_MESSAGE_ID)),
+        "{}",
+        error
+    );
+}
+
+/// `v1/airplanes/register` takes the plain fields and signature, without a message header.
+#[test]
+fn typed_register_request() {
+    let (pk, sk) = gen_keypair();
+    let tx = TxRegisterAirplane::new(
+        &pk,
+        "Boeing",
+        "737-800",
+        "SN1234",
+        AirplaneType::Commercial as u8,
+        0,
+        0,
+        0,
+        1,
+        &sk,
+    );
+
+    let body = json!({
+        "pub_key": pk.to_hex(),
+        "name": "Boeing",
+        "model": "737-800",
+        "serial_number": "SN1234",
+        "airplane_type": 0,
+        "max_takeoff_weight_kg": 0,
+            "max_passenger_capacity": 0,
+        "created_at_height": 0,
+        "nonce": 1,
+        "signature": serde_json::to_value(tx.clone()).unwrap()["signature"],
+    });
+    let request: TxRegisterAirplaneRequest = serde_json::from_value(body).unwrap();
+
+    assert_eq!(TxRegisterAirplane::from(request), tx);
+}
+
+#[test]
+fn register_description() {
+    let (pk, sk) = gen_keypair();
+    let tx = TxRegisterAirplane::new(
+        &pk,
+        "Boeing",
+        "737-800",
+        "SN1234",
+        AirplaneType::Commercial as u8,
+        0,
+        0,
+        0,
+        1,
+        &sk,
+    );
+    let tx = AirplaneTransactions::TxRegisterAirplane(tx);
+
+    let description = tx.description().unwrap();
+
+    assert_eq!(description["action"], "register");
+    assert_eq!(description["airplane_name"], "Boeing");
+    assert_eq!(description["owner"], json!(pk.to_hex()));
+}
+
+/// `v1/airplane` adds the computed fields next to the stored ones.
+#[test]
+fn airplane_response_flattens_airplane() {
+    let (pk, _) = gen_keypair();
+    let airplane = AirplaneBuilder::new(&pk, "Boeing", "737-800", "SN1234").build();
+    let response = AirplaneResponse {
+        airplane,
+        remaining_heating_seconds: Some(42),
+        time_in_state_seconds: vec![("Flying".to_owned(), 7200)].into_iter().collect(),
+    };
+
+    let value = serde_json::to_value(&response).unwrap();
+
+    assert_eq!(value["name"], "Boeing");
+    assert_eq!(value["pub_key"], json!(pk.to_hex()));
+    assert_eq!(value["remaining_heating_seconds"], 42);
+    assert_eq!(value["time_in_state_seconds"]["Flying"], 7200);
+}
+
+/// `v2/airplane` serves the same airplane with camelCase names; `v1/airplane` keeps snake_case.
+#[test]
+fn v2_airplane_uses_camel_case() {
+    let (pk, _) = gen_keypair();
+    let response = || AirplaneResponse {
+        airplane: AirplaneBuilder::new(&pk, "Boeing", "737-800", "SN1234").build(),
+        remaining_heating_seconds: None,
+        time_in_state_seconds: vec![("Waiting for flight".to_owned(), 60)]
+            .into_iter()
+            .collect(),
+    };
+
+    let v1 = serde_json::to_value(response()).unwrap();
+    let v2 = serde_json::to_value(AirplaneResponseV2::from(response())).unwrap();
+
+    assert_eq!(v1["pub_key"], json!(pk.to_hex()));
+    assert!(v1.get("pubKey").is_none());
+    assert_eq!(v2["pubKey"], json!(pk.to_hex()));
+    assert!(v2.get("pub_key").is_none());
+    assert_eq!(v2["stateStr"], "Waiting for flight");
+    assert_eq!(v2["stateNumber"], 0);
+    assert_eq!(v2["timeInStateSeconds"]["Waiting for flight"], 60);
+}
+
+/// `AirplaneDtoV2` copies the fields of `Airplane` by hand; a field added to one of them
+/// must be added to the other.
+#[test]
+fn v2_airplane_has_every_airplane_field() {
+    let (pk, _) = gen_keypair();
+    let airplane = AirplaneBuilder::new(&pk, "Boeing", "737-800", "SN1234").build();
+    let keys =
+        |value: Value| -> BTreeSet<String> { value.as_object().unwrap().keys().cloned().collect() };
+
+    let v1 = camel_case_keys(serde_json::to_value(&airplane).unwrap());
+    let v2 = serde_json::to_value(AirplaneDtoV2::from(&airplane)).unwrap();
+
+    assert_eq!(keys(v2), keys(v1));
+}
+
+/// The remaining `v2/` endpoints rename the keys of the `v1/` responses and bodies.
+#[test]
+fn v2_key_conversion() {
+    let v1 = json!({
+        "storage_ok": true,
+        "by_state": { "Waiting for flight": 1 },
+        "items": [{ "tx_hash": "00" }],
+    });
+
+    let v2 = camel_case_keys(v1.clone());
+
+    assert_eq!(
+        v2,
+        json!({
+            "storageOk": true,
+            "byState": { "Waiting for flight": 1 },
+            "items": [{ "txHash": "00" }],
+        })
+    );
+    assert_eq!(snake_case_keys(v2), v1);
+}
+
+/// A signed `v1/airplanes/register` body.
+fn register_body() -> Value {
+    let (pk, sk) = gen_keypair();
+    let tx = TxRegisterAirplane::new(
+        &pk,
+        "Boeing",
+        "737-800",
+        "SN1234",
+        AirplaneType::Commercial as u8,
+        0,
+        0,
+        0,
+        1,
+        &sk,
+    );
+    let mut body = serde_json::to_value(tx).unwrap();
+    let signature = body["signature"].take();
+    let mut body = body["body"].take();
+    body["signature"] = signature;
+    // The request takes plain numbers rather than the strings Exonum uses for `u64`.
+    body["created_at_height"] = json!(0);
+    body["nonce"] = json!(1);
+    body
+}
+
+fn register_error(body: Value) -> TransactionDeserializationError {
+    from_request_body::<TxRegisterAirplaneRequest>(body).unwrap_err()
+}
+
+#[test]
+fn malformed_register_requests_name_the_field() {
+    assert!(from_request_body::<TxRegisterAirplaneRequest>(register_body()).is_ok());
+
+    let mut missing = register_body();
+    missing.as_object_mut().unwrap().remove("name");
+    let missing = register_error(missing);
+    assert_eq!(missing.field, "name");
+    assert_eq!(missing.message, "missing field");
+
+    let mut wrong_type = register_body();
+    wrong_type["name"] = json!(737);
+    let wrong_type = register_error(wrong_type);
+    assert_eq!(wrong_type.field, "name");
+    assert!(
+        wrong_type.message.contains("expected a string"),
+        "{}",
+        wrong_type.message
+    );
+
+    let mut extra = register_body();
+    extra["color"] = json!("white");
+    let extra = register_error(extra);
+    assert_eq!(extra.field, "color");
+    assert!(
+        extra.message.starts_with("unknown field"),
+        "{}",
+        extra.message
+    );
+
+    assert_ne!(missing.message, wrong_type.message);
+    assert_ne!(wrong_type.message, extra.message);
+
+    let whole = register_error(json!([]));
+    assert_eq!(whole.field, "");
+}
+
+#[test]
+fn malformed_body_is_a_bad_request() {
+    let mut body = register_body();
+    body["nonce"] = json!("one");
+
+    match api::Error::from(register_error(body)) {
+        api::Error::BadRequest(message) => {
+            let message: Value = serde_json::from_str(&message).unwrap();
+            assert_eq!(message["error"], "malformed_json");
+            assert_eq!(message["field"], "nonce");
+            assert!(message["detail"].is_string());
+        }
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+/// Messages posted to `v1/transactions` are checked header field by header field.
+#[test]
+fn malformed_transactions_name_the_field() {
+    let (pk, sk) = gen_keypair();
+    let message = serde_json::to_value(TxStartFlying::new(&pk, &pk, 0, 1, &sk)).unwrap();
+    let error = |change: &dyn Fn(&mut Value)| {
+        let mut message = message.clone();
+        change(&mut message);
+        parse_transaction(message, SERVICE_ID).unwrap_err()
+    };
+
+    assert_eq!(
+        parse_transaction(message.clone(), SERVICE_ID)
+            .unwrap()
+            .type_name(),
+        "TxStartFlying"
+    );
+    assert_eq!(
+        error(&|m| {
+            m.as_object_mut().unwrap().remove("signature");
+        })
+        .field,
+        "signature"
+    );
+    assert_eq!(error(&|m| m["message_id"] = json!("3")).field, "message_id");
+    assert_eq!(
+        error(&|m| m["message_id"] = json!(MAX_MESSAGE_ID + 1)).field,
+        "message_id"
+    );
+    assert_eq!(error(&|m| m["service_id"] = json!(0)).field, "service_id");
+    assert_eq!(error(&|m| m["extra"] = json!(0)).field, "extra");
+    assert_eq!(
+        error(&|m| m["body"]["pub_key"] = json!(1)).field,
+        "body.pub_key"
+    );
+}
+
+/// Errors in a message body name the field as `body.<field>` and say what is wrong with it.
+#[test]
+fn malformed_transaction_bodies_name_the_field() {
+    let (pk, sk) = gen_keypair();
+    let message = serde_json::to_value(TxStartFlying::new(&pk, &pk, 0, 1, &sk)).unwrap();
+    let error = |change: &dyn Fn(&mut Value)| {
+        let mut message = message.clone();
+        change(&mut message);
+        parse_transaction(message, SERVICE_ID).unwrap_err()
+    };
+
+    let missing = error(&|m| {
+        m["body"].as_object_mut().unwrap().remove("signer");
+    });
+    assert_eq!(missing.field, "body.signer");
+    assert_eq!(missing.message, "missing field");
+
+    let wrong_type = error(&|m| m["body"]["nonce"] = json!(1));
+    assert_eq!(wrong_type.field, "body.nonce");
+    assert_ne!(wrong_type.message, missing.message);
+
+    let unknown = error(&|m| m["body"]["altitude"] = json!("1000"));
+    assert_eq!(unknown.field, "body.altitude");
+    assert!(unknown.message.starts_with("unknown field"));
+    assert!(unknown.message.contains("signer"));
+    assert_ne!(unknown.message, wrong_type.message);
+
+    assert_eq!(error(&|m| m["body"] = json!([])).field, "body");
+}
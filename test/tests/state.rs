@@ -0,0 +1,114 @@
+//! Conversions of `AirplaneState` to and from its stored number, its name and JSON.
+
+extern crate airplane_service;
+#[macro_use]
+extern crate serde_json;
+
+use airplane_service::schema::AirplaneState::{self, *};
+use airplane_service::transactions::Error;
+
+use std::convert::TryFrom;
+
+#[test]
+fn state_numbers_round_trip() {
+    for &state in &[WaitingForFlight, TechnicalCheck, HeatingEngine, Flying] {
+        assert_eq!(AirplaneState::try_from(u8::from(state)).unwrap(), state);
+    }
+}
+
+#[test]
+fn unknown_state_numbers_are_rejected() {
+    for &value in &[42, u8::MAX] {
+        match AirplaneState::try_from(value) {
+            Err(Error::InvalidState) => {}
+            result => panic!("state number {} parsed as {:?}", value, result),
+        }
+    }
+}
+
+#[test]
+fn states_display_their_names() {
+    assert_eq!(format!("{}", HeatingEngine), "Heating engine");
+    assert_eq!(WaitingForFlight.to_string(), "Waiting for flight");
+    let mut names: Vec<String> = AirplaneState::all()
+        .into_iter()
+        .map(|state| state.to_string())
+        .collect();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), AirplaneState::all().len());
+}
+
+#[test]
+fn states_parse_from_names_ignoring_case() {
+    for state in AirplaneState::all() {
+        let name = state.to_string();
+        assert_eq!(name.parse::<AirplaneState>(), Ok(state));
+        assert_eq!(name.to_lowercase().parse::<AirplaneState>(), Ok(state));
+        assert_eq!(name.to_uppercase().parse::<AirplaneState>(), Ok(state));
+    }
+    assert_eq!("fLyInG".parse::<AirplaneState>(), Ok(Flying));
+
+    for input in &["", "Fly", " Flying", "Flying ", "WaitingForFlight", "3"] {
+        let error = input.parse::<AirplaneState>().unwrap_err();
+        assert!(error.contains(&format!("'{}'", input)), "{}", error);
+        assert!(
+            error.contains("Waiting for flight, Technical check"),
+            "{}",
+            error
+        );
+    }
+}
+
+#[test]
+fn states_round_trip_through_json() {
+    for state in AirplaneState::all() {
+        let value = serde_json::to_value(state).unwrap();
+        assert_eq!(value, json!(state.to_string()));
+        let decoded: AirplaneState = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, state);
+
+        // Numeric discriminants are still accepted, as numbers or strings.
+        let number = u8::from(state);
+        assert_eq!(
+            serde_json::from_value::<AirplaneState>(json!(number)).unwrap(),
+            state
+        );
+        assert_eq!(
+            serde_json::from_value::<AirplaneState>(json!(number.to_string())).unwrap(),
+            state
+        );
+    }
+    assert_eq!(
+        serde_json::from_value::<AirplaneState>(json!("flying")).unwrap(),
+        Flying
+    );
+
+    for invalid in &[
+        json!(99),
+        json!(-1),
+        json!(256),
+        json!("Landing"),
+        json!(null),
+    ] {
+        assert!(
+            serde_json::from_value::<AirplaneState>(invalid.clone()).is_err(),
+            "{}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn invalid_transition_names_both_states() {
+    let error = Error::InvalidStateTransition {
+        found: HeatingEngine,
+        required: &[WaitingForFlight, Flying],
+    };
+    assert_eq!(error.code(), 2);
+    let description = error.to_string();
+    assert_eq!(
+        description,
+        "Cannot transition from Heating engine; required one of [WaitingForFlight, Flying]"
+    );
+}
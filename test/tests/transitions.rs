@@ -0,0 +1,96 @@
+//! The transition table checked against the expected state machine, cell by cell.
+
+extern crate airplane_service;
+
+use airplane_service::schema::AirplaneState::{self, *};
+use airplane_service::transitions::{allowed_transactions, can_transition};
+
+use std::convert::TryFrom;
+
+/// Pairs of distinct states connected by some transaction.
+const STATE_CHANGES: &[(AirplaneState, AirplaneState)] = &[
+    (WaitingForFlight, TechnicalCheck),
+    (WaitingForFlight, Decommissioned),
+    (WaitingForFlight, Grounded),
+    (TechnicalCheck, HeatingEngine),
+    (TechnicalCheck, WaitingForFlight),
+    (TechnicalCheck, TechnicalCheckExtended),
+    (TechnicalCheck, Grounded),
+    (TechnicalCheckExtended, HeatingEngine),
+    (TechnicalCheckExtended, WaitingForFlight),
+    (TechnicalCheckExtended, Grounded),
+    (HeatingEngine, Flying),
+    (HeatingEngine, WaitingForFlight),
+    (HeatingEngine, Grounded),
+    (Flying, WaitingForFlight),
+    (Flying, Maintenance),
+    (Flying, Emergency),
+    (Flying, Grounded),
+    (Maintenance, WaitingForFlight),
+    (Maintenance, Grounded),
+    (Emergency, WaitingForFlight),
+    (Emergency, Grounded),
+    (Grounded, WaitingForFlight),
+    (WaitingForFlight, AwaitingCheckApproval),
+    (AwaitingCheckApproval, TechnicalCheck),
+    (AwaitingCheckApproval, WaitingForFlight),
+    (AwaitingCheckApproval, Grounded),
+    (WaitingForFlight, Inactive),
+    (Inactive, WaitingForFlight),
+    (Inactive, Decommissioned),
+];
+
+#[test]
+fn transition_matrix() {
+    for from in AirplaneState::all() {
+        for to in AirplaneState::all() {
+            // Every state but `Inactive` accepts a transaction that keeps it, such as
+            // `TxReleaseRunway` or, while flying, `TxRecordFlightRoute`.
+            let expected = (from == to && from != Inactive) || STATE_CHANGES.contains(&(from, to));
+            assert_eq!(can_transition(from, to), expected, "{} -> {}", from, to);
+        }
+    }
+}
+
+#[test]
+fn decommissioned_is_final() {
+    assert_eq!(
+        allowed_transactions(Decommissioned),
+        vec!["TxReleaseRunway"]
+    );
+}
+
+#[test]
+fn inactive_only_allows_reactivation_or_decommissioning() {
+    assert_eq!(
+        allowed_transactions(Inactive),
+        vec!["TxReactivateAirplane", "TxDecommissionAirplane"]
+    );
+}
+
+#[test]
+fn allowed_transactions_while_heating() {
+    assert_eq!(
+        allowed_transactions(HeatingEngine),
+        vec![
+            "TxStartFlying",
+            "TxExpireHeating",
+            "TxUpdateEngineHeatingTime",
+            "TxGroundAirplane",
+            "TxUpdateAirplaneName",
+            "TxReleaseRunway",
+            "TxSetMetadataField",
+            "TxRemoveMetadataField",
+        ]
+    );
+}
+
+#[test]
+fn state_numbers_match_discriminants() {
+    assert_eq!(u8::from(Flying), 3u8);
+    assert_eq!(u16::from(Flying), 3u16);
+    assert_eq!(u32::from(AwaitingCheckApproval), 9u32);
+    for state in AirplaneState::all() {
+        assert_eq!(AirplaneState::try_from(u8::from(state)).unwrap(), state);
+    }
+}
@@ -0,0 +1,33 @@
+//! Field checks shared by several transactions.
+
+extern crate airplane_service;
+
+use airplane_service::transactions::Error;
+use airplane_service::validation::{validate_iata_code, validate_known_iata_code, KNOWN_AIRPORTS};
+
+#[test]
+fn iata_code_format() {
+    assert!(validate_iata_code("JFK").is_ok());
+    for &code in &["", "jfk", "JFKX", "J1K", "ÄÖÜ"] {
+        match validate_iata_code(code) {
+            Err(Error::InvalidIataCode) => {}
+            other => panic!("{:?} for {:?}", other, code),
+        }
+    }
+}
+
+#[test]
+fn known_airports() {
+    let mut sorted = KNOWN_AIRPORTS.to_vec();
+    sorted.sort();
+    assert_eq!(sorted, KNOWN_AIRPORTS);
+    for &code in KNOWN_AIRPORTS {
+        assert!(validate_iata_code(code).is_ok(), "{}", code);
+    }
+
+    assert!(validate_known_iata_code("JFK").is_ok());
+    match validate_known_iata_code("XYZ") {
+        Err(Error::InvalidIataCode) => {}
+        other => panic!("{:?}", other),
+    }
+}